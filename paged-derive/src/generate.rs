@@ -1,4 +1,4 @@
-use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
 use quote::{format_ident, quote, ToTokens};
 use syn::{punctuated::Punctuated, spanned::Spanned, Token};
 
@@ -35,6 +35,27 @@ fn extend_generics(
 	result
 }
 
+/// Returns an identifier for the context type parameter that does not
+/// collide with any of the given generic parameters.
+fn fresh_context_ident(generics: &syn::Generics) -> Ident {
+	let is_used = |ident: &Ident| {
+		generics.params.iter().any(|g| match g {
+			syn::GenericParam::Type(p) => p.ident == *ident,
+			syn::GenericParam::Const(p) => p.ident == *ident,
+			syn::GenericParam::Lifetime(_) => false,
+		})
+	};
+
+	let mut ident = format_ident!("_C");
+	let mut i = 0;
+	while is_used(&ident) {
+		ident = format_ident!("_C{i}");
+		i += 1
+	}
+
+	ident
+}
+
 pub enum FieldIdentOrIndex<'a> {
 	Ident(&'a TokenStream, &'a Ident),
 	Index(&'a TokenStream, syn::Index),
@@ -101,7 +122,17 @@ impl<'a> ToTokens for DecodeFieldsFromHeap<'a> {
 		let args = self.0.iter().map(|f| {
 			let ident = FieldConstructor::new(f);
 			let ty = &f.ty;
-			quote!(#ident <#ty as ::paged::DecodeFromHeap<#context_ident>>::decode_from_heap(input, context, heap)?)
+			let field_options = FieldOptions::of(f);
+			if field_options.skip {
+				let value = field_options.default_value();
+				quote!(#ident #value)
+			} else if let Some(codec) = &field_options.with {
+				quote!(#ident #codec::decode(input, context)?)
+			} else if field_uses_heap(self.0, f) {
+				quote!(#ident <#ty as ::paged::DecodeFromHeap<#context_ident>>::decode_from_heap(input, context, heap)?)
+			} else {
+				quote!(#ident <#ty as ::paged::Decode<#context_ident>>::decode(input, context)?)
+			}
 		});
 
 		match self.0 {
@@ -124,7 +155,15 @@ impl<'a> ToTokens for DecodeFields<'a> {
 		let args = self.0.iter().map(|f| {
 			let ident = FieldConstructor::new(f);
 			let ty = &f.ty;
-			quote!(#ident <#ty as ::paged::Decode<#context_ident>>::decode(input, context)?)
+			let field_options = FieldOptions::of(f);
+			if field_options.skip {
+				let value = field_options.default_value();
+				quote!(#ident #value)
+			} else if let Some(codec) = &field_options.with {
+				quote!(#ident #codec::decode(input, context)?)
+			} else {
+				quote!(#ident <#ty as ::paged::Decode<#context_ident>>::decode(input, context)?)
+			}
 		});
 
 		match self.0 {
@@ -140,7 +179,15 @@ impl<'a> ToTokens for DecodeFields<'a> {
 }
 
 pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
+	let is_non_exhaustive = input
+		.attrs
+		.iter()
+		.any(|attr| attr.path().is_ident("non_exhaustive"));
 	let mut options = parse_attributes(input.attrs)?;
+	check_field_attributes(&input.data)?;
+	options.requires_heap |= data_requires_heap(&input.data);
+	let validate = options.validate.take();
+	let validate = validate.as_ref();
 	let ident = input.ident;
 
 	let context_ident;
@@ -166,7 +213,7 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 			}
 		}
 		None => {
-			context_ident = format_ident!("_C");
+			context_ident = fresh_context_ident(&input.generics);
 			Some(syn::TypeParam {
 				attrs: Vec::new(),
 				ident: context_ident.clone(),
@@ -187,7 +234,7 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 	let (encode_impl_generics, _, encode_where_clause) = encode_generics.split_for_impl();
 	let (decode_impl_generics, _, decode_where_clause) = decode_generics.split_for_impl();
 
-	let (_, type_generics, _) = input.generics.split_for_impl();
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
 
 	match input.data {
 		syn::Data::Struct(s) => {
@@ -196,6 +243,41 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 
 			let mut tokens = TokenStream::new();
 
+			if let Some(key) = &options.key {
+				let key_ty = key_type(&s.fields, key)?;
+
+				tokens.extend(quote! {
+					impl #impl_generics ::paged::Keyed for #ident #type_generics #where_clause {
+						type Key = #key_ty;
+
+						fn paged_key(&self) -> &#key_ty {
+							&self.#key
+						}
+					}
+				});
+			}
+
+			if options.transparent {
+				if options.columnar {
+					return Err(syn::Error::new_spanned(
+						&ident,
+						"transparent structs cannot be columnar",
+					)
+					.into());
+				}
+
+				tokens.extend(transparent(
+					&ident,
+					&input.generics,
+					&s.fields,
+					options.is_unsized,
+					validate,
+					&context_ident,
+					[&encode_sized_generics, &encode_generics, &decode_generics],
+				)?);
+				return Ok(tokens);
+			}
+
 			if !options.is_unsized {
 				tokens.extend(quote! {
 					impl #encode_sized_impl_generics ::paged::EncodeSized for #ident #type_generics #encode_sized_where_clause {
@@ -211,9 +293,16 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 				);
 				let decode_constructor_from_heap = DecodeFieldsFromHeap(&s.fields, &context_ident);
 
+				let decode_from_heap_body = validated(
+					validate,
+					quote! {
+						Ok(Self #decode_constructor_from_heap)
+					},
+				);
+
 				tokens.extend(quote! {
 					impl #encode_impl_generics ::paged::EncodeOnHeap<#context_ident> for #ident #type_generics #encode_where_clause {
-						fn encode_on_heap(&self, context: &#context_ident, heap: &mut ::paged::Heap, output: &mut impl ::std::io::Write) -> ::std::io::Result<u32> {
+						fn encode_on_heap<_W: ::paged::utils::Output>(&self, context: &#context_ident, heap: &mut ::paged::Heap<_W::ByteOrder>, output: &mut _W) -> ::std::io::Result<u32> {
 							let mut len = 0;
 							#encode_fields_to_heap
 							Ok(len)
@@ -221,12 +310,72 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 					}
 
 					impl #decode_impl_generics ::paged::DecodeFromHeap<#context_ident> for #ident #type_generics #decode_where_clause {
-						fn decode_from_heap<_R: ::std::io::Seek + ::std::io::Read>(
-							input: &mut ::paged::reader::Cursor<_R>,
+						fn decode_from_heap<_R: ::std::io::Seek + ::std::io::Read, _O: ::paged::utils::ByteOrder>(
+							input: &mut ::paged::reader::Cursor<_R, _O>,
 							context: &mut #context_ident,
 							heap: ::paged::HeapSection,
 						) -> ::std::io::Result<Self> {
-							Ok(Self #decode_constructor_from_heap)
+							#decode_from_heap_body
+						}
+					}
+				});
+			}
+
+			if options.columnar {
+				let column_fields: Vec<_> = s
+					.fields
+					.iter()
+					.enumerate()
+					.filter(|(_, f)| !FieldOptions::of(f).skip)
+					.collect();
+
+				if let Some((_, f)) = column_fields
+					.iter()
+					.find(|(_, f)| FieldOptions::of(f).with.is_some())
+				{
+					return Err(syn::Error::new_spanned(
+						f,
+						"fields with a codec cannot be stored in columns",
+					)
+					.into());
+				}
+				let column_count = column_fields.len();
+				let column_types = column_fields.iter().map(|(_, f)| &f.ty);
+				let write_columns = column_fields.iter().enumerate().map(|(c, (_, f))| {
+					let ty = &f.ty;
+					quote!(::paged::columnar::write_column::<_W, #ty, _O>(encoder, heap, &columns[#c], entry_count)?)
+				});
+				let encode_columns = column_fields.iter().enumerate().map(|(c, (i, f))| {
+					let ty = &f.ty;
+					let field = FieldIdentOrIndex::new(&field_prefix, f, *i);
+					quote!(<#ty as ::paged::EncodeOnHeap<#context_ident>>::encode_on_heap(#field, context, heap, &mut ::paged::utils::Ordered::<_, _O>::new(&mut columns[#c]))?;)
+				});
+
+				tokens.extend(quote! {
+					impl #encode_sized_impl_generics ::paged::Columns for #ident #type_generics #encode_sized_where_clause {
+						const COLUMN_COUNT: usize = #column_count;
+
+						type Sections = (#(::paged::Section<#column_types>,)*);
+
+						fn write_columns<_W: ::std::io::Write + ::std::io::Seek, _O: ::paged::utils::ByteOrder>(
+							encoder: &mut ::paged::Encoder<_W, _O>,
+							heap: &mut ::paged::Heap<_O>,
+							columns: &[Vec<u8>],
+							entry_count: u32,
+						) -> ::std::io::Result<Self::Sections> {
+							Ok((#(#write_columns,)*))
+						}
+					}
+
+					impl #encode_impl_generics ::paged::EncodeColumns<#context_ident> for #ident #type_generics #encode_where_clause {
+						fn encode_columns<_O: ::paged::utils::ByteOrder>(
+							&self,
+							context: &#context_ident,
+							heap: &mut ::paged::Heap<_O>,
+							columns: &mut [Vec<u8>],
+						) -> ::std::io::Result<()> {
+							#(#encode_columns)*
+							Ok(())
 						}
 					}
 				});
@@ -241,9 +390,16 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 				);
 				let decode_constructor = DecodeFields(&s.fields, &context_ident);
 
+				let decode_body = validated(
+					validate,
+					quote! {
+						Ok(Self #decode_constructor)
+					},
+				);
+
 				tokens.extend(quote! {
 					impl #encode_impl_generics ::paged::Encode<#context_ident> for #ident #type_generics #encode_where_clause {
-						fn encode(&self, context: &#context_ident, output: &mut impl ::std::io::Write) -> ::std::io::Result<u32> {
+						fn encode(&self, context: &#context_ident, output: &mut impl ::paged::utils::Output) -> ::std::io::Result<u32> {
 							let mut len = 0;
 							#encode_fields
 							Ok(len)
@@ -251,11 +407,11 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 					}
 
 					impl #decode_impl_generics ::paged::Decode<#context_ident> for #ident #type_generics #decode_where_clause {
-						fn decode<_R: ::std::io::Read>(
+						fn decode<_R: ::paged::utils::Input>(
 							input: &mut _R,
 							context: &mut #context_ident
 						) -> ::std::io::Result<Self> {
-							Ok(Self #decode_constructor)
+							#decode_body
 						}
 					}
 				})
@@ -264,6 +420,161 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 			Ok(tokens)
 		}
 		syn::Data::Enum(e) => {
+			if let Some(key) = options.key {
+				return Err(syn::Error::new_spanned(key, "enums cannot have a key").into());
+			}
+
+			if options.transparent {
+				return Err(syn::Error::new_spanned(&ident, "enums cannot be transparent").into());
+			}
+
+			let mut fallback = None;
+			let mut discriminants = Vec::with_capacity(e.variants.len());
+			let mut implicit_discriminant = 0u64;
+			for v in &e.variants {
+				let variant_options = parse_variant_attributes(&v.attrs)?;
+
+				// Like Rust, variants without an explicit discriminant follow
+				// the previous one.
+				let explicit_discriminant = match &v.discriminant {
+					Some((_, expr)) => Some(parse_discriminant(expr)?),
+					None => None,
+				};
+				let rust_discriminant = explicit_discriminant.unwrap_or(implicit_discriminant);
+				implicit_discriminant = rust_discriminant.wrapping_add(1);
+
+				let discriminant = match (variant_options.tag, explicit_discriminant) {
+					(Some(_), Some(_)) => {
+						return Err(syn::Error::new_spanned(
+							v,
+							"a variant cannot have both a tag and an explicit discriminant",
+						)
+						.into())
+					}
+					(Some(tag), None) => tag,
+					(None, _) => rust_discriminant,
+				};
+
+				if discriminant > options.discriminant_repr.max() {
+					return Err(syn::Error::new_spanned(
+						v,
+						format!(
+							"variant discriminant does not fit in `{}`",
+							options.discriminant_repr.name()
+						),
+					)
+					.into());
+				}
+
+				if discriminants.contains(&discriminant) {
+					return Err(syn::Error::new_spanned(v, "duplicate variant tag").into());
+				}
+
+				discriminants.push(discriminant);
+
+				if variant_options.is_unknown {
+					if fallback.is_some() {
+						return Err(
+							syn::Error::new_spanned(v, "duplicate `unknown` variant").into()
+						);
+					}
+
+					if !matches!(v.fields, syn::Fields::Unit) {
+						return Err(syn::Error::new_spanned(
+							v,
+							"the `unknown` variant must be a unit variant",
+						)
+						.into());
+					}
+
+					fallback = Some(&v.ident)
+				}
+			}
+
+			if is_non_exhaustive && fallback.is_none() {
+				return Err(syn::Error::new_spanned(
+					&ident,
+					"non-exhaustive enums require a `#[paged(unknown)]` fallback variant",
+				)
+				.into());
+			}
+
+			if options.discriminant_repr == DiscriminantRepr::Varint {
+				let encode_cases = e.variants.iter().enumerate().map(|(i, v)| {
+					let variant_ident = &v.ident;
+					let inputs = VariantInputs(&v.fields);
+					let encode_variant =
+						encode_fields_to_heap(&v.fields, &context_ident, VariantInput, false);
+					let discriminant = discriminants[i];
+					quote!(Self::#variant_ident #inputs => {
+						::paged::utils::encode_varint(#discriminant, output)?;
+						#encode_variant
+					})
+				});
+
+				let decode_cases = e.variants.iter().enumerate().map(|(i, v)| {
+					let variant_ident = &v.ident;
+					let discriminant = discriminants[i];
+					let decode_variant = DecodeFieldsFromHeap(&v.fields, &context_ident);
+					quote!(#discriminant => Ok(Self::#variant_ident #decode_variant))
+				});
+
+				let unknown_case = match fallback {
+					Some(v) => quote!(_ => Ok(Self::#v)),
+					None => quote!(_ => Err(::std::io::ErrorKind::InvalidData.into())),
+				};
+
+				let decode_from_heap_body = validated(
+					validate,
+					quote! {
+						let entry = <::paged::heap::Entry as ::paged::Decode<#context_ident>>::decode(input, context)?;
+						input.report_heap_entry(entry);
+						input.at_heap_offset(heap, entry.offset, |input| {
+							let discriminant = ::paged::utils::decode_varint(input)?;
+							match discriminant {
+								#(#decode_cases,)*
+								#unknown_case
+							}
+						})
+					},
+				);
+
+				return Ok(quote! {
+					impl #encode_sized_impl_generics ::paged::EncodeSized for #ident #type_generics #encode_sized_where_clause {
+						const ENCODED_SIZE: u32 = <::paged::heap::Entry as ::paged::EncodeSized>::ENCODED_SIZE;
+					}
+
+					impl #encode_impl_generics ::paged::EncodeOnHeap<#context_ident> for #ident #type_generics #encode_where_clause {
+						fn encode_on_heap<_W: ::paged::utils::Output>(&self, context: &#context_ident, heap: &mut ::paged::Heap<_W::ByteOrder>, output: &mut _W) -> ::std::io::Result<u32> {
+							let mut payload = ::paged::utils::Ordered::like(output, Vec::new());
+
+							{
+								let output = &mut payload;
+								match self {
+									#(#encode_cases),*
+								}
+							}
+
+							let payload = payload.into_inner();
+							let entry = heap.insert(context, payload.as_slice())?.sized(payload.len() as u32);
+							<::paged::heap::Entry as ::paged::Encode<#context_ident>>::encode(&entry, context, output)
+						}
+					}
+
+					impl #decode_impl_generics ::paged::DecodeFromHeap<#context_ident> for #ident #type_generics #decode_where_clause {
+						fn decode_from_heap<_R: ::std::io::Seek + ::std::io::Read, _O: ::paged::utils::ByteOrder>(
+							input: &mut ::paged::reader::Cursor<_R, _O>,
+							context: &mut #context_ident,
+							heap: ::paged::HeapSection,
+						) -> ::std::io::Result<Self> {
+							#decode_from_heap_body
+						}
+					}
+				});
+			}
+
+			let discriminant_ty = options.discriminant_repr.ty();
+			let discriminant_size = options.discriminant_repr.size();
 			let mut encoded_size = quote!(0u32);
 
 			for v in &e.variants {
@@ -273,7 +584,7 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 
 			let mut tokens = quote! {
 				impl #encode_sized_impl_generics ::paged::EncodeSized for #ident #type_generics #encode_sized_where_clause {
-					const ENCODED_SIZE: u32 = 1 + #encoded_size;
+					const ENCODED_SIZE: u32 = #discriminant_size + #encoded_size;
 				}
 			};
 
@@ -282,20 +593,24 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 				let inputs = VariantInputs(&v.fields);
 				let encode_variant =
 					encode_fields_to_heap(&v.fields, &context_ident, VariantInput, false);
-				let discriminant = i as u8;
+				let discriminant = Literal::u64_unsuffixed(discriminants[i]);
+				let variant_size = fields_size(&v.fields);
+				let padding =
+					quote!(<Self as ::paged::EncodeSized>::ENCODED_SIZE - #discriminant_size - (#variant_size));
 				quote!(Self::#variant_ident #inputs => {
-					<u8 as ::paged::Encode<#context_ident>>::encode(&#discriminant, context, output)?;
+					<#discriminant_ty as ::paged::Encode<#context_ident>>::encode(&#discriminant, context, output)?;
 					#encode_variant
+					::paged::utils::write_padding(output, #padding)?;
 				})
 			});
 
 			let decode_from_heap_cases = e.variants.iter().enumerate().map(|(i, v)| {
 				let variant_ident = &v.ident;
-				let discriminant = i as u8;
+				let discriminant = Literal::u64_unsuffixed(discriminants[i]);
 				let decode_variant = DecodeFieldsFromHeap(&v.fields, &context_ident);
 				let variant_size = fields_size(&v.fields);
 				let padding =
-					quote!(<Self as ::paged::EncodeSized>::ENCODED_SIZE - 1 - (#variant_size));
+					quote!(<Self as ::paged::EncodeSized>::ENCODED_SIZE - #discriminant_size - (#variant_size));
 				quote!(#discriminant => {
 					let result = Self::#variant_ident #decode_variant ;
 					input.pad(#padding)?;
@@ -303,9 +618,28 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 				})
 			});
 
+			let unknown_case = match fallback {
+				Some(v) => quote!(_ => {
+					input.pad(<Self as ::paged::EncodeSized>::ENCODED_SIZE - #discriminant_size)?;
+					Ok(Self::#v)
+				}),
+				None => quote!(_ => Err(::std::io::ErrorKind::InvalidData.into())),
+			};
+
+			let decode_from_heap_body = validated(
+				validate,
+				quote! {
+					let discriminant = <#discriminant_ty as ::paged::Decode<#context_ident>>::decode(input, context)?;
+					match discriminant {
+						#(#decode_from_heap_cases,)*
+						#unknown_case
+					}
+				},
+			);
+
 			tokens.extend(quote! {
 				impl #encode_impl_generics ::paged::EncodeOnHeap<#context_ident> for #ident #type_generics #encode_where_clause {
-					fn encode_on_heap(&self, context: &#context_ident, heap: &mut ::paged::Heap, output: &mut impl ::std::io::Write) -> ::std::io::Result<u32> {
+					fn encode_on_heap<_W: ::paged::utils::Output>(&self, context: &#context_ident, heap: &mut ::paged::Heap<_W::ByteOrder>, output: &mut _W) -> ::std::io::Result<u32> {
 						match self {
 							#(#encode_cases),*
 						}
@@ -315,16 +649,12 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 				}
 
 				impl #decode_impl_generics ::paged::DecodeFromHeap<#context_ident> for #ident #type_generics #decode_where_clause {
-					fn decode_from_heap<_R: ::std::io::Seek + ::std::io::Read>(
-						input: &mut ::paged::reader::Cursor<_R>,
+					fn decode_from_heap<_R: ::std::io::Seek + ::std::io::Read, _O: ::paged::utils::ByteOrder>(
+						input: &mut ::paged::reader::Cursor<_R, _O>,
 						context: &mut #context_ident,
 						heap: ::paged::HeapSection,
 					) -> ::std::io::Result<Self> {
-						let discriminant = <u8 as ::paged::Decode<#context_ident>>::decode(input, context)?;
-						match discriminant {
-							#(#decode_from_heap_cases,)*
-							_ => Err(::std::io::ErrorKind::InvalidData.into())
-						}
+						#decode_from_heap_body
 					}
 				}
 			});
@@ -335,20 +665,24 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 					let inputs = VariantInputs(&v.fields);
 					let encode_variant =
 						encode_fields(&v.fields, &context_ident, VariantInput, false);
-					let discriminant = i as u8;
+					let discriminant = Literal::u64_unsuffixed(discriminants[i]);
+					let variant_size = fields_size(&v.fields);
+					let padding =
+						quote!(<Self as ::paged::EncodeSized>::ENCODED_SIZE - #discriminant_size - (#variant_size));
 					quote!(Self::#variant_ident #inputs => {
-						<u8 as ::paged::Encode<#context_ident>>::encode(&#discriminant, context, output)?;
+						<#discriminant_ty as ::paged::Encode<#context_ident>>::encode(&#discriminant, context, output)?;
 						#encode_variant
+						::paged::utils::write_padding(output, #padding)?;
 					})
 				});
 
 				let decode_cases = e.variants.iter().enumerate().map(|(i, v)| {
 					let variant_ident = &v.ident;
-					let discriminant = i as u8;
+					let discriminant = Literal::u64_unsuffixed(discriminants[i]);
 					let decode_variant = DecodeFields(&v.fields, &context_ident);
 					let variant_size = fields_size(&v.fields);
 					let padding =
-						quote!(<Self as ::paged::EncodeSized>::ENCODED_SIZE - (#variant_size));
+						quote!(<Self as ::paged::EncodeSized>::ENCODED_SIZE - #discriminant_size - (#variant_size));
 					quote!(#discriminant => {
 						let result = Self::#variant_ident #decode_variant ;
 						let mut padding = [0; (#padding) as usize];
@@ -357,9 +691,29 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 					})
 				});
 
+				let unknown_case = match fallback {
+					Some(v) => quote!(_ => {
+						let mut padding = [0; (<Self as ::paged::EncodeSized>::ENCODED_SIZE - #discriminant_size) as usize];
+						input.read_exact(&mut padding)?;
+						Ok(Self::#v)
+					}),
+					None => quote!(_ => Err(::std::io::ErrorKind::InvalidData.into())),
+				};
+
+				let decode_body = validated(
+					validate,
+					quote! {
+						let discriminant = <#discriminant_ty as ::paged::Decode<#context_ident>>::decode(input, context)?;
+						match discriminant {
+							#(#decode_cases,)*
+							#unknown_case
+						}
+					},
+				);
+
 				tokens.extend(quote! {
 					impl #encode_impl_generics ::paged::Encode<#context_ident> for #ident #type_generics #encode_where_clause {
-						fn encode(&self, context: &#context_ident, output: &mut impl ::std::io::Write) -> ::std::io::Result<u32> {
+						fn encode(&self, context: &#context_ident, output: &mut impl ::paged::utils::Output) -> ::std::io::Result<u32> {
 							match self {
 								#(#encode_cases),*
 							}
@@ -368,15 +722,11 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 					}
 
 					impl #decode_impl_generics ::paged::Decode<#context_ident> for #ident #type_generics #decode_where_clause {
-						fn decode<_R: ::std::io::Read>(
+						fn decode<_R: ::paged::utils::Input>(
 							input: &mut _R,
 							context: &mut #context_ident
 						) -> ::std::io::Result<Self> {
-							let discriminant = <u8 as ::paged::Decode<#context_ident>>::decode(input, context)?;
-							match discriminant {
-								#(#decode_cases,)*
-								_ => Err(::std::io::ErrorKind::InvalidData.into())
-							}
+							#decode_body
 						}
 					}
 				})
@@ -384,17 +734,224 @@ pub fn paged(input: syn::DeriveInput) -> Result<TokenStream, Error> {
 
 			Ok(tokens)
 		}
-		_ => todo!(),
+		syn::Data::Union(u) => {
+			Err(syn::Error::new_spanned(u.union_token, "unions are not supported").into())
+		}
+	}
+}
+
+/// Generates the impls of a `#[paged(transparent)]` struct, forwarding to
+/// its single field.
+fn transparent(
+	ident: &Ident,
+	generics: &syn::Generics,
+	fields: &syn::Fields,
+	is_unsized: bool,
+	validate: Option<&syn::Path>,
+	context_ident: &Ident,
+	[encode_sized_generics, encode_generics, decode_generics]: [&syn::Generics; 3],
+) -> Result<TokenStream, Error> {
+	let field = match fields.iter().collect::<Vec<_>>().as_slice() {
+		[field] => *field,
+		_ => {
+			return Err(syn::Error::new_spanned(
+				fields,
+				"transparent structs must have exactly one field",
+			)
+			.into())
+		}
+	};
+
+	if FieldOptions::of(field).with.is_some() {
+		return Err(syn::Error::new_spanned(
+			field,
+			"the field of a transparent struct cannot have a codec",
+		)
+		.into());
+	}
+
+	if FieldOptions::of(field).skip {
+		return Err(syn::Error::new_spanned(
+			field,
+			"the field of a transparent struct cannot be skipped",
+		)
+		.into());
+	}
+
+	let bounded = |generics: &syn::Generics, bound: TokenStream| -> Result<syn::Generics, Error> {
+		let mut result = generics.clone();
+		result
+			.make_where_clause()
+			.predicates
+			.push(syn::parse2(bound)?);
+		Ok(result)
+	};
+
+	let ty = &field.ty;
+	let prefix = quote!(&self.);
+	let value = FieldIdentOrIndex::new(&prefix, field, 0);
+	let constructor = FieldConstructor::new(field);
+	let construct = |inner: TokenStream| match fields {
+		syn::Fields::Named(_) => quote!(Self { #constructor #inner }),
+		_ => quote!(Self(#inner)),
+	};
+	let (_, type_generics, _) = generics.split_for_impl();
+
+	let mut tokens = TokenStream::new();
+
+	if !is_unsized {
+		let encode_sized_generics =
+			bounded(encode_sized_generics, quote!(#ty: ::paged::EncodeSized))?;
+		let (impl_generics, _, where_clause) = encode_sized_generics.split_for_impl();
+		let encode_on_heap_generics = bounded(
+			encode_generics,
+			quote!(#ty: ::paged::EncodeOnHeap<#context_ident>),
+		)?;
+		let (encode_impl_generics, _, encode_where_clause) =
+			encode_on_heap_generics.split_for_impl();
+		let decode_from_heap_generics = bounded(
+			decode_generics,
+			quote!(#ty: ::paged::DecodeFromHeap<#context_ident>),
+		)?;
+		let (decode_impl_generics, _, decode_where_clause) =
+			decode_from_heap_generics.split_for_impl();
+		let decode_from_heap = construct(
+			quote!(<#ty as ::paged::DecodeFromHeap<#context_ident>>::decode_from_heap(input, context, heap)?),
+		);
+
+		let decode_from_heap_body = validated(
+			validate,
+			quote! {
+				Ok(#decode_from_heap)
+			},
+		);
+
+		tokens.extend(quote! {
+			impl #impl_generics ::paged::EncodeSized for #ident #type_generics #where_clause {
+				const ENCODED_SIZE: u32 = <#ty as ::paged::EncodeSized>::ENCODED_SIZE;
+			}
+
+			impl #encode_impl_generics ::paged::EncodeOnHeap<#context_ident> for #ident #type_generics #encode_where_clause {
+				fn encode_on_heap<_W: ::paged::utils::Output>(&self, context: &#context_ident, heap: &mut ::paged::Heap<_W::ByteOrder>, output: &mut _W) -> ::std::io::Result<u32> {
+					<#ty as ::paged::EncodeOnHeap<#context_ident>>::encode_on_heap(#value, context, heap, output)
+				}
+			}
+
+			impl #decode_impl_generics ::paged::DecodeFromHeap<#context_ident> for #ident #type_generics #decode_where_clause {
+				fn decode_from_heap<_R: ::std::io::Seek + ::std::io::Read, _O: ::paged::utils::ByteOrder>(
+					input: &mut ::paged::reader::Cursor<_R, _O>,
+					context: &mut #context_ident,
+					heap: ::paged::HeapSection,
+				) -> ::std::io::Result<Self> {
+					#decode_from_heap_body
+				}
+			}
+		});
+	}
+
+	let encode_generics = bounded(
+		encode_generics,
+		quote!(#ty: ::paged::Encode<#context_ident>),
+	)?;
+	let (encode_impl_generics, _, encode_where_clause) = encode_generics.split_for_impl();
+	let decode_generics = bounded(
+		decode_generics,
+		quote!(#ty: ::paged::Decode<#context_ident>),
+	)?;
+	let (decode_impl_generics, _, decode_where_clause) = decode_generics.split_for_impl();
+	let decode =
+		construct(quote!(<#ty as ::paged::Decode<#context_ident>>::decode(input, context)?));
+
+	let decode_body = validated(
+		validate,
+		quote! {
+			Ok(#decode)
+		},
+	);
+
+	tokens.extend(quote! {
+		impl #encode_impl_generics ::paged::Encode<#context_ident> for #ident #type_generics #encode_where_clause {
+			fn encode(&self, context: &#context_ident, output: &mut impl ::paged::utils::Output) -> ::std::io::Result<u32> {
+				<#ty as ::paged::Encode<#context_ident>>::encode(#value, context, output)
+			}
+		}
+
+		impl #decode_impl_generics ::paged::Decode<#context_ident> for #ident #type_generics #decode_where_clause {
+			fn decode<_R: ::paged::utils::Input>(
+				input: &mut _R,
+				context: &mut #context_ident
+			) -> ::std::io::Result<Self> {
+				#decode_body
+			}
+		}
+	});
+
+	Ok(tokens)
+}
+
+/// Wraps the body of a decoding function so that the decoded value is
+/// passed to the validation function, if any.
+fn validated(validate: Option<&syn::Path>, body: TokenStream) -> TokenStream {
+	match validate {
+		Some(validate) => quote! {
+			let result: ::std::io::Result<Self> = { #body };
+			let value = result?;
+			#validate(&value)?;
+			Ok(value)
+		},
+		None => body,
 	}
 }
 
+fn parse_discriminant(expr: &syn::Expr) -> Result<u64, Error> {
+	match expr {
+		syn::Expr::Lit(syn::ExprLit {
+			lit: syn::Lit::Int(i),
+			..
+		}) => Ok(i.base10_parse()?),
+		syn::Expr::Lit(syn::ExprLit {
+			lit: syn::Lit::Byte(b),
+			..
+		}) => Ok(b.value() as u64),
+		_ => Err(syn::Error::new_spanned(expr, "expected an integer literal discriminant").into()),
+	}
+}
+
+fn key_type<'a>(fields: &'a syn::Fields, key: &syn::Member) -> Result<&'a syn::Type, Error> {
+	fields
+		.iter()
+		.enumerate()
+		.find(|(i, f)| match key {
+			syn::Member::Named(ident) => f.ident.as_ref() == Some(ident),
+			syn::Member::Unnamed(index) => f.ident.is_none() && index.index as usize == *i,
+		})
+		.map(|(_, f)| {
+			if FieldOptions::of(f).skip {
+				Err(syn::Error::new_spanned(key, "the key field cannot be skipped").into())
+			} else {
+				Ok(&f.ty)
+			}
+		})
+		.unwrap_or_else(|| Err(syn::Error::new_spanned(key, "unknown key field").into()))
+}
+
 fn fields_size(fields: &syn::Fields) -> TokenStream {
 	let mut size = quote!(0u32);
 
 	for f in fields {
+		let field_options = FieldOptions::of(f);
+		if field_options.skip {
+			continue;
+		}
+
 		let ty = &f.ty;
-		size = quote! {
-			#size + <#ty as ::paged::EncodeSized>::ENCODED_SIZE
+		size = match &field_options.with {
+			Some(codec) => quote! {
+				#size + #codec::encoded_size()
+			},
+			None => quote! {
+				#size + <#ty as ::paged::EncodeSized>::ENCODED_SIZE
+			},
 		}
 	}
 
@@ -410,14 +967,22 @@ fn encode_fields<'a, T: ToTokens>(
 	let mut result = TokenStream::new();
 
 	for (i, f) in fields.iter().enumerate() {
+		let field_options = FieldOptions::of(f);
+		if field_options.skip {
+			continue;
+		}
+
 		let accessor = accessor(f, i);
 		let ty = &f.ty;
 		if capture_len {
 			result.extend(quote!(len += ));
 		}
-		result.extend(
-			quote!(<#ty as ::paged::Encode<#context_ident>>::encode(#accessor, context, output)?;),
-		)
+		match &field_options.with {
+			Some(codec) => result.extend(quote!(#codec::encode(#accessor, context, output)?;)),
+			None => result.extend(
+				quote!(<#ty as ::paged::Encode<#context_ident>>::encode(#accessor, context, output)?;),
+			),
+		}
 	}
 
 	result
@@ -432,12 +997,23 @@ fn encode_fields_to_heap<'a, T: ToTokens>(
 	let mut result = TokenStream::new();
 
 	for (i, f) in fields.iter().enumerate() {
+		let field_options = FieldOptions::of(f);
+		if field_options.skip {
+			continue;
+		}
+
 		let accessor = accessor(f, i);
 		let ty = &f.ty;
 		if capture_len {
 			result.extend(quote!(len += ));
 		}
-		result.extend(quote!(<#ty as ::paged::EncodeOnHeap<#context_ident>>::encode_on_heap(#accessor, context, heap, output)?;))
+		match &field_options.with {
+			Some(codec) => result.extend(quote!(#codec::encode(#accessor, context, output)?;)),
+			None if field_uses_heap(fields, f) => result.extend(quote!(<#ty as ::paged::EncodeOnHeap<#context_ident>>::encode_on_heap(#accessor, context, heap, output)?;)),
+			None => result.extend(
+				quote!(<#ty as ::paged::Encode<#context_ident>>::encode(#accessor, context, output)?;),
+			),
+		}
 	}
 
 	result
@@ -450,11 +1026,24 @@ impl<'a> ToTokens for VariantInputs<'a> {
 		match self.0 {
 			syn::Fields::Unit => (),
 			syn::Fields::Named(fields) => {
-				let fields = fields.named.iter().map(|f| &f.ident);
+				let fields = fields.named.iter().map(|f| {
+					let ident = &f.ident;
+					if FieldOptions::of(f).skip {
+						quote!(#ident: _)
+					} else {
+						quote!(#ident)
+					}
+				});
 				tokens.extend(quote!({ #(#fields),* }))
 			}
 			syn::Fields::Unnamed(fields) => {
-				let fields = (0..fields.unnamed.len()).map(|i| format_ident!("_arg{i}"));
+				let fields = fields.unnamed.iter().enumerate().map(|(i, f)| {
+					if FieldOptions::of(f).skip {
+						quote!(_)
+					} else {
+						format_ident!("_arg{i}").into_token_stream()
+					}
+				});
 				tokens.extend(quote!(( #(#fields),* )))
 			}
 		}
@@ -480,6 +1069,458 @@ pub struct Options {
 	encode_sized_bounds: Vec<syn::WherePredicate>,
 	decode_bounds: Vec<syn::WherePredicate>,
 	context: Option<syn::TypeParam>,
+	key: Option<syn::Member>,
+	discriminant_repr: DiscriminantRepr,
+	columnar: bool,
+	transparent: bool,
+
+	/// Function checking decoded values.
+	validate: Option<syn::Path>,
+}
+
+/// Encoding of enum discriminants, set with `#[paged(repr = ..)]`.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum DiscriminantRepr {
+	#[default]
+	U8,
+	U16,
+	U32,
+
+	/// LEB128 varint, stored on the heap with the variant.
+	Varint,
+}
+
+impl DiscriminantRepr {
+	fn name(self) -> &'static str {
+		match self {
+			Self::U8 => "u8",
+			Self::U16 => "u16",
+			Self::U32 => "u32",
+			Self::Varint => "u64",
+		}
+	}
+
+	fn max(self) -> u64 {
+		match self {
+			Self::U8 => u8::MAX as u64,
+			Self::U16 => u16::MAX as u64,
+			Self::U32 => u32::MAX as u64,
+			Self::Varint => u64::MAX,
+		}
+	}
+
+	/// Type of fixed-width discriminants.
+	fn ty(self) -> TokenStream {
+		let ident = Ident::new(self.name(), Span::call_site());
+		quote!(#ident)
+	}
+
+	/// Size of fixed-width discriminants.
+	fn size(self) -> u32 {
+		match self {
+			Self::U8 => 1,
+			Self::U16 => 2,
+			Self::U32 => 4,
+			Self::Varint => unreachable!("varint discriminants have no fixed size"),
+		}
+	}
+}
+
+#[derive(Default)]
+pub struct FieldOptions {
+	skip: bool,
+	heap: bool,
+	default: Option<syn::Path>,
+
+	/// Module encoding the field in place of its type's impls.
+	///
+	/// See [`crate::derive_paged`] for the expected functions.
+	with: Option<syn::Path>,
+}
+
+impl FieldOptions {
+	/// Returns the options of the given field.
+	///
+	/// Field attributes are checked by [`check_field_attributes`] before any
+	/// code is generated, so parsing them again cannot fail.
+	fn of(f: &syn::Field) -> Self {
+		parse_field_attributes(&f.attrs).expect("field attributes already checked")
+	}
+
+	/// Returns the expression building the value of a skipped field.
+	fn default_value(&self) -> TokenStream {
+		match &self.default {
+			Some(path) => quote!(#path()),
+			None => quote!(::std::default::Default::default()),
+		}
+	}
+}
+
+#[derive(Default)]
+pub struct VariantOptions {
+	is_unknown: bool,
+	tag: Option<u64>,
+}
+
+fn parse_variant_attributes(attributes: &[syn::Attribute]) -> Result<VariantOptions, Error> {
+	let mut options = VariantOptions::default();
+
+	for attr in attributes {
+		if attr.path().is_ident("paged") {
+			match &attr.meta {
+				syn::Meta::List(list) => {
+					let mut tokens = list.tokens.clone().into_iter();
+					loop {
+						match tokens.next() {
+							Some(TokenTree::Ident(id)) => {
+								if id == "unknown" {
+									options.is_unknown = true
+								} else if id == "tag" {
+									match tokens.next() {
+										Some(TokenTree::Punct(p)) if p.as_char() == '=' => (),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(
+												syn::Error::new_spanned(&id, "missing tag").into()
+											)
+										}
+									}
+
+									match tokens.next() {
+										Some(TokenTree::Literal(lit)) => {
+											let tag = match syn::parse2(lit.into_token_stream())? {
+												syn::Lit::Byte(b) => b.value() as u64,
+												syn::Lit::Int(i) => i.base10_parse()?,
+												lit => {
+													return Err(syn::Error::new_spanned(
+														lit,
+														"expected a byte or integer literal",
+													)
+													.into())
+												}
+											};
+
+											options.tag = Some(tag)
+										}
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(
+												syn::Error::new_spanned(&id, "missing tag").into()
+											)
+										}
+									}
+								} else {
+									return Err(syn::Error::new_spanned(
+										&id,
+										"unknown `paged` variant attribute",
+									)
+									.into());
+								}
+							}
+							Some(t) => {
+								return Err(syn::Error::new_spanned(t, "unexpected token").into())
+							}
+							None => {
+								return Err(syn::Error::new_spanned(
+									attr,
+									"missing `paged` attribute name",
+								)
+								.into())
+							}
+						}
+
+						match tokens.next() {
+							Some(TokenTree::Punct(p)) if p.as_char() == ',' => (),
+							Some(t) => {
+								return Err(syn::Error::new_spanned(t, "unexpected token").into())
+							}
+							None => break,
+						}
+					}
+				}
+				meta => return Err(syn::Error::new_spanned(meta, "invalid attribute").into()),
+			}
+		}
+	}
+
+	Ok(options)
+}
+
+/// Standard types implementing `EncodeOnHeap` without `Encode`.
+///
+/// Types are matched by their full path, with `alloc` and `core` written
+/// `std`, so that user types with the same name are not mistaken for them.
+/// Only the types of the prelude are also matched by name.
+const HEAP_TYPES: &[&str] = &[
+	"String",
+	"Vec",
+	"std::string::String",
+	"std::borrow::Cow",
+	"std::vec::Vec",
+	"std::collections::VecDeque",
+	"std::collections::HashMap",
+	"std::collections::HashSet",
+	"std::collections::BTreeMap",
+	"std::collections::BTreeSet",
+	"paged::utils::OptionVec",
+	"paged::utils::VarLen",
+	"paged::utils::Bounded",
+	"paged::utils::Compressed",
+	"paged::utils::PortablePath",
+];
+
+/// Standard types requiring a heap if one of their type parameters does.
+///
+/// Matched like [`HEAP_TYPES`].
+const HEAP_WRAPPER_TYPES: &[&str] = &[
+	"Option",
+	"Result",
+	"Box",
+	"std::option::Option",
+	"std::result::Result",
+	"std::boxed::Box",
+	"std::rc::Rc",
+	"std::sync::Arc",
+	"std::ops::Range",
+	"std::ops::RangeFrom",
+	"std::ops::RangeTo",
+	"paged::utils::NicheOption",
+	"paged::utils::Checked",
+	"paged::utils::Interned",
+];
+
+/// Returns the path of the given type, as matched against [`HEAP_TYPES`]
+/// and [`HEAP_WRAPPER_TYPES`].
+fn type_path_name(path: &syn::Path) -> String {
+	let mut name = String::new();
+
+	for (i, segment) in path.segments.iter().enumerate() {
+		if i > 0 {
+			name.push_str("::")
+		}
+
+		let ident = segment.ident.to_string();
+		if i == 0 && path.segments.len() > 1 && (ident == "alloc" || ident == "core") {
+			name.push_str("std")
+		} else {
+			name.push_str(&ident)
+		}
+	}
+
+	name
+}
+
+/// Checks if some field of the given type is known to require a heap.
+///
+/// Fields with a codec or skipped are ignored.
+fn data_requires_heap(data: &syn::Data) -> bool {
+	let fields: Vec<&syn::Field> = match data {
+		syn::Data::Struct(s) => s.fields.iter().collect(),
+		syn::Data::Enum(e) => e.variants.iter().flat_map(|v| &v.fields).collect(),
+		syn::Data::Union(_) => Vec::new(),
+	};
+
+	fields.into_iter().any(|f| {
+		let field_options = FieldOptions::of(f);
+		field_options.heap
+			|| (!field_options.skip && field_options.with.is_none() && type_requires_heap(&f.ty))
+	})
+}
+
+/// Checks if the given field is encoded with `EncodeOnHeap`.
+///
+/// By default every field is. Once a field is marked with `#[paged(heap)]`,
+/// only marked fields and fields of known heap types are, the others using
+/// `Encode`.
+fn field_uses_heap(fields: &syn::Fields, f: &syn::Field) -> bool {
+	if fields.iter().any(|f| FieldOptions::of(f).heap) {
+		FieldOptions::of(f).heap || type_requires_heap(&f.ty)
+	} else {
+		true
+	}
+}
+
+/// Checks if the given type is known to require a heap, meaning it only
+/// implements `EncodeOnHeap` and `DecodeFromHeap`.
+///
+/// Only standard types are recognized, by path (see [`HEAP_TYPES`]). Other
+/// types are assumed not to require a heap.
+fn type_requires_heap(ty: &syn::Type) -> bool {
+	match ty {
+		syn::Type::Path(p) if p.qself.is_none() => match p.path.segments.last() {
+			Some(segment) => {
+				let name = type_path_name(&p.path);
+				if name == "str" || HEAP_TYPES.contains(&name.as_str()) {
+					true
+				} else if HEAP_WRAPPER_TYPES.contains(&name.as_str()) {
+					match &segment.arguments {
+						syn::PathArguments::AngleBracketed(args) => args.args.iter().any(
+							|a| matches!(a, syn::GenericArgument::Type(t) if type_requires_heap(t)),
+						),
+						_ => false,
+					}
+				} else {
+					false
+				}
+			}
+			None => false,
+		},
+		syn::Type::Slice(_) => true,
+		syn::Type::Array(a) => type_requires_heap(&a.elem),
+		syn::Type::Tuple(t) => t.elems.iter().any(type_requires_heap),
+		syn::Type::Group(g) => type_requires_heap(&g.elem),
+		syn::Type::Paren(p) => type_requires_heap(&p.elem),
+		_ => false,
+	}
+}
+
+fn check_field_attributes(data: &syn::Data) -> Result<(), Error> {
+	let fields: Vec<&syn::Field> = match data {
+		syn::Data::Struct(s) => s.fields.iter().collect(),
+		syn::Data::Enum(e) => e.variants.iter().flat_map(|v| &v.fields).collect(),
+		syn::Data::Union(_) => Vec::new(),
+	};
+
+	for f in fields {
+		parse_field_attributes(&f.attrs)?;
+	}
+
+	Ok(())
+}
+
+fn parse_field_attributes(attributes: &[syn::Attribute]) -> Result<FieldOptions, Error> {
+	let mut options = FieldOptions::default();
+
+	for attr in attributes {
+		if attr.path().is_ident("paged") {
+			match &attr.meta {
+				syn::Meta::List(list) => {
+					let mut tokens = list.tokens.clone().into_iter().peekable();
+					loop {
+						match tokens.next() {
+							Some(TokenTree::Ident(id)) => {
+								if id == "skip" {
+									options.skip = true
+								} else if id == "heap" {
+									options.heap = true
+								} else if id == "default" {
+									match tokens.next() {
+										Some(TokenTree::Punct(p)) if p.as_char() == '=' => (),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing default",
+											)
+											.into())
+										}
+									}
+
+									options.default = Some(parse_path_value(&mut tokens)?)
+								} else if id == "with" {
+									match tokens.next() {
+										Some(TokenTree::Punct(p)) if p.as_char() == '=' => (),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing codec",
+											)
+											.into())
+										}
+									}
+
+									options.with = Some(parse_path_value(&mut tokens)?)
+								} else {
+									return Err(syn::Error::new_spanned(
+										&id,
+										"unknown `paged` field attribute",
+									)
+									.into());
+								}
+							}
+							Some(t) => {
+								return Err(syn::Error::new_spanned(t, "unexpected token").into())
+							}
+							None => {
+								return Err(syn::Error::new_spanned(
+									attr,
+									"missing `paged` attribute name",
+								)
+								.into())
+							}
+						}
+
+						match tokens.next() {
+							Some(TokenTree::Punct(p)) if p.as_char() == ',' => (),
+							Some(t) => {
+								return Err(syn::Error::new_spanned(t, "unexpected token").into())
+							}
+							None => break,
+						}
+					}
+				}
+				meta => return Err(syn::Error::new_spanned(meta, "invalid attribute").into()),
+			}
+		}
+	}
+
+	if let (Some(default), false) = (&options.default, options.skip) {
+		return Err(syn::Error::new_spanned(default, "`default` requires `skip`").into());
+	}
+
+	if let (Some(codec), true) = (&options.with, options.skip) {
+		return Err(syn::Error::new_spanned(codec, "skipped fields cannot have a codec").into());
+	}
+
+	if let (Some(codec), true) = (&options.with, options.heap) {
+		return Err(
+			syn::Error::new_spanned(codec, "fields with a codec cannot be on the heap").into(),
+		);
+	}
+
+	Ok(options)
+}
+
+/// Parses a path attribute value, either bare or as a string literal, up to
+/// the next comma.
+fn parse_path_value(
+	tokens: &mut std::iter::Peekable<impl Iterator<Item = TokenTree>>,
+) -> Result<syn::Path, Error> {
+	let mut path = TokenStream::new();
+	while let Some(t) = tokens.next_if(|t| !matches!(t, TokenTree::Punct(p) if p.as_char() == ','))
+	{
+		path.extend([t])
+	}
+
+	match syn::parse2::<syn::LitStr>(path.clone()) {
+		Ok(lit) => Ok(lit.parse()?),
+		Err(_) => Ok(syn::parse2(path)?),
+	}
 }
 
 pub struct BoundsAttribute {
@@ -499,9 +1540,9 @@ fn parse_attributes(attributes: Vec<syn::Attribute>) -> Result<Options, Error> {
 
 	for attr in attributes {
 		if attr.path().is_ident("paged") {
-			match attr.meta {
+			match &attr.meta {
 				syn::Meta::List(list) => {
-					let mut tokens = list.tokens.into_iter();
+					let mut tokens = list.tokens.clone().into_iter().peekable();
 					loop {
 						match tokens.next() {
 							Some(TokenTree::Ident(id)) => {
@@ -509,6 +1550,30 @@ fn parse_attributes(attributes: Vec<syn::Attribute>) -> Result<Options, Error> {
 									options.is_unsized = true
 								} else if id == "heap" {
 									options.requires_heap = true
+								} else if id == "columnar" {
+									options.columnar = true
+								} else if id == "transparent" {
+									options.transparent = true
+								} else if id == "validate" {
+									match tokens.next() {
+										Some(TokenTree::Punct(p)) if p.as_char() == '=' => (),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing validation function",
+											)
+											.into())
+										}
+									}
+
+									options.validate = Some(parse_path_value(&mut tokens)?)
 								} else if id == "bounds" {
 									match tokens.next() {
 										Some(TokenTree::Group(group)) => {
@@ -522,8 +1587,20 @@ fn parse_attributes(attributes: Vec<syn::Attribute>) -> Result<Options, Error> {
 												.extend(bounds.list.iter().cloned());
 											options.decode_bounds.extend(bounds.list);
 										}
-										Some(_) => panic!("unexpected token"),
-										None => panic!("missing bounds"),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing bounds",
+											)
+											.into())
+										}
 									}
 								} else if id == "decode_bounds" {
 									match tokens.next() {
@@ -532,8 +1609,20 @@ fn parse_attributes(attributes: Vec<syn::Attribute>) -> Result<Options, Error> {
 												syn::parse2(group.stream())?;
 											options.decode_bounds.extend(bounds.list);
 										}
-										Some(_) => panic!("unexpected token"),
-										None => panic!("missing bounds"),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing bounds",
+											)
+											.into())
+										}
 									}
 								} else if id == "encode_sized_bounds" {
 									match tokens.next() {
@@ -542,8 +1631,20 @@ fn parse_attributes(attributes: Vec<syn::Attribute>) -> Result<Options, Error> {
 												syn::parse2(group.stream())?;
 											options.encode_sized_bounds.extend(bounds.list);
 										}
-										Some(_) => panic!("unexpected token"),
-										None => panic!("missing bounds"),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing bounds",
+											)
+											.into())
+										}
 									}
 								} else if id == "encode_bounds" {
 									match tokens.next() {
@@ -552,33 +1653,155 @@ fn parse_attributes(attributes: Vec<syn::Attribute>) -> Result<Options, Error> {
 												syn::parse2(group.stream())?;
 											options.encode_bounds.extend(bounds.list);
 										}
-										Some(_) => panic!("unexpected token"),
-										None => panic!("missing bounds"),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing bounds",
+											)
+											.into())
+										}
+									}
+								} else if id == "key" {
+									match tokens.next() {
+										Some(TokenTree::Punct(p)) if p.as_char() == '=' => (),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(
+												syn::Error::new_spanned(&id, "missing key").into()
+											)
+										}
+									}
+
+									match tokens.next() {
+										Some(TokenTree::Ident(field)) => {
+											options.key = Some(syn::Member::Named(field))
+										}
+										Some(TokenTree::Literal(index)) => {
+											options.key = Some(syn::Member::Unnamed(syn::parse2(
+												index.into_token_stream(),
+											)?))
+										}
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(
+												syn::Error::new_spanned(&id, "missing key").into()
+											)
+										}
+									}
+								} else if id == "repr" {
+									match tokens.next() {
+										Some(TokenTree::Punct(p)) if p.as_char() == '=' => (),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing repr",
+											)
+											.into())
+										}
+									}
+
+									match tokens.next() {
+										Some(TokenTree::Ident(repr)) if repr == "varint" => {
+											options.discriminant_repr = DiscriminantRepr::Varint
+										}
+										Some(TokenTree::Ident(repr)) if repr == "u8" => {
+											options.discriminant_repr = DiscriminantRepr::U8
+										}
+										Some(TokenTree::Ident(repr)) if repr == "u16" => {
+											options.discriminant_repr = DiscriminantRepr::U16
+										}
+										Some(TokenTree::Ident(repr)) if repr == "u32" => {
+											options.discriminant_repr = DiscriminantRepr::U32
+										}
+										Some(t) => {
+											return Err(
+												syn::Error::new_spanned(t, "unknown repr").into()
+											)
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing repr",
+											)
+											.into())
+										}
 									}
 								} else if id == "context" {
 									match tokens.next() {
 										Some(TokenTree::Group(group)) => {
 											options.context = Some(syn::parse2(group.stream())?);
 										}
-										Some(_) => panic!("unexpected token"),
-										None => panic!("missing bounds"),
+										Some(t) => {
+											return Err(syn::Error::new_spanned(
+												t,
+												"unexpected token",
+											)
+											.into())
+										}
+										None => {
+											return Err(syn::Error::new_spanned(
+												&id,
+												"missing bounds",
+											)
+											.into())
+										}
 									}
 								} else {
-									panic!("unknown `paged` attribute")
+									return Err(syn::Error::new_spanned(
+										&id,
+										"unknown `paged` attribute",
+									)
+									.into());
 								}
 							}
-							Some(_) => panic!("unexpected token"),
-							None => panic!("missing `paged` attribute name"),
+							Some(t) => {
+								return Err(syn::Error::new_spanned(t, "unexpected token").into())
+							}
+							None => {
+								return Err(syn::Error::new_spanned(
+									attr,
+									"missing `paged` attribute name",
+								)
+								.into())
+							}
 						}
 
 						match tokens.next() {
 							Some(TokenTree::Punct(p)) if p.as_char() == ',' => (),
-							Some(_) => panic!("unexpected token"),
+							Some(t) => {
+								return Err(syn::Error::new_spanned(t, "unexpected token").into())
+							}
 							None => break,
 						}
 					}
 				}
-				_ => panic!("invalid attribute"),
+				meta => return Err(syn::Error::new_spanned(meta, "invalid attribute").into()),
 			}
 		}
 	}
@@ -4,6 +4,36 @@ use proc_macro_error::{abort, proc_macro_error};
 
 mod generate;
 
+/// Derives the encoding traits of the `paged` library.
+///
+/// # Heap fields
+///
+/// Types with a field stored on the heap only implement `EncodeOnHeap` and
+/// `DecodeFromHeap`. This is inferred for the standard heap types of `std`
+/// and `paged::utils`, possibly wrapped in `Option`, `Box`, tuples, etc.
+/// Apart from `String`, `Vec`, `Option`, `Result` and `Box`, these types
+/// must be written with their full path (e.g. `std::collections::HashMap`),
+/// so that user types of the same name are not mistaken for them. Other
+/// heap types require the `#[paged(heap)]` attribute, on the type or on the
+/// field.
+///
+/// # Custom field codecs
+///
+/// A field marked `#[paged(with = codec)]` (or `with = "codec"`) is encoded
+/// by the functions of the `codec` module instead of the impls of its type.
+/// For a field of type `T`, the module must provide:
+///
+/// ```ignore
+/// pub const fn encoded_size() -> u32;
+///
+/// pub fn encode<C>(value: &T, context: &C, output: &mut impl paged::utils::Output) -> std::io::Result<u32>;
+///
+/// pub fn decode<C, R: paged::utils::Input>(input: &mut R, context: &mut C) -> std::io::Result<T>;
+/// ```
+///
+/// `encode` must write exactly `encoded_size()` bytes and return this
+/// length. The codec is also used when the type is encoded on the heap, so
+/// it cannot store data there.
 #[proc_macro_derive(Paged, attributes(paged))]
 #[proc_macro_error]
 pub fn derive_paged(input: TokenStream) -> TokenStream {
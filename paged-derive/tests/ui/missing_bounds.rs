@@ -0,0 +1,9 @@
+use paged::Paged;
+
+#[derive(Paged)]
+#[paged(bounds)]
+pub struct Foo<T> {
+	bar: T,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use paged::Paged;
+
+#[derive(Paged)]
+#[paged(unsize)]
+pub struct Foo {
+	bar: String,
+}
+
+fn main() {}
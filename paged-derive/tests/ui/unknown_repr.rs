@@ -0,0 +1,10 @@
+use paged::Paged;
+
+#[derive(Paged)]
+#[paged(repr = u64)]
+pub enum Foo {
+	Bar,
+	Baz,
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+//! Compares loading pages of `u64` entries with `Reader::get_page_pod` and
+//! `Reader::get_page`.
+//!
+//! Run with `cargo bench -p paged --features bytemuck --bench pod`.
+use std::{io::Cursor, time::Instant};
+
+use paged::{reader::Cache, utils::NativeEndian, Encoder, Heap, Reader};
+
+const ENTRY_COUNT: u64 = 1_000_000;
+const PAGE_LEN: u32 = 4096;
+
+fn main() {
+	let mut encoder =
+		Encoder::new(Cursor::new(Vec::new()), PAGE_LEN).with_byte_order::<NativeEndian>();
+	let mut heap = Heap::new();
+	let entries: Vec<u64> = (0..ENTRY_COUNT).collect();
+	let section = encoder
+		.section_from_iter(&mut heap, entries.iter())
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), PAGE_LEN, 0)
+		.with_byte_order::<NativeEndian>();
+	let page_count = section.page_count(PAGE_LEN);
+
+	let start = Instant::now();
+	let cache = Cache::default();
+	let mut sum = 0u64;
+	for page_index in 0..page_count {
+		let page = reader.get_page_pod(section, &cache, page_index).unwrap();
+		sum += page.iter().sum::<u64>();
+	}
+	let pod = start.elapsed();
+	assert_eq!(sum, ENTRY_COUNT * (ENTRY_COUNT - 1) / 2);
+
+	let start = Instant::now();
+	let cache = Cache::default();
+	let mut sum = 0u64;
+	for page_index in 0..page_count {
+		let page = reader
+			.get_page(section, &cache, &mut (), heap, page_index)
+			.unwrap();
+		sum += page.iter().sum::<u64>();
+	}
+	let decoded = start.elapsed();
+	assert_eq!(sum, ENTRY_COUNT * (ENTRY_COUNT - 1) / 2);
+
+	println!("get_page_pod: {pod:?}");
+	println!("get_page:     {decoded:?}");
+}
@@ -21,7 +21,7 @@ pub struct Dataset {
 }
 
 #[derive(Paged)]
-#[paged(heap)]
+#[paged(heap, key = id)]
 pub struct Iri {
 	value: String,
 	id: u32,
@@ -63,12 +63,126 @@ pub struct GraphResource {
 }
 
 #[derive(Paged)]
+#[paged(key = 0)]
 pub struct Triple(u32, u32, u32);
 
 #[derive(Paged)]
+#[non_exhaustive]
 pub enum Cause {
 	Stated(u32),
 	Entailed(u32),
+	#[paged(unknown)]
+	Unknown,
+}
+
+#[derive(Paged)]
+#[paged(heap)]
+pub enum Justification {
+	None,
+	Stated(String),
+	Derived(u32, Vec<u32>),
+}
+
+#[derive(Paged)]
+#[repr(u8)]
+pub enum Status {
+	Active(u32) = 10,
+	Suspended,
+	Closed = 20,
+}
+
+#[derive(Paged)]
+pub struct Contact {
+	id: u32,
+	name: String,
+	phones: Option<Vec<u64>>,
+}
+
+#[derive(Paged)]
+#[paged(transparent)]
+pub struct ResourceId(u32);
+
+#[derive(Paged)]
+#[paged(transparent)]
+pub struct Title {
+	text: String,
+}
+
+#[derive(Paged)]
+pub struct Chapter {
+	number: u32,
+	#[paged(heap)]
+	title: Title,
+}
+
+#[derive(Paged)]
+#[paged(repr = u16)]
+pub enum Opcode {
+	Nop,
+	Push(u32),
+	#[paged(tag = 1000)]
+	Extended(u64),
+}
+
+#[derive(Paged)]
+#[paged(heap, repr = varint)]
+pub enum Term {
+	Iri(u32),
+	Literal { value: u32, ty: u32 },
+	Blank,
+}
+
+#[derive(Paged)]
+#[paged(heap, columnar)]
+pub struct Measure {
+	time: u64,
+	value: f32,
+	unit: String,
+}
+
+#[derive(Paged)]
+pub enum RecordType {
+	#[paged(tag = b'A')]
+	Address(u32),
+	#[paged(tag = b'B')]
+	Blank,
+}
+
+#[derive(Paged)]
+#[paged(
+	encode_sized_bounds(C: ::paged::EncodeSized, T: ::paged::EncodeSized),
+	encode_bounds(
+		C: ::paged::Encode<_C> + ::paged::EncodeOnHeap<_C>,
+		T: ::paged::Encode<_C> + ::paged::EncodeOnHeap<_C>
+	),
+	decode_bounds(
+		C: ::paged::Decode<_C> + ::paged::DecodeFromHeap<_C>,
+		T: ::paged::Decode<_C> + ::paged::DecodeFromHeap<_C>
+	)
+)]
+pub struct Tagged<C, T>
+where
+	C: Copy,
+	T: Clone,
+{
+	kind: C,
+	value: T,
+}
+
+#[derive(Paged)]
+#[paged(heap)]
+pub struct Label {
+	text: String,
+	#[paged(skip)]
+	pub lowercase: Option<String>,
+	#[paged(skip, default = Vec::new)]
+	pub words: Vec<String>,
+}
+
+#[derive(Paged)]
+pub struct Id<T> {
+	value: u32,
+	t: std::marker::PhantomData<T>,
 }
 
 fn main() {
@@ -0,0 +1,169 @@
+//! Column-oriented sections.
+//!
+//! A columnar section stores each field of its entries in its own section,
+//! so that scanning a single field only reads the pages of its column.
+//! Columns are regular sections, read like any other.
+use std::{io, marker::PhantomData};
+
+use crate::{
+	too_large_error,
+	utils::{BigEndian, ByteOrder, Ordered},
+	EncodeOnHeap, EncodeSized, Heap, Section,
+};
+
+/// Entry type that can be stored column by column.
+///
+/// This is implemented for tuples, where each element is a column, and can
+/// be derived for structs with `#[paged(columnar)]`.
+pub trait Columns {
+	/// Number of columns.
+	const COLUMN_COUNT: usize;
+
+	/// Sections storing each column, as a tuple.
+	type Sections;
+
+	/// Writes the given encoded columns, each holding `entry_count` entries.
+	fn write_columns<W: io::Write + io::Seek, O: ByteOrder>(
+		encoder: &mut crate::Encoder<W, O>,
+		heap: &mut Heap<O>,
+		columns: &[Vec<u8>],
+		entry_count: u32,
+	) -> io::Result<Self::Sections>;
+}
+
+/// Entry type that can be encoded column by column.
+pub trait EncodeColumns<C = ()>: Columns {
+	/// Appends the encoding of each field to its column.
+	fn encode_columns<O: ByteOrder>(
+		&self,
+		context: &C,
+		heap: &mut Heap<O>,
+		columns: &mut [Vec<u8>],
+	) -> io::Result<()>;
+}
+
+/// Writes a single encoded column as a section.
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] if the column does not hold
+/// exactly `entry_count` encoded entries.
+pub fn write_column<W: io::Write + io::Seek, T: EncodeSized, O: ByteOrder>(
+	encoder: &mut crate::Encoder<W, O>,
+	heap: &mut Heap<O>,
+	column: &[u8],
+	entry_count: u32,
+) -> io::Result<Section<T>> {
+	let len = T::ENCODED_SIZE as usize;
+	if column.len() as u64 != entry_count as u64 * len as u64 {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"column size mismatch",
+		));
+	}
+
+	let mut section = encoder.begin_section::<T>(heap)?;
+	for i in 0..entry_count as usize {
+		section.push_raw(&column[(i * len)..((i + 1) * len)])?;
+	}
+
+	section.end()
+}
+
+/// Columnar section encoder.
+///
+/// Columns are buffered in memory until the end of the section, then written
+/// one after the other.
+pub struct Encoder<'a, 'h, W, T: Columns, O = BigEndian> {
+	encoder: &'a mut crate::Encoder<W, O>,
+	heap: &'h mut Heap<O>,
+	columns: Vec<Vec<u8>>,
+	entry_count: u32,
+	t: PhantomData<T>,
+}
+
+impl<'a, 'h, W, T: Columns, O: ByteOrder> Encoder<'a, 'h, W, T, O> {
+	pub(crate) fn new(encoder: &'a mut crate::Encoder<W, O>, heap: &'h mut Heap<O>) -> Self {
+		Self {
+			encoder,
+			heap,
+			columns: vec![Vec::new(); T::COLUMN_COUNT],
+			entry_count: 0,
+			t: PhantomData,
+		}
+	}
+
+	pub fn entry_count(&self) -> u32 {
+		self.entry_count
+	}
+
+	pub fn push<C>(&mut self, context: &C, value: &T) -> io::Result<()>
+	where
+		T: EncodeColumns<C>,
+	{
+		value.encode_columns(context, self.heap, &mut self.columns)?;
+		self.entry_count = self
+			.entry_count
+			.checked_add(1)
+			.ok_or_else(too_large_error)?;
+		Ok(())
+	}
+
+	/// Writes the columns and returns their sections.
+	pub fn end(self) -> io::Result<T::Sections>
+	where
+		W: io::Write + io::Seek,
+	{
+		T::write_columns(self.encoder, self.heap, &self.columns, self.entry_count)
+	}
+}
+
+macro_rules! columns_tuple {
+	($($n:literal: ($($t:ident : $i:tt),*)),*) => {
+		$(
+			impl<$($t: EncodeSized),*> Columns for ($($t,)*) {
+				const COLUMN_COUNT: usize = $n;
+
+				type Sections = ($(Section<$t>,)*);
+
+				fn write_columns<W: io::Write + io::Seek, O: ByteOrder>(
+					encoder: &mut crate::Encoder<W, O>,
+					heap: &mut Heap<O>,
+					columns: &[Vec<u8>],
+					entry_count: u32,
+				) -> io::Result<Self::Sections> {
+					Ok(($(write_column::<W, $t, O>(encoder, heap, &columns[$i], entry_count)?,)*))
+				}
+			}
+
+			impl<C, $($t: EncodeSized + EncodeOnHeap<C>),*> EncodeColumns<C> for ($($t,)*) {
+				fn encode_columns<O: ByteOrder>(
+					&self,
+					context: &C,
+					heap: &mut Heap<O>,
+					columns: &mut [Vec<u8>],
+				) -> io::Result<()> {
+					$(self.$i.encode_on_heap(
+						context,
+						heap,
+						&mut Ordered::<_, O>::new(&mut columns[$i]),
+					)?;)*
+					Ok(())
+				}
+			}
+		)*
+	};
+}
+
+columns_tuple!(
+	1: (T1: 0),
+	2: (T1: 0, T2: 1),
+	3: (T1: 0, T2: 1, T3: 2),
+	4: (T1: 0, T2: 1, T3: 2, T4: 3),
+	5: (T1: 0, T2: 1, T3: 2, T4: 3, T5: 4),
+	6: (T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5),
+	7: (T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6),
+	8: (T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7),
+	9: (T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8),
+	10: (T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8, T10: 9),
+	11: (T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8, T10: 9, T11: 10),
+	12: (T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8, T10: 9, T11: 10, T12: 11)
+);
@@ -1,28 +1,40 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasher, Hash};
 use std::io;
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::{
+	NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+	NonZeroU32, NonZeroU64, NonZeroU8,
+};
+use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::{heap, reader, EncodeSized, HeapSection};
+use crate::utils::{ByteOrder, Input};
+use crate::{heap, reader, utils, EncodeSized, HeapSection};
 
 pub trait Decode<C>: Sized {
-	fn decode<R: io::Read>(input: &mut R, context: &mut C) -> io::Result<Self>;
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self>;
 }
 
 macro_rules! decode_int {
 	($($ty:ty),*) => {
 		$(
 			impl<C> Decode<C> for $ty {
-				fn decode<R: io::Read>(
+				fn decode<R: Input>(
 					input: &mut R,
 					_context: &mut C
 				) -> io::Result<Self> {
-					let mut result = [0u8; std::mem::size_of::<$ty>()];
-					input.read_exact(&mut result)?;
-					Ok(Self::from_be_bytes(result))
+					utils::decode_int(input)
 				}
 			}
 
 			impl<C> DecodeFromHeap<C> for $ty {
-				fn decode_from_heap<R: io::Seek + io::Read>(
-					input: &mut reader::Cursor<R>,
+				fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+					input: &mut reader::Cursor<R, O>,
 					context: &mut C,
 					_heap: HeapSection
 				) -> io::Result<Self> {
@@ -33,31 +45,235 @@ macro_rules! decode_int {
 	};
 }
 
-decode_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+decode_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+macro_rules! decode_non_zero {
+	($($ty:ident : $int:ty),*) => {
+		$(
+			/// Fails with [`io::ErrorKind::InvalidData`] if the decoded value is zero.
+			impl<C> Decode<C> for $ty {
+				fn decode<R: Input>(
+					input: &mut R,
+					context: &mut C
+				) -> io::Result<Self> {
+					$ty::new(<$int>::decode(input, context)?).ok_or_else(|| io::ErrorKind::InvalidData.into())
+				}
+			}
+
+			impl<C> DecodeFromHeap<C> for $ty {
+				fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+					input: &mut reader::Cursor<R, O>,
+					context: &mut C,
+					_heap: HeapSection
+				) -> io::Result<Self> {
+					Self::decode(input, context)
+				}
+			}
+		)*
+	};
+}
+
+decode_non_zero!(
+	NonZeroI8: i8,
+	NonZeroI16: i16,
+	NonZeroI32: i32,
+	NonZeroI64: i64,
+	NonZeroI128: i128,
+	NonZeroU8: u8,
+	NonZeroU16: u16,
+	NonZeroU32: u32,
+	NonZeroU64: u64,
+	NonZeroU128: u128
+);
+
+impl<C> Decode<C> for () {
+	fn decode<R: Input>(_input: &mut R, _context: &mut C) -> io::Result<Self> {
+		Ok(())
+	}
+}
+
+impl<C> DecodeFromHeap<C> for () {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		_input: &mut reader::Cursor<R, O>,
+		_context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Ok(())
+	}
+}
+
+impl<C, T: ?Sized> Decode<C> for PhantomData<T> {
+	fn decode<R: Input>(_input: &mut R, _context: &mut C) -> io::Result<Self> {
+		Ok(PhantomData)
+	}
+}
+
+impl<C, T: ?Sized> DecodeFromHeap<C> for PhantomData<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		_input: &mut reader::Cursor<R, O>,
+		_context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Ok(PhantomData)
+	}
+}
+
+impl<C> Decode<C> for bool {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		match u8::decode(input, context)? {
+			0 => Ok(false),
+			1 => Ok(true),
+			_ => Err(io::ErrorKind::InvalidData.into()),
+		}
+	}
+}
+
+impl<C> DecodeFromHeap<C> for bool {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
+
+impl<C> Decode<C> for char {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		char::from_u32(u32::decode(input, context)?)
+			.ok_or_else(|| io::ErrorKind::InvalidData.into())
+	}
+}
+
+impl<C> DecodeFromHeap<C> for char {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
+
+impl<C> Decode<C> for Duration {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		let secs = u64::decode(input, context)?;
+		let nanos = u32::decode(input, context)?;
+		if nanos < 1_000_000_000 {
+			Ok(Duration::new(secs, nanos))
+		} else {
+			Err(io::ErrorKind::InvalidData.into())
+		}
+	}
+}
+
+impl<C> DecodeFromHeap<C> for Duration {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
+
+macro_rules! decode_sized_from_heap {
+	($($ty:ty),*) => {
+		$(
+			impl<C> DecodeFromHeap<C> for $ty {
+				fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+					input: &mut reader::Cursor<R, O>,
+					context: &mut C,
+					_heap: HeapSection,
+				) -> io::Result<Self> {
+					Self::decode(input, context)
+				}
+			}
+		)*
+	};
+}
+
+impl<C> Decode<C> for Ipv4Addr {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		<[u8; 4]>::decode(input, context).map(Self::from)
+	}
+}
+
+impl<C> Decode<C> for Ipv6Addr {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		<[u8; 16]>::decode(input, context).map(Self::from)
+	}
+}
+
+impl<C> Decode<C> for IpAddr {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		match u8::decode(input, context)? {
+			0 => {
+				let a = Ipv4Addr::decode(input, context)?;
+				pad(input, Ipv6Addr::ENCODED_SIZE - Ipv4Addr::ENCODED_SIZE)?;
+				Ok(Self::V4(a))
+			}
+			1 => Ipv6Addr::decode(input, context).map(Self::V6),
+			_ => Err(io::ErrorKind::InvalidData.into()),
+		}
+	}
+}
+
+impl<C> Decode<C> for SocketAddr {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		let ip = IpAddr::decode(input, context)?;
+		let port = u16::decode(input, context)?;
+		Ok(Self::new(ip, port))
+	}
+}
+
+decode_sized_from_heap!(Ipv4Addr, Ipv6Addr, IpAddr, SocketAddr);
 
 pub trait DecodeFromHeap<C = ()>: Sized {
-	fn decode_from_heap<R: io::Seek + io::Read>(
-		input: &mut reader::Cursor<R>,
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
 		context: &mut C,
 		heap: HeapSection,
 	) -> io::Result<Self>;
 }
 
 impl<C> DecodeFromHeap<C> for String {
-	fn decode_from_heap<R: io::Seek + io::Read>(
-		input: &mut reader::Cursor<R>,
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
 		context: &mut C,
 		heap: HeapSection,
 	) -> io::Result<Self> {
 		let entry = heap::Entry::decode(input, context)?;
-		let mut bytes = Vec::new();
-		bytes.resize(entry.len as usize, 0u8);
+		input.report_heap_entry(entry);
+		let mut bytes = vec![0u8; entry.len as usize];
 		input.read_from_heap(heap, entry.offset, bytes.as_mut_slice())?;
 		String::from_utf8(bytes).map_err(|_| io::ErrorKind::InvalidData.into())
 	}
 }
 
-fn pad(input: &mut impl io::Read, len: u32) -> io::Result<()> {
+/// Always decodes into the [`Cow::Owned`] variant.
+impl<C> DecodeFromHeap<C> for Cow<'static, str> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		String::decode_from_heap(input, context, heap).map(Cow::Owned)
+	}
+}
+
+/// Shares the heap layout of [`String`], without the spare capacity.
+impl<C> DecodeFromHeap<C> for Box<str> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		String::decode_from_heap(input, context, heap).map(String::into_boxed_str)
+	}
+}
+
+pub(crate) fn pad(input: &mut impl io::Read, len: u32) -> io::Result<()> {
 	let mut buffer = [0u8; 1];
 	for _ in 0..len {
 		input.read_exact(&mut buffer)?;
@@ -67,7 +283,17 @@ fn pad(input: &mut impl io::Read, len: u32) -> io::Result<()> {
 }
 
 impl<C, T: EncodeSized + Decode<C>> Decode<C> for Option<T> {
-	fn decode<R: io::Read>(input: &mut R, context: &mut C) -> io::Result<Self> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		if let Some(niche) = T::NICHE {
+			let mut bytes = vec![0u8; T::ENCODED_SIZE as usize];
+			input.read_exact(&mut bytes)?;
+			return if bytes == niche {
+				Ok(None)
+			} else {
+				T::decode(&mut utils::Ordered::like(input, bytes.as_slice()), context).map(Some)
+			};
+		}
+
 		let discriminant = u8::decode(input, context)?;
 		match discriminant {
 			0 => {
@@ -81,11 +307,23 @@ impl<C, T: EncodeSized + Decode<C>> Decode<C> for Option<T> {
 }
 
 impl<C, T: EncodeSized + DecodeFromHeap<C>> DecodeFromHeap<C> for Option<T> {
-	fn decode_from_heap<R: io::Seek + io::Read>(
-		input: &mut reader::Cursor<R>,
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
 		context: &mut C,
 		heap: HeapSection,
 	) -> io::Result<Self> {
+		if let Some(niche) = T::NICHE {
+			let offset = input.offset();
+			let mut bytes = vec![0u8; T::ENCODED_SIZE as usize];
+			input.read(&mut bytes)?;
+			if bytes == niche {
+				return Ok(None);
+			}
+
+			input.seek(offset)?;
+			return T::decode_from_heap(input, context, heap).map(Some);
+		}
+
 		let discriminant = u8::decode(input, context)?;
 		match discriminant {
 			0 => {
@@ -98,39 +336,349 @@ impl<C, T: EncodeSized + DecodeFromHeap<C>> DecodeFromHeap<C> for Option<T> {
 	}
 }
 
+impl<C, T: EncodeSized + Decode<C>, E: EncodeSized + Decode<C>> Decode<C> for Result<T, E> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		let discriminant = u8::decode(input, context)?;
+		let (result, len) = match discriminant {
+			0 => (Ok(T::decode(input, context)?), T::ENCODED_SIZE),
+			1 => (Err(E::decode(input, context)?), E::ENCODED_SIZE),
+			_ => return Err(io::ErrorKind::InvalidData.into()),
+		};
+
+		pad(input, Self::ENCODED_SIZE - 1 - len)?;
+		Ok(result)
+	}
+}
+
+impl<C, T: EncodeSized + DecodeFromHeap<C>, E: EncodeSized + DecodeFromHeap<C>> DecodeFromHeap<C>
+	for Result<T, E>
+{
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let discriminant = u8::decode(input, context)?;
+		let (result, len) = match discriminant {
+			0 => (
+				Ok(T::decode_from_heap(input, context, heap)?),
+				T::ENCODED_SIZE,
+			),
+			1 => (
+				Err(E::decode_from_heap(input, context, heap)?),
+				E::ENCODED_SIZE,
+			),
+			_ => return Err(io::ErrorKind::InvalidData.into()),
+		};
+
+		input.pad(Self::ENCODED_SIZE - 1 - len)?;
+		Ok(result)
+	}
+}
+
+impl<C, T: Decode<C>> Decode<C> for Box<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		T::decode(input, context).map(Box::new)
+	}
+}
+
+impl<C, T: DecodeFromHeap<C>> DecodeFromHeap<C> for Box<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		T::decode_from_heap(input, context, heap).map(Box::new)
+	}
+}
+
+impl<C, T: Decode<C>> Decode<C> for Rc<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		T::decode(input, context).map(Rc::new)
+	}
+}
+
+impl<C, T: DecodeFromHeap<C>> DecodeFromHeap<C> for Rc<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		T::decode_from_heap(input, context, heap).map(Rc::new)
+	}
+}
+
+impl<C, T: Decode<C>> Decode<C> for Arc<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		T::decode(input, context).map(Arc::new)
+	}
+}
+
+impl<C, T: DecodeFromHeap<C>> DecodeFromHeap<C> for Arc<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		T::decode_from_heap(input, context, heap).map(Arc::new)
+	}
+}
+
 impl<C, T: Decode<C>> DecodeFromHeap<C> for Vec<T> {
-	fn decode_from_heap<R: io::Seek + io::Read>(
-		input: &mut reader::Cursor<R>,
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
 		context: &mut C,
 		heap: HeapSection,
 	) -> io::Result<Self> {
 		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
 		let mut result = Vec::with_capacity(entry.len as usize);
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			result.push(t);
+			Ok(())
+		})?;
+		Ok(result)
+	}
+}
 
-		for _ in 0..entry.len {
-			result.push(input.decode_from_heap(context, heap, entry.offset)?)
-		}
+/// Shares the heap layout of [`Vec`], without the spare capacity.
+impl<C, T: Decode<C>> DecodeFromHeap<C> for Box<[T]> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		Vec::decode_from_heap(input, context, heap).map(Vec::into_boxed_slice)
+	}
+}
+
+/// Shares the heap layout of [`Vec`], from front to back.
+impl<C, T: Decode<C>> DecodeFromHeap<C> for VecDeque<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut result = VecDeque::with_capacity(entry.len as usize);
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			result.push_back(t);
+			Ok(())
+		})?;
+		Ok(result)
+	}
+}
+
+/// Shares the heap layout of `Vec<(K, V)>`.
+impl<C, K: Eq + Hash + Decode<C>, V: Decode<C>, S: Default + BuildHasher> DecodeFromHeap<C>
+	for HashMap<K, V, S>
+{
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut result = HashMap::with_capacity_and_hasher(entry.len as usize, S::default());
+		input.decode_seq_from_heap(context, heap, entry, |(k, v)| {
+			result.insert(k, v);
+			Ok(())
+		})?;
+		Ok(result)
+	}
+}
+
+/// Shares the heap layout of `Vec<(K, V)>`.
+impl<C, K: Ord + Decode<C>, V: Decode<C>> DecodeFromHeap<C> for BTreeMap<K, V> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut result = BTreeMap::new();
+		input.decode_seq_from_heap(context, heap, entry, |(k, v)| {
+			result.insert(k, v);
+			Ok(())
+		})?;
+		Ok(result)
+	}
+}
+
+/// Shares the heap layout of `Vec<T>`. Fails with
+/// [`io::ErrorKind::InvalidData`] on duplicate elements.
+impl<C, T: Eq + Hash + Decode<C>, S: Default + BuildHasher> DecodeFromHeap<C> for HashSet<T, S> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut result = HashSet::with_capacity_and_hasher(entry.len as usize, S::default());
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			if result.insert(t) {
+				Ok(())
+			} else {
+				Err(io::ErrorKind::InvalidData.into())
+			}
+		})?;
+		Ok(result)
+	}
+}
 
+/// Shares the heap layout of `Vec<T>`. Fails with
+/// [`io::ErrorKind::InvalidData`] on duplicate elements.
+impl<C, T: Ord + Decode<C>> DecodeFromHeap<C> for BTreeSet<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut result = BTreeSet::new();
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			if result.insert(t) {
+				Ok(())
+			} else {
+				Err(io::ErrorKind::InvalidData.into())
+			}
+		})?;
 		Ok(result)
 	}
 }
 
-impl<C, T1: Decode<C>, T2: Decode<C>> Decode<C> for (T1, T2) {
-	fn decode<R: io::Read>(input: &mut R, context: &mut C) -> io::Result<Self> {
-		let t1 = T1::decode(input, context)?;
-		let t2 = T2::decode(input, context)?;
-		Ok((t1, t2))
+/// Collects exactly `N` elements into an array.
+fn collect_array<T, const N: usize>(mut f: impl FnMut() -> io::Result<T>) -> io::Result<[T; N]> {
+	let mut elements = Vec::with_capacity(N);
+	for _ in 0..N {
+		elements.push(f()?);
+	}
+
+	Ok(elements
+		.try_into()
+		.unwrap_or_else(|_| unreachable!("exactly N elements were collected")))
+}
+
+impl<C, T: Decode<C>, const N: usize> Decode<C> for [T; N] {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		collect_array(|| T::decode(input, context))
+	}
+}
+
+impl<C, T: DecodeFromHeap<C>, const N: usize> DecodeFromHeap<C> for [T; N] {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		collect_array(|| T::decode_from_heap(input, context, heap))
+	}
+}
+
+macro_rules! decode_tuple {
+	($(($($t:ident),*)),*) => {
+		$(
+			impl<C, $($t: Decode<C>),*> Decode<C> for ($($t,)*) {
+				fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+					Ok(($($t::decode(input, context)?,)*))
+				}
+			}
+
+			impl<C, $($t: DecodeFromHeap<C>),*> DecodeFromHeap<C> for ($($t,)*) {
+				fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+					input: &mut reader::Cursor<R, O>,
+					context: &mut C,
+					heap: HeapSection
+				) -> io::Result<Self> {
+					Ok(($($t::decode_from_heap(input, context, heap)?,)*))
+				}
+			}
+		)*
+	};
+}
+
+decode_tuple!(
+	(T1),
+	(T1, T2),
+	(T1, T2, T3),
+	(T1, T2, T3, T4),
+	(T1, T2, T3, T4, T5),
+	(T1, T2, T3, T4, T5, T6),
+	(T1, T2, T3, T4, T5, T6, T7),
+	(T1, T2, T3, T4, T5, T6, T7, T8),
+	(T1, T2, T3, T4, T5, T6, T7, T8, T9),
+	(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10),
+	(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11),
+	(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12)
+);
+
+/// The decoded range may be empty or inverted: `start <= end` is not checked.
+impl<C, T: Decode<C>> Decode<C> for Range<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		Ok(T::decode(input, context)?..T::decode(input, context)?)
+	}
+}
+
+/// The decoded range may be empty or inverted: `start <= end` is not checked.
+impl<C, T: DecodeFromHeap<C>> DecodeFromHeap<C> for Range<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		Ok(T::decode_from_heap(input, context, heap)?..T::decode_from_heap(input, context, heap)?)
+	}
+}
+
+impl<C, T: Decode<C>> Decode<C> for RangeFrom<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		Ok(T::decode(input, context)?..)
 	}
 }
 
-impl<C, T1: DecodeFromHeap<C>, T2: DecodeFromHeap<C>> DecodeFromHeap<C> for (T1, T2) {
-	fn decode_from_heap<R: io::Seek + io::Read>(
-		input: &mut reader::Cursor<R>,
+impl<C, T: DecodeFromHeap<C>> DecodeFromHeap<C> for RangeFrom<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
 		context: &mut C,
 		heap: HeapSection,
 	) -> io::Result<Self> {
-		let t1 = T1::decode_from_heap(input, context, heap)?;
-		let t2 = T2::decode_from_heap(input, context, heap)?;
-		Ok((t1, t2))
+		Ok(T::decode_from_heap(input, context, heap)?..)
+	}
+}
+
+impl<C, T: Decode<C>> Decode<C> for RangeTo<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		Ok(..T::decode(input, context)?)
+	}
+}
+
+impl<C, T: DecodeFromHeap<C>> DecodeFromHeap<C> for RangeTo<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		Ok(..T::decode_from_heap(input, context, heap)?)
+	}
+}
+
+impl<C> Decode<C> for RangeFull {
+	fn decode<R: Input>(_input: &mut R, _context: &mut C) -> io::Result<Self> {
+		Ok(..)
+	}
+}
+
+impl<C> DecodeFromHeap<C> for RangeFull {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		_input: &mut reader::Cursor<R, O>,
+		_context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Ok(..)
 	}
 }
@@ -1,53 +1,286 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::io;
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::{
+	NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+	NonZeroU32, NonZeroU64, NonZeroU8,
+};
+use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::heap::{self, Heap};
+use crate::utils::{self, Output};
 
 pub trait Encode<C = ()> {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32>;
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32>;
 }
 
 pub trait EncodeOnHeap<C = ()>: EncodeSized {
-	fn encode_on_heap(
+	fn encode_on_heap<W: Output>(
 		&self,
 		context: &C,
-		heap: &mut Heap,
-		output: &mut impl io::Write,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
 	) -> io::Result<u32>;
 }
 
 pub trait EncodeSized {
 	const ENCODED_SIZE: u32;
+
+	/// Bytes never produced when encoding a value of this type, if any.
+	///
+	/// Its length must be `Self::ENCODED_SIZE`. `Option<Self>` then encodes
+	/// `None` with these bytes instead of a discriminant (see
+	/// [`utils::Nicheable`]).
+	const NICHE: Option<&'static [u8]> = None;
 }
 
 macro_rules! encode_int {
 	($($ty:ty),*) => {
 		$(
 			impl<C> Encode<C> for $ty {
-				fn encode(&self, _context: &C, output: &mut impl io::Write) -> io::Result<u32> {
-					output.write_all(&self.to_be_bytes())?;
-					Ok(Self::ENCODED_SIZE)
+				fn encode(&self, _context: &C, output: &mut impl Output) -> io::Result<u32> {
+					utils::encode_int(*self, output)
+				}
+			}
+
+			impl<C> EncodeOnHeap<C> for $ty {
+				fn encode_on_heap<W: Output>(&self, _context: &C, _heap: &mut Heap<W::ByteOrder>, output: &mut W) -> io::Result<u32> {
+					utils::encode_int(*self, output)
+				}
+			}
+
+			impl EncodeSized for $ty {
+				const ENCODED_SIZE: u32 = std::mem::size_of::<$ty>() as u32;
+			}
+		)*
+	};
+}
+
+encode_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+macro_rules! encode_non_zero {
+	($($ty:ty),*) => {
+		$(
+			impl<C> Encode<C> for $ty {
+				fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+					self.get().encode(context, output)
 				}
 			}
 
 			impl<C> EncodeOnHeap<C> for $ty {
-				fn encode_on_heap(&self, _context: &C, _heap: &mut Heap, output: &mut impl io::Write) -> io::Result<u32> {
-					output.write_all(&self.to_be_bytes())?;
-					Ok(Self::ENCODED_SIZE)
+				fn encode_on_heap<W: Output>(&self, context: &C, _heap: &mut Heap<W::ByteOrder>, output: &mut W) -> io::Result<u32> {
+					self.encode(context, output)
 				}
 			}
 
 			impl EncodeSized for $ty {
 				const ENCODED_SIZE: u32 = std::mem::size_of::<$ty>() as u32;
+				const NICHE: Option<&'static [u8]> = Some(&[0; std::mem::size_of::<$ty>()]);
 			}
 		)*
 	};
 }
 
-encode_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+encode_non_zero!(
+	NonZeroI8,
+	NonZeroI16,
+	NonZeroI32,
+	NonZeroI64,
+	NonZeroI128,
+	NonZeroU8,
+	NonZeroU16,
+	NonZeroU32,
+	NonZeroU64,
+	NonZeroU128
+);
+
+impl<C> Encode<C> for () {
+	fn encode(&self, _context: &C, _output: &mut impl Output) -> io::Result<u32> {
+		Ok(0)
+	}
+}
+
+impl<C> EncodeOnHeap<C> for () {
+	fn encode_on_heap<W: Output>(
+		&self,
+		_context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		_output: &mut W,
+	) -> io::Result<u32> {
+		Ok(0)
+	}
+}
+
+impl EncodeSized for () {
+	const ENCODED_SIZE: u32 = 0;
+}
+
+impl<C, T: ?Sized> Encode<C> for PhantomData<T> {
+	fn encode(&self, _context: &C, _output: &mut impl Output) -> io::Result<u32> {
+		Ok(0)
+	}
+}
+
+impl<C, T: ?Sized> EncodeOnHeap<C> for PhantomData<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		_context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		_output: &mut W,
+	) -> io::Result<u32> {
+		Ok(0)
+	}
+}
+
+impl<T: ?Sized> EncodeSized for PhantomData<T> {
+	const ENCODED_SIZE: u32 = 0;
+}
+
+impl<C> Encode<C> for bool {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		(*self as u8).encode(context, output)
+	}
+}
+
+impl<C> EncodeOnHeap<C> for bool {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.encode(context, output)
+	}
+}
+
+impl EncodeSized for bool {
+	const ENCODED_SIZE: u32 = 1;
+	const NICHE: Option<&'static [u8]> = Some(&[0x02]);
+}
+
+impl<C> Encode<C> for char {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		(*self as u32).encode(context, output)
+	}
+}
+
+impl<C> EncodeOnHeap<C> for char {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.encode(context, output)
+	}
+}
+
+impl EncodeSized for char {
+	const ENCODED_SIZE: u32 = u32::ENCODED_SIZE;
+	const NICHE: Option<&'static [u8]> = Some(&[0xff, 0xff, 0xff, 0xff]);
+}
+
+/// Encoded as a `u64` number of seconds followed by a `u32` number of
+/// nanoseconds.
+impl<C> Encode<C> for Duration {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.as_secs().encode(context, output)?;
+		self.subsec_nanos().encode(context, output)?;
+		Ok(Self::ENCODED_SIZE)
+	}
+}
+
+impl<C> EncodeOnHeap<C> for Duration {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.encode(context, output)
+	}
+}
+
+impl EncodeSized for Duration {
+	const ENCODED_SIZE: u32 = u64::ENCODED_SIZE + u32::ENCODED_SIZE;
+}
+
+macro_rules! encode_sized_on_heap {
+	($($ty:ty),*) => {
+		$(
+			impl<C> EncodeOnHeap<C> for $ty {
+				fn encode_on_heap<W: Output>(
+					&self,
+					context: &C,
+					_heap: &mut Heap<W::ByteOrder>,
+					output: &mut W,
+				) -> io::Result<u32> {
+					self.encode(context, output)
+				}
+			}
+		)*
+	};
+}
+
+impl<C> Encode<C> for Ipv4Addr {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.octets().encode(context, output)
+	}
+}
+
+impl EncodeSized for Ipv4Addr {
+	const ENCODED_SIZE: u32 = 4;
+}
+
+impl<C> Encode<C> for Ipv6Addr {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.octets().encode(context, output)
+	}
+}
+
+impl EncodeSized for Ipv6Addr {
+	const ENCODED_SIZE: u32 = 16;
+}
+
+/// Encoded as a `0` byte followed by the IPv4 address padded to 16 bytes, or
+/// a `1` byte followed by the IPv6 address.
+impl<C> Encode<C> for IpAddr {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		match self {
+			Self::V4(a) => Ok(0u8.encode(context, output)?
+				+ a.encode(context, output)?
+				+ pad(output, Ipv6Addr::ENCODED_SIZE - Ipv4Addr::ENCODED_SIZE)?),
+			Self::V6(a) => Ok(1u8.encode(context, output)? + a.encode(context, output)?),
+		}
+	}
+}
 
-pub fn encode_string_on_heap(
-	heap: &mut Heap,
-	output: &mut impl io::Write,
+impl EncodeSized for IpAddr {
+	const ENCODED_SIZE: u32 = 1 + Ipv6Addr::ENCODED_SIZE;
+}
+
+/// Encoded as the IP address followed by the port. The flow information and
+/// scope identifier of IPv6 socket addresses are not stored.
+impl<C> Encode<C> for SocketAddr {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		Ok(self.ip().encode(context, output)? + self.port().encode(context, output)?)
+	}
+}
+
+impl EncodeSized for SocketAddr {
+	const ENCODED_SIZE: u32 = IpAddr::ENCODED_SIZE + u16::ENCODED_SIZE;
+}
+
+encode_sized_on_heap!(Ipv4Addr, Ipv6Addr, IpAddr, SocketAddr);
+
+pub fn encode_string_on_heap<W: Output>(
+	heap: &mut Heap<W::ByteOrder>,
+	output: &mut W,
 	str: &str,
 ) -> io::Result<u32> {
 	let entry = heap.insert(&(), str)?.sized(str.len() as u32);
@@ -55,18 +288,18 @@ pub fn encode_string_on_heap(
 }
 
 impl<C> Encode<C> for str {
-	fn encode(&self, _context: &C, output: &mut impl io::Write) -> io::Result<u32> {
+	fn encode(&self, _context: &C, output: &mut impl Output) -> io::Result<u32> {
 		output.write_all(self.as_bytes())?;
 		Ok(self.len() as u32)
 	}
 }
 
 impl<C> EncodeOnHeap<C> for String {
-	fn encode_on_heap(
+	fn encode_on_heap<W: Output>(
 		&self,
 		_context: &C,
-		heap: &mut Heap,
-		output: &mut impl io::Write,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
 	) -> io::Result<u32> {
 		encode_string_on_heap(heap, output, self.as_str())
 	}
@@ -74,46 +307,191 @@ impl<C> EncodeOnHeap<C> for String {
 
 impl EncodeSized for String {
 	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+	const NICHE: Option<&'static [u8]> = Some(heap::Entry::NONE_BYTES);
+}
+
+impl<C> EncodeOnHeap<C> for Cow<'static, str> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		_context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		encode_string_on_heap(heap, output, self)
+	}
+}
+
+impl EncodeSized for Cow<'static, str> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+	const NICHE: Option<&'static [u8]> = Some(heap::Entry::NONE_BYTES);
 }
 
-fn pad(output: &mut impl io::Write, len: u32) -> io::Result<u32> {
-	for _ in 0..len {
-		0u8.encode(&(), output)?;
+impl<C> EncodeOnHeap<C> for Box<str> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		_context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		encode_string_on_heap(heap, output, self)
 	}
+}
+
+impl EncodeSized for Box<str> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+	const NICHE: Option<&'static [u8]> = Some(heap::Entry::NONE_BYTES);
+}
+
+pub(crate) fn pad(output: &mut impl io::Write, len: u32) -> io::Result<u32> {
+	utils::write_padding(output, len)?;
 	Ok(len)
 }
 
+/// If `T` has a niche (see [`EncodeSized::NICHE`]), `None` is encoded as the
+/// niche and `Some(t)` as `t`, without discriminant. Otherwise `None` is
+/// encoded as a `0` byte followed by `T::ENCODED_SIZE` bytes of padding, both
+/// inline and on heap, so that every value has the same size. For heap-backed
+/// `T`, the padding covers the heap entry reference.
 impl<T: EncodeSized> EncodeSized for Option<T> {
-	const ENCODED_SIZE: u32 = 1 + T::ENCODED_SIZE;
+	const ENCODED_SIZE: u32 = match T::NICHE {
+		Some(_) => T::ENCODED_SIZE,
+		None => 1 + T::ENCODED_SIZE,
+	};
 }
 
 impl<C, T: EncodeSized + Encode<C>> Encode<C> for Option<T> {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32> {
-		match self {
-			Self::None => Ok(0u8.encode(context, output)? + pad(output, T::ENCODED_SIZE)?),
-			Self::Some(t) => Ok(1u8.encode(context, output)? + t.encode(context, output)?),
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		match (self, T::NICHE) {
+			(Self::None, Some(niche)) => {
+				output.write_all(niche)?;
+				Ok(T::ENCODED_SIZE)
+			}
+			(Self::Some(t), Some(_)) => t.encode(context, output),
+			(Self::None, None) => Ok(0u8.encode(context, output)? + pad(output, T::ENCODED_SIZE)?),
+			(Self::Some(t), None) => Ok(1u8.encode(context, output)? + t.encode(context, output)?),
 		}
 	}
 }
 
 impl<C, T: EncodeOnHeap<C>> EncodeOnHeap<C> for Option<T> {
-	fn encode_on_heap(
+	fn encode_on_heap<W: Output>(
 		&self,
 		context: &C,
-		heap: &mut Heap,
-		output: &mut impl io::Write,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
 	) -> io::Result<u32> {
-		match self {
-			Self::None => Ok(0u8.encode(context, output)? + pad(output, T::ENCODED_SIZE)?),
-			Self::Some(t) => {
+		match (self, T::NICHE) {
+			(Self::None, Some(niche)) => {
+				output.write_all(niche)?;
+				Ok(T::ENCODED_SIZE)
+			}
+			(Self::Some(t), Some(_)) => t.encode_on_heap(context, heap, output),
+			(Self::None, None) => Ok(0u8.encode(context, output)? + pad(output, T::ENCODED_SIZE)?),
+			(Self::Some(t), None) => {
 				Ok(1u8.encode(context, output)? + t.encode_on_heap(context, heap, output)?)
 			}
 		}
 	}
 }
 
+/// `Ok` and `Err` are respectively encoded with a `0` and `1` byte followed by
+/// the value, padded to the size of the largest of `T` and `E`.
+impl<T: EncodeSized, E: EncodeSized> EncodeSized for Result<T, E> {
+	const ENCODED_SIZE: u32 = 1 + utils::max(T::ENCODED_SIZE, E::ENCODED_SIZE);
+}
+
+impl<C, T: EncodeSized + Encode<C>, E: EncodeSized + Encode<C>> Encode<C> for Result<T, E> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		let len = match self {
+			Ok(t) => 0u8.encode(context, output)? + t.encode(context, output)?,
+			Err(e) => 1u8.encode(context, output)? + e.encode(context, output)?,
+		};
+
+		Ok(len + pad(output, Self::ENCODED_SIZE - len)?)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>, E: EncodeOnHeap<C>> EncodeOnHeap<C> for Result<T, E> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let len = match self {
+			Ok(t) => 0u8.encode(context, output)? + t.encode_on_heap(context, heap, output)?,
+			Err(e) => 1u8.encode(context, output)? + e.encode_on_heap(context, heap, output)?,
+		};
+
+		Ok(len + pad(output, Self::ENCODED_SIZE - len)?)
+	}
+}
+
+impl<T: EncodeSized> EncodeSized for Box<T> {
+	const ENCODED_SIZE: u32 = T::ENCODED_SIZE;
+}
+
+impl<C, T: ?Sized + Encode<C>> Encode<C> for Box<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		T::encode(self, context, output)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>> EncodeOnHeap<C> for Box<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		T::encode_on_heap(self, context, heap, output)
+	}
+}
+
+impl<T: EncodeSized> EncodeSized for Rc<T> {
+	const ENCODED_SIZE: u32 = T::ENCODED_SIZE;
+}
+
+impl<C, T: ?Sized + Encode<C>> Encode<C> for Rc<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		T::encode(self, context, output)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>> EncodeOnHeap<C> for Rc<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		T::encode_on_heap(self, context, heap, output)
+	}
+}
+
+impl<T: EncodeSized> EncodeSized for Arc<T> {
+	const ENCODED_SIZE: u32 = T::ENCODED_SIZE;
+}
+
+impl<C, T: ?Sized + Encode<C>> Encode<C> for Arc<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		T::encode(self, context, output)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>> EncodeOnHeap<C> for Arc<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		T::encode_on_heap(self, context, heap, output)
+	}
+}
+
 impl<C, T: Encode<C>> Encode<C> for [T] {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
 		let mut len = 0;
 		for t in self {
 			len += t.encode(context, output)?;
@@ -123,11 +501,11 @@ impl<C, T: Encode<C>> Encode<C> for [T] {
 }
 
 impl<C, T: Encode<C>> EncodeOnHeap<C> for Vec<T> {
-	fn encode_on_heap(
+	fn encode_on_heap<W: Output>(
 		&self,
 		context: &C,
-		heap: &mut Heap,
-		output: &mut impl io::Write,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
 	) -> io::Result<u32> {
 		let entry = heap
 			.insert(context, self.as_slice())?
@@ -138,29 +516,536 @@ impl<C, T: Encode<C>> EncodeOnHeap<C> for Vec<T> {
 
 impl<T> EncodeSized for Vec<T> {
 	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+	const NICHE: Option<&'static [u8]> = Some(heap::Entry::NONE_BYTES);
 }
 
-impl<T1: EncodeSized, T2: EncodeSized> EncodeSized for (T1, T2) {
-	const ENCODED_SIZE: u32 = T1::ENCODED_SIZE + T2::ENCODED_SIZE;
+impl<C, T: Encode<C>> EncodeOnHeap<C> for Box<[T]> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let entry = heap.insert(context, &**self)?.sized(self.len() as u32);
+		entry.encode(context, output)
+	}
+}
+
+impl<T> EncodeSized for Box<[T]> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+	const NICHE: Option<&'static [u8]> = Some(heap::Entry::NONE_BYTES);
+}
+
+impl<C, T: Encode<C>> EncodeOnHeap<C> for VecDeque<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		// The two halves of the ring buffer are encoded in a single buffer
+		// so that they are inserted as one contiguous value.
+		let mut bytes = utils::Ordered::like(output, Vec::new());
+		for t in self {
+			t.encode(context, &mut bytes)?;
+		}
+
+		let entry = heap
+			.insert(context, bytes.0.as_slice())?
+			.sized(self.len() as u32);
+		entry.encode(context, output)
+	}
+}
+
+impl<T> EncodeSized for VecDeque<T> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+	const NICHE: Option<&'static [u8]> = Some(heap::Entry::NONE_BYTES);
+}
+
+/// Stores the given encoded elements on the heap, like a `Vec`.
+fn insert_encoded_seq<C, W: Output>(
+	context: &C,
+	heap: &mut Heap<W::ByteOrder>,
+	output: &mut W,
+	mut elements: Vec<Vec<u8>>,
+	sort: bool,
+) -> io::Result<u32> {
+	if sort {
+		elements.sort_unstable()
+	}
+
+	let entry = heap
+		.insert(context, elements.concat().as_slice())?
+		.sized(elements.len() as u32);
+	entry.encode(context, output)
+}
+
+/// Stores the given key-value pairs on the heap, like a `Vec<(K, V)>`.
+fn encode_map_on_heap<'a, C, K: 'a + Encode<C>, V: 'a + Encode<C>, W: Output>(
+	context: &C,
+	heap: &mut Heap<W::ByteOrder>,
+	output: &mut W,
+	entries: impl Iterator<Item = (&'a K, &'a V)>,
+	sort: bool,
+) -> io::Result<u32> {
+	let mut pairs = Vec::new();
+	for (k, v) in entries {
+		let mut bytes = utils::Ordered::like(output, Vec::new());
+		k.encode(context, &mut bytes)?;
+		v.encode(context, &mut bytes)?;
+		pairs.push(bytes.0)
+	}
+
+	insert_encoded_seq(context, heap, output, pairs, sort)
 }
 
-impl<C, T1: Encode<C>, T2: Encode<C>> Encode<C> for (T1, T2) {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32> {
-		let a = self.0.encode(context, output)?;
-		let b = self.1.encode(context, output)?;
-		Ok(a + b)
+/// Stores the given elements on the heap, like a `Vec<T>`.
+fn encode_set_on_heap<'a, C, T: 'a + Encode<C>, W: Output>(
+	context: &C,
+	heap: &mut Heap<W::ByteOrder>,
+	output: &mut W,
+	items: impl Iterator<Item = &'a T>,
+	sort: bool,
+) -> io::Result<u32> {
+	let mut elements = Vec::new();
+	for t in items {
+		let mut bytes = utils::Ordered::like(output, Vec::new());
+		t.encode(context, &mut bytes)?;
+		elements.push(bytes.0)
 	}
+
+	insert_encoded_seq(context, heap, output, elements, sort)
+}
+
+/// Entries are stored in iteration order, which is arbitrary. With the
+/// `sorted-maps` feature, they are sorted by encoding so that the output is
+/// deterministic.
+impl<C, K: Encode<C>, V: Encode<C>, S> EncodeOnHeap<C> for HashMap<K, V, S> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		encode_map_on_heap(
+			context,
+			heap,
+			output,
+			self.iter(),
+			cfg!(feature = "sorted-maps"),
+		)
+	}
+}
+
+impl<K, V, S> EncodeSized for HashMap<K, V, S> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
 }
 
-impl<C, T1: EncodeOnHeap<C>, T2: EncodeOnHeap<C>> EncodeOnHeap<C> for (T1, T2) {
-	fn encode_on_heap(
+/// Entries are stored in key order.
+impl<C, K: Encode<C>, V: Encode<C>> EncodeOnHeap<C> for BTreeMap<K, V> {
+	fn encode_on_heap<W: Output>(
 		&self,
 		context: &C,
-		heap: &mut Heap,
-		output: &mut impl io::Write,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
 	) -> io::Result<u32> {
-		let a = self.0.encode_on_heap(context, heap, output)?;
-		let b = self.0.encode_on_heap(context, heap, output)?;
-		Ok(a + b)
+		encode_map_on_heap(context, heap, output, self.iter(), false)
+	}
+}
+
+impl<K, V> EncodeSized for BTreeMap<K, V> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+/// Elements are stored in iteration order, which is arbitrary. With the
+/// `sorted-maps` feature, they are sorted by encoding so that the output is
+/// deterministic.
+impl<C, T: Encode<C>, S> EncodeOnHeap<C> for HashSet<T, S> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		encode_set_on_heap(
+			context,
+			heap,
+			output,
+			self.iter(),
+			cfg!(feature = "sorted-maps"),
+		)
+	}
+}
+
+impl<T, S> EncodeSized for HashSet<T, S> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+/// Elements are stored in order.
+impl<C, T: Encode<C>> EncodeOnHeap<C> for BTreeSet<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		encode_set_on_heap(context, heap, output, self.iter(), false)
+	}
+}
+
+impl<T> EncodeSized for BTreeSet<T> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<T: EncodeSized, const N: usize> EncodeSized for [T; N] {
+	const ENCODED_SIZE: u32 = N as u32 * T::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>, const N: usize> Encode<C> for [T; N] {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.as_slice().encode(context, output)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>, const N: usize> EncodeOnHeap<C> for [T; N] {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let mut len = 0;
+		for t in self {
+			len += t.encode_on_heap(context, heap, output)?;
+		}
+		Ok(len)
+	}
+}
+
+// Sizes of nested types are computed at compile time.
+const _: () = assert!(<[Option<(u32, u32)>; 4]>::ENCODED_SIZE == 4 * (1 + 8));
+const _: () = assert!(<Option<[Option<u8>; 0]>>::ENCODED_SIZE == 1);
+const _: () = assert!(<Option<Vec<u32>>>::ENCODED_SIZE == heap::Entry::ENCODED_SIZE);
+const _: () = assert!(<Option<BTreeSet<u32>>>::ENCODED_SIZE == 1 + heap::Entry::ENCODED_SIZE);
+
+macro_rules! encode_tuple {
+	($(($($t:ident : $i:tt),*)),*) => {
+		$(
+			impl<$($t: EncodeSized),*> EncodeSized for ($($t,)*) {
+				const ENCODED_SIZE: u32 = 0 $(+ $t::ENCODED_SIZE)*;
+			}
+
+			impl<C, $($t: Encode<C>),*> Encode<C> for ($($t,)*) {
+				fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+					let mut len = 0;
+					$(len += self.$i.encode(context, output)?;)*
+					Ok(len)
+				}
+			}
+
+			impl<C, $($t: EncodeOnHeap<C>),*> EncodeOnHeap<C> for ($($t,)*) {
+				fn encode_on_heap<W: Output>(&self, context: &C, heap: &mut Heap<W::ByteOrder>, output: &mut W) -> io::Result<u32> {
+					let mut len = 0;
+					$(len += self.$i.encode_on_heap(context, heap, output)?;)*
+					Ok(len)
+				}
+			}
+		)*
+	};
+}
+
+encode_tuple!(
+	(T1: 0),
+	(T1: 0, T2: 1),
+	(T1: 0, T2: 1, T3: 2),
+	(T1: 0, T2: 1, T3: 2, T4: 3),
+	(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4),
+	(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5),
+	(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6),
+	(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7),
+	(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8),
+	(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8, T10: 9),
+	(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8, T10: 9, T11: 10),
+	(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8, T10: 9, T11: 10, T12: 11)
+);
+
+impl<T: EncodeSized> EncodeSized for Range<T> {
+	const ENCODED_SIZE: u32 = 2 * T::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>> Encode<C> for Range<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		Ok(self.start.encode(context, output)? + self.end.encode(context, output)?)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>> EncodeOnHeap<C> for Range<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		Ok(self.start.encode_on_heap(context, heap, output)?
+			+ self.end.encode_on_heap(context, heap, output)?)
+	}
+}
+
+impl<T: EncodeSized> EncodeSized for RangeFrom<T> {
+	const ENCODED_SIZE: u32 = T::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>> Encode<C> for RangeFrom<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.start.encode(context, output)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>> EncodeOnHeap<C> for RangeFrom<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.start.encode_on_heap(context, heap, output)
+	}
+}
+
+impl<T: EncodeSized> EncodeSized for RangeTo<T> {
+	const ENCODED_SIZE: u32 = T::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>> Encode<C> for RangeTo<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.end.encode(context, output)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>> EncodeOnHeap<C> for RangeTo<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.end.encode_on_heap(context, heap, output)
+	}
+}
+
+impl EncodeSized for RangeFull {
+	const ENCODED_SIZE: u32 = 0;
+}
+
+impl<C> Encode<C> for RangeFull {
+	fn encode(&self, _context: &C, _output: &mut impl Output) -> io::Result<u32> {
+		Ok(0)
+	}
+}
+
+impl<C> EncodeOnHeap<C> for RangeFull {
+	fn encode_on_heap<W: Output>(
+		&self,
+		_context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		_output: &mut W,
+	) -> io::Result<u32> {
+		Ok(0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		collections::VecDeque,
+		io::Cursor,
+		num::NonZeroU32,
+		ops::{RangeFrom, RangeFull, RangeTo},
+	};
+
+	use crate::{heap, reader::Cache, EncodeSized, Encoder, Heap, Reader};
+
+	#[test]
+	fn option_of_heap_value() {
+		let entries: Vec<Option<Vec<u32>>> = (0..40)
+			.map(|i| (i % 3 != 0).then(|| (0..i).collect()))
+			.collect();
+
+		let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+		let mut heap = Heap::new();
+		let section = encoder
+			.section_from_iter(&mut heap, entries.iter())
+			.unwrap();
+		let heap = encoder.add_heap(heap).unwrap();
+		let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+		assert_eq!(reader.decode_all(section, &mut (), heap).unwrap(), entries);
+
+		let cache = Cache::default();
+		for i in [0, 1, 3, 38, 39] {
+			let entry = reader.get(section, &cache, &mut (), heap, i).unwrap();
+			assert_eq!(entry.as_deref(), Some(&entries[i as usize]));
+		}
+	}
+
+	#[test]
+	fn wrapped_vec_deque() {
+		let mut value = VecDeque::with_capacity(8);
+		value.extend([3u32, 4, 5]);
+		value.push_front(2);
+		value.push_front(1);
+		assert!(!value.as_slices().1.is_empty());
+
+		let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+		let mut heap = Heap::new();
+		let entries = [value.clone(), VecDeque::new(), value];
+		let section = encoder
+			.section_from_iter(&mut heap, entries.iter())
+			.unwrap();
+		let heap = encoder.add_heap(heap).unwrap();
+		let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+		assert_eq!(reader.decode_all(section, &mut (), heap).unwrap(), entries);
+	}
+
+	#[test]
+	fn niche_options() {
+		assert_eq!(Option::<NonZeroU32>::ENCODED_SIZE, 4);
+		assert_eq!(Option::<String>::ENCODED_SIZE, heap::Entry::ENCODED_SIZE);
+
+		let numbers: Vec<Option<NonZeroU32>> = (0..40).map(NonZeroU32::new).collect();
+		let strings: Vec<Option<String>> = (0..40)
+			.map(|i| (i % 3 != 0).then(|| "s".repeat(i % 5)))
+			.collect();
+
+		let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+		let mut heap = Heap::new();
+		let numbers_section = encoder
+			.section_from_iter(&mut heap, numbers.iter())
+			.unwrap();
+		let strings_section = encoder
+			.section_from_iter(&mut heap, strings.iter())
+			.unwrap();
+		let heap = encoder.add_heap(heap).unwrap();
+		let bytes = encoder.end().into_inner();
+
+		// No discriminant: `None` is the zero niche, followed by `Some(1)`.
+		assert_eq!(bytes[0..8], [0, 0, 0, 0, 0, 0, 0, 1]);
+		assert_eq!(numbers_section.page_count(64), 3);
+		assert_eq!(strings_section.page_count(64), 5);
+
+		let reader = Reader::new(Cursor::new(bytes), 64, 0);
+		assert_eq!(
+			reader.decode_all(numbers_section, &mut (), heap).unwrap(),
+			numbers
+		);
+		assert_eq!(
+			reader.decode_all(strings_section, &mut (), heap).unwrap(),
+			strings
+		);
+	}
+
+	#[test]
+	fn nested_sizes() {
+		type Nested = [Option<(u32, u32)>; 4];
+		assert_eq!(Nested::ENCODED_SIZE, 36);
+		assert_eq!(<(u8, [u16; 3], Option<u64>)>::ENCODED_SIZE, 1 + 6 + 9);
+		assert_eq!(<[[Option<bool>; 2]; 3]>::ENCODED_SIZE, 6);
+		assert_eq!(<Option<[Option<u8>; 0]>>::ENCODED_SIZE, 1);
+
+		let entries: Vec<Nested> = (0..10)
+			.map(|i| [None, Some((i, i + 1)), Some((u32::MAX, i)), None])
+			.collect();
+
+		let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+		let mut heap = Heap::new();
+		let section = encoder
+			.section_from_iter(&mut heap, entries.iter())
+			.unwrap();
+		let heap = encoder.add_heap(heap).unwrap();
+		let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+		assert_eq!(section.page_count(64), 10);
+		assert_eq!(reader.decode_all(section, &mut (), heap).unwrap(), entries);
+	}
+
+	#[test]
+	fn open_ranges() {
+		assert_eq!(RangeFrom::<u32>::ENCODED_SIZE, 4);
+		assert_eq!(RangeTo::<u64>::ENCODED_SIZE, 8);
+		assert_eq!(RangeFull::ENCODED_SIZE, 0);
+
+		let from: Vec<RangeFrom<u32>> = (0..20).map(|i| (i * 7)..).collect();
+		let to: Vec<RangeTo<u64>> = (0..20).map(|i| ..(u64::MAX - i)).collect();
+		let full = vec![..; 20];
+
+		let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+		let mut heap = Heap::new();
+		let from_section = encoder.section_from_iter(&mut heap, from.iter()).unwrap();
+		let to_section = encoder.section_from_iter(&mut heap, to.iter()).unwrap();
+		let full_section = encoder.section_from_iter(&mut heap, full.iter()).unwrap();
+		let heap = encoder.add_heap(heap).unwrap();
+		let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+		assert_eq!(
+			reader.decode_all(from_section, &mut (), heap).unwrap(),
+			from
+		);
+		assert_eq!(reader.decode_all(to_section, &mut (), heap).unwrap(), to);
+		assert_eq!(
+			reader.decode_all(full_section, &mut (), heap).unwrap(),
+			full
+		);
+	}
+
+	#[test]
+	fn float_bit_patterns() {
+		assert_eq!(f32::ENCODED_SIZE, 4);
+		assert_eq!(f64::ENCODED_SIZE, 8);
+
+		let singles = [
+			f32::NAN,
+			-f32::NAN,
+			f32::from_bits(0x7fc0_0001),
+			f32::INFINITY,
+			f32::NEG_INFINITY,
+			-0.0,
+			0.0,
+			f32::MIN_POSITIVE,
+		];
+		let doubles = [
+			f64::NAN,
+			-f64::NAN,
+			f64::from_bits(0x7ff8_0000_0000_0001),
+			f64::INFINITY,
+			f64::NEG_INFINITY,
+			-0.0,
+			0.0,
+			f64::MAX,
+		];
+
+		let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+		let mut heap = Heap::new();
+		let singles_section = encoder
+			.section_from_iter(&mut heap, singles.iter())
+			.unwrap();
+		let doubles_section = encoder
+			.section_from_iter(&mut heap, doubles.iter())
+			.unwrap();
+		let heap = encoder.add_heap(heap).unwrap();
+		let bytes = encoder.end().into_inner();
+		assert_eq!(bytes[0..4], f32::NAN.to_be_bytes());
+
+		let reader = Reader::new(Cursor::new(bytes), 64, 0);
+		let decoded = reader.decode_all(singles_section, &mut (), heap).unwrap();
+		assert_eq!(
+			decoded.iter().map(|f| f.to_bits()).collect::<Vec<_>>(),
+			singles.map(f32::to_bits)
+		);
+		let decoded = reader.decode_all(doubles_section, &mut (), heap).unwrap();
+		assert_eq!(
+			decoded.iter().map(|f| f.to_bits()).collect::<Vec<_>>(),
+			doubles.map(f64::to_bits)
+		);
 	}
 }
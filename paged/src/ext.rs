@@ -0,0 +1,19 @@
+//! Implementations for types of external crates.
+#[cfg(feature = "arrayvec")]
+mod arrayvec;
+#[cfg(feature = "bstr")]
+mod bstr;
+#[cfg(feature = "bumpalo")]
+mod bumpalo;
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "either")]
+mod either;
+#[cfg(feature = "glam")]
+mod glam;
+#[cfg(feature = "ordered-float")]
+mod ordered_float;
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal;
+#[cfg(feature = "smallvec")]
+mod smallvec;
@@ -0,0 +1,50 @@
+use std::io;
+
+use arrayvec::ArrayVec;
+
+use crate::utils::{ByteOrder, Output};
+use crate::{
+	heap, reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap, HeapSection,
+};
+
+impl<T, const CAP: usize> EncodeSized for ArrayVec<T, CAP> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>, const CAP: usize> EncodeOnHeap<C> for ArrayVec<T, CAP> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let entry = heap
+			.insert(context, self.as_slice())?
+			.sized(self.len() as u32);
+		entry.encode(context, output)
+	}
+}
+
+/// Fails with [`io::ErrorKind::InvalidData`] if the encoded length exceeds the
+/// capacity of the array.
+impl<C, T: Decode<C>, const CAP: usize> DecodeFromHeap<C> for ArrayVec<T, CAP> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+
+		if entry.len as usize > CAP {
+			return Err(io::ErrorKind::InvalidData.into());
+		}
+
+		let mut result = ArrayVec::new();
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			result.push(t);
+			Ok(())
+		})?;
+		Ok(result)
+	}
+}
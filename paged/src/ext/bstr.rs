@@ -0,0 +1,40 @@
+use std::io;
+
+use bstr::BString;
+
+use crate::utils::{ByteOrder, Output};
+use crate::{
+	heap, reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap, HeapSection,
+};
+
+impl EncodeSized for BString {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<C> EncodeOnHeap<C> for BString {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let bytes: &[u8] = self.as_ref();
+		let entry = heap.insert(context, bytes)?.sized(bytes.len() as u32);
+		entry.encode(context, output)
+	}
+}
+
+/// Bytes are decoded as is, without UTF-8 validation.
+impl<C> DecodeFromHeap<C> for BString {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut bytes = vec![0u8; entry.len as usize];
+		input.read_from_heap(heap, entry.offset, bytes.as_mut_slice())?;
+		Ok(BString::from(bytes))
+	}
+}
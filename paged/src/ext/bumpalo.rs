@@ -0,0 +1,81 @@
+//! Arena-allocated strings and vectors.
+//!
+//! The arena is passed as decoding context, so that heap-backed values
+//! allocate from it instead of the global allocator.
+use std::io;
+
+use bumpalo::{
+	collections::{String, Vec},
+	Bump,
+};
+
+use crate::utils::{ByteOrder, Output};
+use crate::{
+	encode_string_on_heap, heap, reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized,
+	Heap, HeapSection,
+};
+
+impl<'b> EncodeSized for String<'b> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<'b, C> EncodeOnHeap<C> for String<'b> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		_context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		encode_string_on_heap(heap, output, self.as_str())
+	}
+}
+
+impl<'b> DecodeFromHeap<&'b Bump> for String<'b> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut &'b Bump,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut bytes = Vec::with_capacity_in(entry.len as usize, context);
+		bytes.resize(entry.len as usize, 0u8);
+		input.read_from_heap(heap, entry.offset, bytes.as_mut_slice())?;
+		String::from_utf8(bytes).map_err(|_| io::ErrorKind::InvalidData.into())
+	}
+}
+
+impl<'b, T> EncodeSized for Vec<'b, T> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<'b, C, T: Encode<C>> EncodeOnHeap<C> for Vec<'b, T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let entry = heap
+			.insert(context, self.as_slice())?
+			.sized(self.len() as u32);
+		entry.encode(context, output)
+	}
+}
+
+impl<'b, T: Decode<&'b Bump>> DecodeFromHeap<&'b Bump> for Vec<'b, T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut &'b Bump,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut result = Vec::with_capacity_in(entry.len as usize, context);
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			result.push(t);
+			Ok(())
+		})?;
+		Ok(result)
+	}
+}
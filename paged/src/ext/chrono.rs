@@ -0,0 +1,90 @@
+//! Date and time types.
+//!
+//! Points in time are encoded as a signed number of seconds since the Unix
+//! epoch followed by the sub-second nanoseconds, and dates as a signed
+//! number of days since January 1st of year 1 (CE). Values out of the range
+//! supported by `chrono` are rejected on decode with
+//! [`io::ErrorKind::InvalidData`].
+use std::io;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::utils::{ByteOrder, Input, Output};
+use crate::{reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap, HeapSection};
+
+impl EncodeSized for DateTime<Utc> {
+	const ENCODED_SIZE: u32 = i64::ENCODED_SIZE + u32::ENCODED_SIZE;
+}
+
+impl<C> Encode<C> for DateTime<Utc> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.timestamp().encode(context, output)?;
+		self.timestamp_subsec_nanos().encode(context, output)?;
+		Ok(Self::ENCODED_SIZE)
+	}
+}
+
+impl<C> Decode<C> for DateTime<Utc> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		let secs = i64::decode(input, context)?;
+		let nanos = u32::decode(input, context)?;
+		DateTime::from_timestamp(secs, nanos).ok_or_else(|| io::ErrorKind::InvalidData.into())
+	}
+}
+
+impl EncodeSized for NaiveDateTime {
+	const ENCODED_SIZE: u32 = DateTime::<Utc>::ENCODED_SIZE;
+}
+
+impl<C> Encode<C> for NaiveDateTime {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.and_utc().encode(context, output)
+	}
+}
+
+impl<C> Decode<C> for NaiveDateTime {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		DateTime::<Utc>::decode(input, context).map(|t| t.naive_utc())
+	}
+}
+
+impl EncodeSized for NaiveDate {
+	const ENCODED_SIZE: u32 = i32::ENCODED_SIZE;
+}
+
+impl<C> Encode<C> for NaiveDate {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		chrono::Datelike::num_days_from_ce(self).encode(context, output)
+	}
+}
+
+impl<C> Decode<C> for NaiveDate {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		NaiveDate::from_num_days_from_ce_opt(i32::decode(input, context)?)
+			.ok_or_else(|| io::ErrorKind::InvalidData.into())
+	}
+}
+
+macro_rules! sized_heap_impls {
+	($($ty:ty),*) => {
+		$(
+			impl<C> EncodeOnHeap<C> for $ty {
+				fn encode_on_heap<W: Output>(&self, context: &C, _heap: &mut Heap<W::ByteOrder>, output: &mut W) -> io::Result<u32> {
+					self.encode(context, output)
+				}
+			}
+
+			impl<C> DecodeFromHeap<C> for $ty {
+				fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+					input: &mut reader::Cursor<R, O>,
+					context: &mut C,
+					_heap: HeapSection
+				) -> io::Result<Self> {
+					Self::decode(input, context)
+				}
+			}
+		)*
+	};
+}
+
+sized_heap_impls!(DateTime<Utc>, NaiveDateTime, NaiveDate);
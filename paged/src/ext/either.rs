@@ -0,0 +1,84 @@
+use std::io;
+
+use either::Either;
+
+use crate::utils::{ByteOrder, Input, Output};
+use crate::{
+	decode, encode, reader, utils, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap,
+	HeapSection,
+};
+
+/// Encoded as a one byte tag (`0` for `Left`, `1` for `Right`) followed by
+/// the value, padded to the size of the largest side.
+impl<L: EncodeSized, R: EncodeSized> EncodeSized for Either<L, R> {
+	const ENCODED_SIZE: u32 = 1 + utils::max(L::ENCODED_SIZE, R::ENCODED_SIZE);
+}
+
+impl<C, L: EncodeSized + Encode<C>, R: EncodeSized + Encode<C>> Encode<C> for Either<L, R> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		let len = match self {
+			Self::Left(l) => 0u8.encode(context, output)? + l.encode(context, output)?,
+			Self::Right(r) => 1u8.encode(context, output)? + r.encode(context, output)?,
+		};
+
+		Ok(len + encode::pad(output, Self::ENCODED_SIZE - len)?)
+	}
+}
+
+impl<C, L: EncodeOnHeap<C>, R: EncodeOnHeap<C>> EncodeOnHeap<C> for Either<L, R> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let len = match self {
+			Self::Left(l) => {
+				0u8.encode(context, output)? + l.encode_on_heap(context, heap, output)?
+			}
+			Self::Right(r) => {
+				1u8.encode(context, output)? + r.encode_on_heap(context, heap, output)?
+			}
+		};
+
+		Ok(len + encode::pad(output, Self::ENCODED_SIZE - len)?)
+	}
+}
+
+impl<C, L: EncodeSized + Decode<C>, R: EncodeSized + Decode<C>> Decode<C> for Either<L, R> {
+	fn decode<I: Input>(input: &mut I, context: &mut C) -> io::Result<Self> {
+		let (result, len) = match u8::decode(input, context)? {
+			0 => (Self::Left(L::decode(input, context)?), L::ENCODED_SIZE),
+			1 => (Self::Right(R::decode(input, context)?), R::ENCODED_SIZE),
+			_ => return Err(io::ErrorKind::InvalidData.into()),
+		};
+
+		decode::pad(input, Self::ENCODED_SIZE - 1 - len)?;
+		Ok(result)
+	}
+}
+
+impl<C, L: EncodeSized + DecodeFromHeap<C>, R: EncodeSized + DecodeFromHeap<C>> DecodeFromHeap<C>
+	for Either<L, R>
+{
+	fn decode_from_heap<I: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<I, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let (result, len) = match u8::decode(input, context)? {
+			0 => (
+				Self::Left(L::decode_from_heap(input, context, heap)?),
+				L::ENCODED_SIZE,
+			),
+			1 => (
+				Self::Right(R::decode_from_heap(input, context, heap)?),
+				R::ENCODED_SIZE,
+			),
+			_ => return Err(io::ErrorKind::InvalidData.into()),
+		};
+
+		input.pad(Self::ENCODED_SIZE - 1 - len)?;
+		Ok(result)
+	}
+}
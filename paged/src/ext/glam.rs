@@ -0,0 +1,65 @@
+use std::io;
+
+use glam::{Mat2, Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+
+use crate::utils::{ByteOrder, Input, Output};
+use crate::{reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap, HeapSection};
+
+/// Implements the encoding traits for a `glam` type by encoding its `f32`
+/// components in order.
+macro_rules! glam_impls {
+	($($ty:ty [$len:literal] : $to:ident, $from:ident),*) => {
+		$(
+			impl EncodeSized for $ty {
+				const ENCODED_SIZE: u32 = $len * f32::ENCODED_SIZE;
+			}
+
+			impl<C> Encode<C> for $ty {
+				fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+					for c in self.$to() {
+						c.encode(context, output)?;
+					}
+
+					Ok(Self::ENCODED_SIZE)
+				}
+			}
+
+			impl<C> EncodeOnHeap<C> for $ty {
+				fn encode_on_heap<W: Output>(&self, context: &C, _heap: &mut Heap<W::ByteOrder>, output: &mut W) -> io::Result<u32> {
+					self.encode(context, output)
+				}
+			}
+
+			impl<C> Decode<C> for $ty {
+				fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+					let mut components = [0f32; $len];
+					for c in &mut components {
+						*c = f32::decode(input, context)?;
+					}
+
+					Ok(<$ty>::$from(&components))
+				}
+			}
+
+			impl<C> DecodeFromHeap<C> for $ty {
+				fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+					input: &mut reader::Cursor<R, O>,
+					context: &mut C,
+					_heap: HeapSection
+				) -> io::Result<Self> {
+					Self::decode(input, context)
+				}
+			}
+		)*
+	};
+}
+
+glam_impls! {
+	Vec2[2]: to_array, from_slice,
+	Vec3[3]: to_array, from_slice,
+	Vec4[4]: to_array, from_slice,
+	Quat[4]: to_array, from_slice,
+	Mat2[4]: to_cols_array, from_cols_slice,
+	Mat3[9]: to_cols_array, from_cols_slice,
+	Mat4[16]: to_cols_array, from_cols_slice
+}
@@ -0,0 +1,67 @@
+//! Ordered floats are encoded so that comparing their encoded bytes matches
+//! the [`OrderedFloat`] ordering, which allows searching sorted sections
+//! without decoding.
+//!
+//! The bits of negative numbers are all flipped, and the sign bit of
+//! positive numbers is set. All NaN values are encoded as the canonical
+//! positive NaN, so they sort after every other number. Negative zero
+//! sorts right before positive zero.
+use std::io;
+
+use ordered_float::OrderedFloat;
+
+use crate::utils::{ByteOrder, Input, Output};
+use crate::{reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap, HeapSection};
+
+macro_rules! ordered_float_impls {
+	($($ty:ty : $bits:ty),*) => {
+		$(
+			impl EncodeSized for OrderedFloat<$ty> {
+				const ENCODED_SIZE: u32 = std::mem::size_of::<$bits>() as u32;
+			}
+
+			impl<C> Encode<C> for OrderedFloat<$ty> {
+				fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+					const SIGN: $bits = 1 << (<$bits>::BITS - 1);
+
+					let bits = if self.0.is_nan() {
+						<$ty>::NAN.to_bits()
+					} else {
+						self.0.to_bits()
+					};
+
+					let ordered = if bits & SIGN == 0 { bits | SIGN } else { !bits };
+					ordered.encode(context, output)
+				}
+			}
+
+			impl<C> EncodeOnHeap<C> for OrderedFloat<$ty> {
+				fn encode_on_heap<W: Output>(&self, context: &C, _heap: &mut Heap<W::ByteOrder>, output: &mut W) -> io::Result<u32> {
+					self.encode(context, output)
+				}
+			}
+
+			impl<C> Decode<C> for OrderedFloat<$ty> {
+				fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+					const SIGN: $bits = 1 << (<$bits>::BITS - 1);
+
+					let ordered = <$bits>::decode(input, context)?;
+					let bits = if ordered & SIGN == 0 { !ordered } else { ordered & !SIGN };
+					Ok(OrderedFloat(<$ty>::from_bits(bits)))
+				}
+			}
+
+			impl<C> DecodeFromHeap<C> for OrderedFloat<$ty> {
+				fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+					input: &mut reader::Cursor<R, O>,
+					context: &mut C,
+					_heap: HeapSection
+				) -> io::Result<Self> {
+					Self::decode(input, context)
+				}
+			}
+		)*
+	};
+}
+
+ordered_float_impls!(f32: u32, f64: u64);
@@ -0,0 +1,62 @@
+//! Decimals are encoded losslessly using their 16 bytes serialized
+//! representation.
+use std::io;
+
+use rust_decimal::Decimal;
+
+use crate::utils::{ByteOrder, Input, Output};
+use crate::{reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap, HeapSection};
+
+/// Flag bits in use: the sign bit and the scale.
+const FLAGS_MASK: u32 = 0x801f_0000;
+
+/// Maximum scale of a decimal.
+const MAX_SCALE: u32 = 28;
+
+impl EncodeSized for Decimal {
+	const ENCODED_SIZE: u32 = 16;
+}
+
+impl<C> Encode<C> for Decimal {
+	fn encode(&self, _context: &C, output: &mut impl Output) -> io::Result<u32> {
+		output.write_all(&self.serialize())?;
+		Ok(Self::ENCODED_SIZE)
+	}
+}
+
+impl<C> EncodeOnHeap<C> for Decimal {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.encode(context, output)
+	}
+}
+
+/// Fails with [`io::ErrorKind::InvalidData`] if the flags of the serialized
+/// representation are invalid.
+impl<C> Decode<C> for Decimal {
+	fn decode<R: Input>(input: &mut R, _context: &mut C) -> io::Result<Self> {
+		let mut bytes = [0u8; 16];
+		input.read_exact(&mut bytes)?;
+
+		let flags = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+		if flags & !FLAGS_MASK != 0 || (flags >> 16) & 0xff > MAX_SCALE {
+			return Err(io::ErrorKind::InvalidData.into());
+		}
+
+		Ok(Decimal::deserialize(bytes))
+	}
+}
+
+impl<C> DecodeFromHeap<C> for Decimal {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
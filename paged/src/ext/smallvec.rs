@@ -0,0 +1,49 @@
+use std::io;
+
+use smallvec::{Array, SmallVec};
+
+use crate::utils::{ByteOrder, Output};
+use crate::{
+	heap, reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap, HeapSection,
+};
+
+impl<A: Array> EncodeSized for SmallVec<A> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<C, A: Array> EncodeOnHeap<C> for SmallVec<A>
+where
+	A::Item: Encode<C>,
+{
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let entry = heap
+			.insert(context, self.as_slice())?
+			.sized(self.len() as u32);
+		entry.encode(context, output)
+	}
+}
+
+impl<C, A: Array> DecodeFromHeap<C> for SmallVec<A>
+where
+	A::Item: Decode<C>,
+{
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut result = SmallVec::with_capacity(entry.len as usize);
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			result.push(t);
+			Ok(())
+		})?;
+		Ok(result)
+	}
+}
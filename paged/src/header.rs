@@ -0,0 +1,86 @@
+//! Standard file header.
+//!
+//! Files written with [`Encoder::with_header`](crate::Encoder::with_header)
+//! start with this header, recording the page length and byte order of the
+//! file. They can then be read with [`Reader::open`](crate::Reader::open)
+//! without knowing how they were written.
+use std::io;
+
+use crate::utils::{self, BigEndian, Endianness, LittleEndian};
+
+/// Bytes every header starts with.
+pub const MAGIC: [u8; 4] = *b"PAGD";
+
+/// Byte length of the header, which is also the offset of the first page.
+pub const LEN: u32 = 10;
+
+/// Byte order mark, encoded with the byte order of the file.
+const BYTE_ORDER_MARK: u16 = 0xfeff;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+	pub page_len: u32,
+	pub byte_order: Endianness,
+}
+
+impl Header {
+	/// Writes the header.
+	pub fn write(&self, output: &mut impl io::Write) -> io::Result<()> {
+		output.write_all(&MAGIC)?;
+		match self.byte_order {
+			Endianness::Big => {
+				utils::encode_int_with::<BigEndian, _>(BYTE_ORDER_MARK, output)?;
+				utils::encode_int_with::<BigEndian, _>(self.page_len, output)?;
+			}
+			Endianness::Little => {
+				utils::encode_int_with::<LittleEndian, _>(BYTE_ORDER_MARK, output)?;
+				utils::encode_int_with::<LittleEndian, _>(self.page_len, output)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Reads a header.
+	///
+	/// Fails with [`io::ErrorKind::InvalidData`] if the input does not start
+	/// with a valid header.
+	pub fn read(input: &mut impl io::Read) -> io::Result<Self> {
+		let mut magic = [0u8; 4];
+		input.read_exact(&mut magic)?;
+		if magic != MAGIC {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"missing paged file header",
+			));
+		}
+
+		let mut mark = [0u8; 2];
+		input.read_exact(&mut mark)?;
+		let byte_order = if mark == BYTE_ORDER_MARK.to_be_bytes() {
+			Endianness::Big
+		} else if mark == BYTE_ORDER_MARK.to_le_bytes() {
+			Endianness::Little
+		} else {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"invalid byte order mark",
+			));
+		};
+
+		let page_len = match byte_order {
+			Endianness::Big => utils::decode_int_with::<BigEndian, u32>(input)?,
+			Endianness::Little => utils::decode_int_with::<LittleEndian, u32>(input)?,
+		};
+		if page_len == 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"invalid page length",
+			));
+		}
+
+		Ok(Self {
+			page_len,
+			byte_order,
+		})
+	}
+}
@@ -1,18 +1,52 @@
-use std::io;
+use std::{
+	collections::{hash_map::DefaultHasher, HashMap},
+	hash::{Hash, Hasher},
+	io,
+	marker::PhantomData,
+};
 
 use crate::{
 	encode::{Encode, EncodeSized},
 	reader,
-	utils::CeilingDiv,
+	utils::{
+		self, BigEndian, ByteOrder, ByteOrdered, CeilingDiv, Checksum, Codec, Endianness, Input,
+		LittleEndian, Ordered, Output,
+	},
 	Decode, DecodeFromHeap, EncodeOnHeap,
 };
 
-#[derive(Default)]
-pub struct Heap {
+/// Hash of the given bytes, used to index deduplicated values.
+fn dedup_hash(bytes: &[u8]) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	bytes.hash(&mut hasher);
+	hasher.finish()
+}
+
+/// Heap data, with numbers encoded with the byte order `O`.
+///
+/// The byte order must be the one of the [`Encoder`](crate::Encoder) the
+/// heap is written with.
+pub struct Heap<O = BigEndian> {
 	data: Vec<u8>,
+
+	/// Values inserted with [`Heap::insert_dedup`], indexed by the hash of
+	/// their bytes.
+	dedup_index: HashMap<u64, Vec<Entry>>,
+
+	byte_order: PhantomData<O>,
+}
+
+impl<O> Default for Heap<O> {
+	fn default() -> Self {
+		Self {
+			data: Vec::new(),
+			dedup_index: HashMap::new(),
+			byte_order: PhantomData,
+		}
+	}
 }
 
-impl Heap {
+impl<O: ByteOrder> Heap<O> {
 	pub fn new() -> Self {
 		Self::default()
 	}
@@ -35,13 +69,137 @@ impl Heap {
 		value: &(impl ?Sized + Encode<C>),
 	) -> io::Result<Offset> {
 		let offset = Offset(self.data.len() as u32);
-		let mut writer = Writer {
-			data: &mut self.data,
-		};
+		let mut writer = Writer::<O>::new(&mut self.data);
+		value.encode(context, &mut writer)?;
+		Ok(offset)
+	}
+
+	/// Inserts a value in the heap, reusing the data of a previously
+	/// deduplicated value with the same encoding if any.
+	///
+	/// Only the hash of the encoded bytes is stored, not a copy of the bytes.
+	/// Values with the same hash are compared against the actual heap data so
+	/// that hash collisions never merge distinct values.
+	pub fn insert_dedup<C>(
+		&mut self,
+		context: &C,
+		value: &(impl ?Sized + Encode<C>),
+	) -> io::Result<Offset> {
+		let offset = self.insert(context, value)?;
+		let bytes = &self.data[offset.0 as usize..];
+		let candidates = self.dedup_index.entry(dedup_hash(bytes)).or_default();
+
+		let existing = candidates.iter().find(|e| {
+			e.len as usize == bytes.len()
+				&& &self.data[e.offset.0 as usize..(e.offset.0 + e.len) as usize] == bytes
+		});
+
+		match existing {
+			Some(e) => {
+				let existing_offset = e.offset;
+				self.data.truncate(offset.0 as usize);
+				Ok(existing_offset)
+			}
+			None => {
+				candidates.push(offset.sized(bytes.len() as u32));
+				Ok(offset)
+			}
+		}
+	}
+
+	/// Inserts a value in the heap, preceded by the given length encoded as
+	/// a LEB128 varint.
+	///
+	/// The value can then be referenced using a [`VarEntry`], only storing
+	/// the offset, and its length read back with
+	/// [`reader::Cursor::read_var_entry`].
+	pub fn insert_with_len<C>(
+		&mut self,
+		context: &C,
+		value: &(impl ?Sized + Encode<C>),
+		len: u32,
+	) -> io::Result<VarEntry> {
+		let offset = Offset(self.data.len() as u32);
+		let mut writer = Writer::<O>::new(&mut self.data);
+		utils::encode_varint(len as u64, &mut writer)?;
 		value.encode(context, &mut writer)?;
+		Ok(VarEntry(offset))
+	}
+
+	/// Inserts a fixed-size value in the heap.
+	///
+	/// Since the length of the value is known from its type, it can be
+	/// referenced using only the returned [`Offset`] instead of a full
+	/// [`Entry`], and read back with [`reader::Cursor::decode_from_heap_at`].
+	pub fn insert_sized<C, T: EncodeSized + Encode<C>>(
+		&mut self,
+		context: &C,
+		value: &T,
+	) -> io::Result<Offset> {
+		let offset = self.insert(context, value)?;
+		debug_assert_eq!(self.len() - offset.0, T::ENCODED_SIZE);
 		Ok(offset)
 	}
 
+	/// Removes every byte of the heap not covered by the given entries.
+	///
+	/// Entry lengths are interpreted as byte lengths: the entry of a heap
+	/// vector must be given with the byte length of its encoded elements.
+	/// Kept data is moved toward the start of the heap, preserving its order.
+	/// Returns a map from the old offset of each live entry to its new offset.
+	///
+	/// Values inserted with [`Heap::insert_dedup`] before compaction are not
+	/// deduplicated against anymore.
+	///
+	/// Fails with [`io::ErrorKind::InvalidInput`], leaving the heap
+	/// untouched, if an entry is not within the heap (such as
+	/// [`Entry::NONE`]).
+	pub fn compact(&mut self, live_entries: &[Entry]) -> io::Result<HashMap<Offset, Offset>> {
+		let mut ranges = Vec::with_capacity(live_entries.len());
+		for e in live_entries {
+			match e.offset.0.checked_add(e.len) {
+				Some(end) if end <= self.len() => ranges.push((e.offset.0, end)),
+				_ => {
+					return Err(io::Error::new(
+						io::ErrorKind::InvalidInput,
+						"heap entry out of bounds",
+					))
+				}
+			}
+		}
+		ranges.sort_unstable();
+
+		let mut data = Vec::new();
+		let mut spans: Vec<(u32, u32, u32)> = Vec::new();
+		for (start, end) in ranges {
+			match spans.last_mut() {
+				Some((_, last_end, _)) if start <= *last_end => {
+					if end > *last_end {
+						data.extend_from_slice(&self.data[*last_end as usize..end as usize]);
+						*last_end = end
+					}
+				}
+				_ => {
+					spans.push((start, end, data.len() as u32));
+					data.extend_from_slice(&self.data[start as usize..end as usize])
+				}
+			}
+		}
+
+		self.data = data;
+		self.dedup_index.clear();
+
+		// Every entry starts within a span, since spans cover every entry.
+		let mut remap = HashMap::new();
+		for e in live_entries {
+			let i = spans.partition_point(|(start, _, _)| *start <= e.offset.0) - 1;
+			let (start, _, new_start) = spans[i];
+			remap.insert(e.offset, Offset(new_start + e.offset.0 - start));
+		}
+
+		Ok(remap)
+	}
+
 	pub fn page_count(&self, page_len: u32) -> u32 {
 		self.len().ceiling_div(page_len)
 	}
@@ -56,7 +214,73 @@ impl Heap {
 	}
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Function packing heap data of a file with the given byte order (see
+/// [`pack`]).
+pub(crate) type Pack = fn(&[u8], Endianness) -> io::Result<Vec<u8>>;
+
+/// Function reading and unpacking heap data of a file with the given byte
+/// order (see [`unpack`]).
+pub(crate) type Unpack = fn(&mut dyn io::Read, Endianness) -> io::Result<Vec<u8>>;
+
+/// Packs the given heap data, compressing it with `K`.
+///
+/// The packed heap starts with the length of the compressed data and its
+/// checksum computed with `H`, followed by the compressed data.
+pub(crate) fn pack<K: Codec, H: Checksum>(
+	bytes: &[u8],
+	byte_order: Endianness,
+) -> io::Result<Vec<u8>> {
+	match byte_order {
+		Endianness::Big => pack_with::<K, H, BigEndian>(bytes),
+		Endianness::Little => pack_with::<K, H, LittleEndian>(bytes),
+	}
+}
+
+fn pack_with<K: Codec, H: Checksum, O: ByteOrder>(bytes: &[u8]) -> io::Result<Vec<u8>> {
+	let compressed = K::compress(bytes)?;
+	let len = u32::try_from(compressed.len()).map_err(|_| crate::too_large_error())?;
+
+	let mut packed = Ordered::<_, O>::new(Vec::new());
+	len.encode(&(), &mut packed)?;
+	H::checksum(&compressed).encode(&(), &mut packed)?;
+	let mut packed = packed.into_inner();
+	packed.extend_from_slice(&compressed);
+	Ok(packed)
+}
+
+/// Reads and unpacks a heap packed by [`pack`].
+///
+/// Fails with [`io::ErrorKind::InvalidData`] if the checksum does not match.
+pub(crate) fn unpack<K: Codec, H: Checksum>(
+	input: &mut dyn io::Read,
+	byte_order: Endianness,
+) -> io::Result<Vec<u8>> {
+	match byte_order {
+		Endianness::Big => unpack_with::<K, H, BigEndian>(input),
+		Endianness::Little => unpack_with::<K, H, LittleEndian>(input),
+	}
+}
+
+fn unpack_with<K: Codec, H: Checksum, O: ByteOrder>(
+	input: &mut dyn io::Read,
+) -> io::Result<Vec<u8>> {
+	let mut input = Ordered::<_, O>::new(input);
+	let len = u32::decode(&mut input, &mut ())?;
+	let checksum = H::Output::decode(&mut input, &mut ())?;
+	let mut compressed = vec![0u8; len as usize];
+	io::Read::read_exact(&mut input, &mut compressed)?;
+
+	if !H::verify(&compressed, &checksum) {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"heap checksum mismatch",
+		));
+	}
+
+	K::decompress(&compressed)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Offset(u32);
 
 impl Offset {
@@ -67,10 +291,15 @@ impl Offset {
 	pub fn sized(self, len: u32) -> Entry {
 		Entry { offset: self, len }
 	}
+
+	/// Returns the offset `len` bytes after this one.
+	pub(crate) fn advance(self, len: u32) -> Self {
+		Self(self.0 + len)
+	}
 }
 
 impl<C> Encode<C> for Offset {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
 		self.0.encode(context, output)
 	}
 }
@@ -80,19 +309,59 @@ impl EncodeSized for Offset {
 }
 
 impl<C> Decode<C> for Offset {
-	fn decode<R: io::Read>(input: &mut R, context: &mut C) -> io::Result<Self> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
 		Ok(Self(u32::decode(input, context)?))
 	}
 }
 
+impl<C> EncodeOnHeap<C> for Offset {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.encode(context, output)
+	}
+}
+
+impl<C> DecodeFromHeap<C> for Offset {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Entry {
 	pub offset: Offset,
 	pub len: u32,
 }
 
+impl Entry {
+	/// Sentinel entry, never referring to actual heap data.
+	///
+	/// It can be used to encode the absence of a heap value without requiring
+	/// an additional discriminant.
+	pub const NONE: Self = Self {
+		offset: Offset(u32::MAX),
+		len: 0,
+	};
+
+	/// Encoding of [`Entry::NONE`], the same in both byte orders.
+	pub(crate) const NONE_BYTES: &'static [u8] = &[0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00];
+
+	/// Checks if this is the [`Entry::NONE`] sentinel.
+	pub fn is_none(&self) -> bool {
+		self.offset.0 == u32::MAX
+	}
+}
+
 impl<C> Encode<C> for Entry {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
 		self.offset.encode(context, output)?;
 		self.len.encode(context, output)?;
 		Ok(Self::ENCODED_SIZE)
@@ -104,7 +373,7 @@ impl EncodeSized for Entry {
 }
 
 impl<C> Decode<C> for Entry {
-	fn decode<R: io::Read>(input: &mut R, context: &mut C) -> io::Result<Self> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
 		Ok(Self {
 			offset: Offset::decode(input, context)?,
 			len: u32::decode(input, context)?,
@@ -112,11 +381,91 @@ impl<C> Decode<C> for Entry {
 	}
 }
 
-pub struct Writer<'a> {
+/// Heap entry storing its length on the heap.
+///
+/// Only the offset is stored, the length being encoded as a LEB128 varint
+/// right before the data (see [`Heap::insert_with_len`]). The entry is half
+/// the size of an [`Entry`], at the cost of one heap byte for lengths under
+/// 128, which makes pages of short values denser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VarEntry(pub Offset);
+
+impl VarEntry {
+	/// Returns the entry of the data, given the length of the varint header
+	/// and the decoded length.
+	pub(crate) fn data_entry(self, header_len: u32, len: u32) -> Entry {
+		Offset(self.0 .0 + header_len).sized(len)
+	}
+
+	/// Returns the entry covering both the varint header and the data, given
+	/// the entry of the data.
+	///
+	/// This is the entry reported while decoding, so that
+	/// [`Heap::compact`] keeps the header and remaps the offset actually
+	/// stored in pages.
+	pub(crate) fn full_entry(self, data_entry: Entry) -> Entry {
+		self.0
+			.sized(data_entry.offset.0 - self.0 .0 + data_entry.len)
+	}
+}
+
+impl<C> Encode<C> for VarEntry {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.0.encode(context, output)
+	}
+}
+
+impl EncodeSized for VarEntry {
+	const ENCODED_SIZE: u32 = Offset::ENCODED_SIZE;
+}
+
+impl<C> Decode<C> for VarEntry {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		Offset::decode(input, context).map(Self)
+	}
+}
+
+impl<C> EncodeOnHeap<C> for VarEntry {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.encode(context, output)
+	}
+}
+
+impl<C> DecodeFromHeap<C> for VarEntry {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
+
+/// Writer appending to heap data, encoding numbers with the byte order `O`.
+pub struct Writer<'a, O = BigEndian> {
 	data: &'a mut Vec<u8>,
+	byte_order: PhantomData<O>,
+}
+
+impl<'a, O> Writer<'a, O> {
+	fn new(data: &'a mut Vec<u8>) -> Self {
+		Self {
+			data,
+			byte_order: PhantomData,
+		}
+	}
+}
+
+impl<'a, O: ByteOrder> ByteOrdered for Writer<'a, O> {
+	type ByteOrder = O;
 }
 
-impl<'a> io::Write for Writer<'a> {
+impl<'a, O> io::Write for Writer<'a, O> {
 	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
 		self.data.extend_from_slice(buf);
 		Ok(buf.len())
@@ -134,7 +483,7 @@ pub struct HeapSection {
 }
 
 impl<C> Encode<C> for HeapSection {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
 		self.page_offset.encode(context, output)?;
 		self.page_count.encode(context, output)?;
 		Ok(Self::ENCODED_SIZE)
@@ -142,11 +491,11 @@ impl<C> Encode<C> for HeapSection {
 }
 
 impl<C> EncodeOnHeap<C> for HeapSection {
-	fn encode_on_heap(
+	fn encode_on_heap<W: Output>(
 		&self,
 		context: &C,
-		_heap: &mut Heap,
-		output: &mut impl io::Write,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
 	) -> io::Result<u32> {
 		self.encode(context, output)
 	}
@@ -157,7 +506,7 @@ impl EncodeSized for HeapSection {
 }
 
 impl<C> Decode<C> for HeapSection {
-	fn decode<R: io::Read>(input: &mut R, context: &mut C) -> io::Result<Self> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
 		Ok(Self {
 			page_offset: u32::decode(input, context)?,
 			page_count: u32::decode(input, context)?,
@@ -166,11 +515,78 @@ impl<C> Decode<C> for HeapSection {
 }
 
 impl<C> DecodeFromHeap<C> for HeapSection {
-	fn decode_from_heap<R: io::Seek + io::Read>(
-		input: &mut reader::Cursor<R>,
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
 		context: &mut C,
 		_heap: HeapSection,
 	) -> io::Result<Self> {
 		Self::decode(input, context)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn heap_of(bytes: &[u8]) -> Heap {
+		let mut heap = Heap::new();
+		heap.insert(&(), bytes).unwrap();
+		heap
+	}
+
+	#[test]
+	fn dedup_hash_collision() {
+		let mut heap = Heap::<BigEndian>::new();
+		let a = heap.insert_dedup(&(), "abc").unwrap();
+		assert_eq!(heap.insert_dedup(&(), "abc").unwrap(), a);
+
+		// Make "abd" collide with "abc".
+		let candidates = heap.dedup_index.remove(&dedup_hash(b"abc")).unwrap();
+		heap.dedup_index.insert(dedup_hash(b"abd"), candidates);
+
+		let b = heap.insert_dedup(&(), "abd").unwrap();
+		assert_ne!(b, a);
+		assert_eq!(heap.as_bytes(), b"abcabd");
+		assert_eq!(heap.insert_dedup(&(), "abd").unwrap(), b);
+	}
+
+	#[test]
+	fn overlapping_entries() {
+		let mut heap = heap_of(b"..abcdef..ghi");
+		let a = Offset(2).sized(4);
+		let b = Offset(4).sized(4);
+		let c = Offset(10).sized(3);
+		let remap = heap.compact(&[c, b, a]).unwrap();
+
+		assert_eq!(heap.as_bytes(), b"abcdefghi");
+		assert_eq!(remap[&a.offset], Offset(0));
+		assert_eq!(remap[&b.offset], Offset(2));
+		assert_eq!(remap[&c.offset], Offset(6));
+	}
+
+	#[test]
+	fn duplicate_entries() {
+		let mut heap = heap_of(b"...abc...");
+		let a = Offset(3).sized(3);
+		let remap = heap.compact(&[a, a, Offset(3).sized(1)]).unwrap();
+
+		assert_eq!(heap.as_bytes(), b"abc");
+		assert_eq!(remap.len(), 1);
+		assert_eq!(remap[&a.offset], Offset(0));
+	}
+
+	#[test]
+	fn invalid_entries() {
+		for invalid in [
+			Entry::NONE,
+			Offset(8).sized(4),
+			Offset(20).sized(0),
+			Offset(u32::MAX).sized(2),
+		] {
+			let mut heap = heap_of(b"..abcdef..");
+			let err = heap.compact(&[Offset(2).sized(2), invalid]).unwrap_err();
+			assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+			assert_eq!(heap.as_bytes(), b"..abcdef..");
+		}
+	}
+}
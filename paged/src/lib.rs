@@ -5,7 +5,7 @@
 //! Data is organized in sections, pages and entries. Each file can include multiple sections, each section include multiple pages, and each page multiple entries. The first section/page may start at any given offset to give room to a potential file header. The file may also include one or more heap sections storing dynamically sized data.
 //!
 //! A typical paged file will look like this:
-//! ```
+//! ```text
 //! ┏━━━━━━━━━━━┓
 //! ┃  Header   ┃
 //! ┃           ┃
@@ -74,54 +74,197 @@
 //!
 //! A file may contain one or more heap sections. A heap stores dynamically sized data without any structure.
 use std::io;
-use std::ops::Deref;
+use std::marker::PhantomData;
+use std::ops::{Deref, Range};
+use utils::{BigEndian, ByteOrder, CeilingDiv, Endianness};
 
 #[cfg(feature = "derive")]
 pub use paged_derive::Paged;
 
+pub mod columnar;
 mod decode;
 mod encode;
+mod ext;
+pub mod header;
 pub mod heap;
 pub mod reader;
 pub mod section;
 pub mod utils;
 
+pub use columnar::{Columns, EncodeColumns};
 pub use decode::*;
 pub use encode::*;
 pub use heap::{Heap, HeapSection};
 pub use reader::*;
-pub use section::Section;
+pub use section::{DynamicSection, PageIndex, Section, Tombstones};
+
+/// Entry type sorted by a key.
+///
+/// Sections of such entries, when sorted by key, can be searched with
+/// [`Reader::binary_search`], or with [`Reader::binary_search_by_key`] using
+/// [`Self::paged_cmp`]. This can be derived with `#[paged(key = field)]`.
+pub trait Keyed {
+	type Key: Ord;
+
+	/// Returns the binary search key of this entry.
+	fn paged_key(&self) -> &Self::Key;
+
+	/// Compares the binary search key of this entry with the given key.
+	fn paged_cmp(&self, key: &Self::Key) -> std::cmp::Ordering {
+		self.paged_key().cmp(key)
+	}
+}
 
 pub fn no_context_mut() -> &'static mut () {
 	unsafe { std::mem::transmute(&mut ()) }
 }
 
-pub struct Encoder<W> {
+pub struct Encoder<W, O = BigEndian> {
 	output: W,
 	page_len: u32,
 	page_count: u32,
+	regions: Vec<Range<u32>>,
+	byte_order: PhantomData<O>,
+
+	/// Function packing heaps, if heaps are packed.
+	pack_heap: Option<heap::Pack>,
 }
 
 impl<W> Encoder<W> {
+	/// Creates a new encoder of a big-endian file.
+	///
+	/// Files with another byte order are written using
+	/// [`Self::with_byte_order`].
 	pub fn new(output: W, page_len: u32) -> Self {
 		Self {
 			output,
 			page_len,
 			page_count: 0,
+			regions: Vec::new(),
+			byte_order: PhantomData,
+			pack_heap: None,
+		}
+	}
+}
+
+impl<W, O: ByteOrder> Encoder<W, O> {
+	/// Sets the byte order of the numbers encoded in the file.
+	///
+	/// Defaults to big-endian. The file must be read with the same byte
+	/// order (see [`Reader::with_byte_order`]): mixing byte orders within a
+	/// file is not supported, except through [`utils::Endian`] values. The
+	/// heaps used with the encoder must have the same byte order.
+	pub fn with_byte_order<P: ByteOrder>(self) -> Encoder<W, P> {
+		Encoder {
+			output: self.output,
+			page_len: self.page_len,
+			page_count: self.page_count,
+			regions: self.regions,
+			byte_order: PhantomData,
+			pack_heap: self.pack_heap,
+		}
+	}
+
+	pub fn byte_order(&self) -> Endianness {
+		O::ENDIANNESS
+	}
+
+	/// Compresses heaps with the codec `K`, and protects them with the
+	/// checksum `H`.
+	///
+	/// Pages of sections have a fixed size and are stored as is: only heaps
+	/// are packed, each as a whole. Use [`utils::Identity`] or
+	/// [`utils::NoChecksum`] to only use a checksum or a codec. The file must
+	/// be read with the same codec and checksum (see [`Reader::with_codec`]).
+	pub fn with_codec<K: utils::Codec, H: utils::Checksum>(mut self) -> Self {
+		self.pack_heap = Some(heap::pack::<K, H>);
+		self
+	}
+
+	/// Creates a new encoder, after writing a [`header::Header`] recording
+	/// the page length and byte order of the file.
+	///
+	/// The file can then be read with [`Reader::open`].
+	pub fn with_header(mut output: W, page_len: u32) -> io::Result<Self>
+	where
+		W: io::Write,
+	{
+		header::Header {
+			page_len,
+			byte_order: O::ENDIANNESS,
+		}
+		.write(&mut output)?;
+		Ok(Encoder::new(output, page_len).with_byte_order())
+	}
+
+	fn is_written(&self, page_index: u32) -> bool {
+		self.regions.iter().any(|r| r.contains(&page_index))
+	}
+
+	/// Claims the current page for writing.
+	pub(crate) fn claim_page(&mut self) -> io::Result<()> {
+		if self.is_written(self.page_count) {
+			return Err(overlap_error());
+		}
+
+		self.page_count = self.page_count.checked_add(1).ok_or_else(too_large_error)?;
+		Ok(())
+	}
+
+	pub(crate) fn add_region(&mut self, region: Range<u32>) {
+		if !region.is_empty() {
+			self.regions.push(region)
 		}
 	}
 
-	pub fn begin_section<'h, T>(&mut self, heap: &'h mut Heap) -> section::Encoder<'_, 'h, W, T> {
+	/// Begins a section right after the previously written data.
+	///
+	/// Fails with [`io::ErrorKind::InvalidInput`] if an entry of type `T`
+	/// does not fit in a page.
+	pub fn begin_section<'h, T: EncodeSized>(
+		&mut self,
+		heap: &'h mut Heap<O>,
+	) -> io::Result<section::Encoder<'_, 'h, W, T, O>>
+	where
+		W: io::Write + io::Seek,
+	{
 		section::Encoder::new(self, heap, self.page_count)
 	}
 
+	/// Begins a section right after the previously written data, with an
+	/// entry size chosen at run time.
+	///
+	/// Fails with [`io::ErrorKind::InvalidInput`] if `entry_size` is greater
+	/// than the page length.
+	pub fn begin_dynamic_section<'h, T>(
+		&mut self,
+		entry_size: u32,
+		heap: &'h mut Heap<O>,
+	) -> io::Result<section::DynamicEncoder<'_, 'h, W, T, O>>
+	where
+		W: io::Write + io::Seek,
+	{
+		section::DynamicEncoder::new(self, heap, self.page_count, entry_size)
+	}
+
+	/// Begins a columnar section right after the previously written data.
+	///
+	/// Each column is written as its own section when the columnar section
+	/// ends.
+	pub fn begin_columnar_section<'h, T: Columns>(
+		&mut self,
+		heap: &'h mut Heap<O>,
+	) -> columnar::Encoder<'_, 'h, W, T, O> {
+		columnar::Encoder::new(self, heap)
+	}
+
 	pub fn end(self) -> W {
 		self.output
 	}
 
 	pub fn section_from_iter<I: IntoIterator>(
 		&mut self,
-		heap: &mut Heap,
+		heap: &mut Heap<O>,
 		items: I,
 	) -> io::Result<Section<<I::Item as Deref>::Target>>
 	where
@@ -129,7 +272,7 @@ impl<W> Encoder<W> {
 		<I::Item as Deref>::Target: Sized + EncodeOnHeap,
 		W: io::Write + io::Seek,
 	{
-		let mut encoder = self.begin_section(heap);
+		let mut encoder = self.begin_section(heap)?;
 
 		for item in items {
 			encoder.push(&(), &*item)?
@@ -140,7 +283,7 @@ impl<W> Encoder<W> {
 
 	pub fn section_from_iter_with<I: IntoIterator, C>(
 		&mut self,
-		heap: &mut Heap,
+		heap: &mut Heap<O>,
 		context: &C,
 		items: I,
 	) -> io::Result<Section<<I::Item as Deref>::Target>>
@@ -149,7 +292,7 @@ impl<W> Encoder<W> {
 		<I::Item as Deref>::Target: Sized + EncodeOnHeap<C>,
 		W: io::Write + io::Seek,
 	{
-		let mut encoder = self.begin_section(heap);
+		let mut encoder = self.begin_section(heap)?;
 
 		for item in items {
 			encoder.push(context, &*item)?
@@ -157,26 +300,163 @@ impl<W> Encoder<W> {
 
 		encoder.end()
 	}
+
+	/// Writes a section along with its page index.
+	///
+	/// The entries must be sorted by key. The page index is a second section
+	/// holding the key of the first entry of each page, written right after
+	/// the section. It can be used with [`Reader::binary_search_indexed`].
+	pub fn indexed_section_from_iter<I: IntoIterator, T: EncodeOnHeap, K: EncodeOnHeap>(
+		&mut self,
+		heap: &mut Heap<O>,
+		items: I,
+		key: impl Fn(&T) -> K,
+	) -> io::Result<(Section<T>, PageIndex<K>)>
+	where
+		I::Item: Deref<Target = T>,
+		W: io::Write + io::Seek,
+	{
+		let entries_per_page = Section::<T>::entries_per_page(self.page_len);
+		let mut page_keys = Vec::new();
+		let mut encoder = self.begin_section(heap)?;
+
+		for (i, item) in items.into_iter().enumerate() {
+			if (i as u32).is_multiple_of(entries_per_page) {
+				page_keys.push(key(&item))
+			}
+
+			encoder.push(&(), &*item)?
+		}
+
+		let section = encoder.end()?;
+		let index = self.section_from_iter(heap, &page_keys)?;
+		Ok((section, index))
+	}
+
+	/// Writes the bitmap of deleted entries of a section of `entry_count`
+	/// entries, given the indices of the deleted entries.
+	pub fn tombstones_from_iter(
+		&mut self,
+		heap: &mut Heap<O>,
+		entry_count: u32,
+		deleted: impl IntoIterator<Item = u32>,
+	) -> io::Result<Tombstones>
+	where
+		W: io::Write + io::Seek,
+	{
+		let mut words = vec![0u64; entry_count.ceiling_div(64) as usize];
+
+		for i in deleted {
+			if i < entry_count {
+				words[(i / 64) as usize] |= 1 << (i % 64)
+			}
+		}
+
+		self.section_from_iter(heap, &words)
+	}
 }
 
-impl<W: io::Seek> Encoder<W> {
+impl<W: io::Seek, O: ByteOrder> Encoder<W, O> {
 	pub(crate) fn pad(&mut self, padding: u32) -> io::Result<()> {
 		self.output.seek(io::SeekFrom::Current(padding as i64))?;
 		Ok(())
 	}
 
-	pub fn add_heap(&mut self, heap: Heap) -> io::Result<HeapSection>
+	/// Begins a section at the given page offset, instead of right after the
+	/// previously written data.
+	///
+	/// The page may be past the end of the written data, leaving a gap, or
+	/// inside a gap left by a previous call. Subsequent sections and heaps
+	/// are written after this one, unless also placed explicitly. Fails if
+	/// the section overlaps a previously written section or heap, or with
+	/// [`io::ErrorKind::InvalidInput`] if an entry of type `T` does not fit
+	/// in a page.
+	pub fn begin_section_at<'h, T: EncodeSized>(
+		&mut self,
+		heap: &'h mut Heap<O>,
+		page_offset: u32,
+	) -> io::Result<section::Encoder<'_, 'h, W, T, O>>
+	where
+		W: io::Write,
+	{
+		if self.is_written(page_offset) {
+			return Err(overlap_error());
+		}
+
+		section::check_entry_size(T::ENCODED_SIZE, self.page_len)?;
+		let shift = (page_offset as i64 - self.page_count as i64) * self.page_len as i64;
+		self.output.seek(io::SeekFrom::Current(shift))?;
+		self.page_count = page_offset;
+		section::Encoder::new(self, heap, page_offset)
+	}
+
+	pub fn add_heap(&mut self, heap: Heap<O>) -> io::Result<HeapSection>
+	where
+		W: io::Write,
+	{
+		self.write_heap(&heap)
+	}
+
+	/// Writes the current content of the given heap and clears it.
+	///
+	/// Values encoded before the flush must be read with the returned heap
+	/// section, and values encoded after it with the section returned by the
+	/// next flush. This allows writing the heap of each section right after
+	/// it, without keeping all the heap data in memory until the end.
+	pub fn flush_heap(&mut self, heap: &mut Heap<O>) -> io::Result<HeapSection>
 	where
 		W: io::Write,
 	{
+		let section = self.write_heap(heap)?;
+		*heap = Heap::new();
+		Ok(section)
+	}
+
+	fn write_heap(&mut self, heap: &Heap<O>) -> io::Result<HeapSection>
+	where
+		W: io::Write,
+	{
+		let packed = match self.pack_heap {
+			Some(pack) => Some(pack(heap.as_bytes(), O::ENDIANNESS)?),
+			None => None,
+		};
+		let bytes = packed.as_deref().unwrap_or(heap.as_bytes());
+		let len = u32::try_from(bytes.len()).map_err(|_| too_large_error())?;
+
 		let page_offset = self.page_count;
-		let page_count = heap.page_count(self.page_len);
-		self.output.write_all(heap.as_bytes())?;
-		self.pad(heap.padding(self.page_len))?;
-		self.page_count += page_count;
+		let page_count = len.ceiling_div(self.page_len);
+		let end = page_offset
+			.checked_add(page_count)
+			.ok_or_else(too_large_error)?;
+		let region = page_offset..end;
+
+		if self
+			.regions
+			.iter()
+			.any(|r| r.start < region.end && region.start < r.end)
+		{
+			return Err(overlap_error());
+		}
+
+		self.output.write_all(bytes)?;
+		self.pad((self.page_len - len % self.page_len) % self.page_len)?;
+		self.page_count = end;
+		self.add_region(region);
 		Ok(HeapSection {
 			page_offset,
 			page_count,
 		})
 	}
 }
+
+/// Error returned when a counter of pages, entries or bytes would overflow.
+pub(crate) fn too_large_error() -> io::Error {
+	io::Error::other("file too large")
+}
+
+fn overlap_error() -> io::Error {
+	io::Error::new(
+		io::ErrorKind::InvalidInput,
+		"overlapping previously written data",
+	)
+}
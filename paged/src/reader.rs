@@ -1,17 +1,37 @@
-use std::{cmp::Ordering, io};
+use std::{
+	cmp::Ordering,
+	collections::{HashMap, HashSet},
+	hash::Hash,
+	io,
+	marker::PhantomData,
+	ops::Range,
+	sync::Arc,
+};
 
 use crate::{
-	heap::Offset, no_context_mut, Decode, DecodeFromHeap, EncodeSized, HeapSection, Section,
+	heap::{self, Offset},
+	no_context_mut,
+	utils::{self, BigEndian, ByteOrder, ByteOrdered, Endianness, LittleEndian},
+	Decode, DecodeFromHeap, DynamicSection, EncodeSized, HeapSection, Keyed, PageIndex, Section,
+	Tombstones,
 };
 
 pub mod cache;
+pub mod navigator;
 pub mod page;
+#[cfg(unix)]
+pub mod positioned;
+pub mod sub;
 
-pub use cache::{Cache, EntryRef, Ref, UnboundRef, UnboundSliceIter};
+pub use cache::{Cache, EntryRef, MissCache, Ref, SliceRef, UnboundRef, UnboundSliceIter};
+pub use navigator::{Caches, Navigator, SectionReader};
 pub use page::Page;
 use parking_lot::Mutex;
+#[cfg(unix)]
+pub use positioned::PositionedReader;
+pub use sub::SubReader;
 
-use self::page::GetEntryBinder;
+use self::page::{GetEntryBinder, GetSliceBinder};
 
 // use self::cache::RefIntoIter;
 
@@ -22,39 +42,283 @@ pub enum Error {
 
 	#[error("out of memory")]
 	OutOfMemory,
+
+	/// The page pool of a cache has no free slot left.
+	///
+	/// Unlike [`Error::OutOfMemory`], this is recoverable: pages can be
+	/// evicted with [`Cache::evict`] before retrying.
+	#[error("page pool exhausted")]
+	PoolExhausted,
+}
+
+/// Checks that decoding the native memory representation of `t` gives back
+/// `t`, which is required to load entries with [`Reader::get_page_pod`].
+#[cfg(feature = "bytemuck")]
+fn pod_layout_matches_encoding<T: bytemuck::Pod + Decode<()>>(t: &T) -> bool {
+	let bytes = bytemuck::bytes_of(t);
+	T::decode(
+		&mut utils::Ordered::<_, utils::NativeEndian>::new(bytes),
+		&mut (),
+	)
+	.is_ok_and(|decoded| bytemuck::bytes_of(&decoded) == bytes)
 }
 
+/// Default maximum heap nesting depth, see [`Options::max_heap_depth`].
+pub const DEFAULT_MAX_HEAP_DEPTH: u32 = 64;
+
+/// Reader options.
+///
+/// New options may be added in the future: options are created with
+/// [`Options::new`] and set with the `with_` methods.
 #[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub struct Options {
 	pub page_len: u32,
 	pub first_page_offset: u32,
+
+	/// Maximum number of nested heap reads performed while decoding a value.
+	///
+	/// Decoding fails with [`io::ErrorKind::InvalidData`] past this depth,
+	/// which prevents crafted input from overflowing the stack.
+	pub max_heap_depth: u32,
+}
+
+impl Options {
+	/// Creates the default options for the given page length and first page
+	/// offset.
+	pub fn new(page_len: u32, first_page_offset: u32) -> Self {
+		Self {
+			page_len,
+			first_page_offset,
+			max_heap_depth: DEFAULT_MAX_HEAP_DEPTH,
+		}
+	}
+
+	/// Sets the maximum heap nesting depth (see [`Self::max_heap_depth`]).
+	pub fn with_max_heap_depth(mut self, max_heap_depth: u32) -> Self {
+		self.max_heap_depth = max_heap_depth;
+		self
+	}
 }
 
-pub struct Cursor<R> {
+pub struct Cursor<R, O = BigEndian> {
 	input: R,
 	current_offset: u32,
+
+	/// Whether `current_offset` is known to be the position of `input`.
+	///
+	/// This is not the case until the first seek, since the initial position
+	/// of the input is not checked.
+	positioned: bool,
+
 	options: Options,
+	heap_entries: Option<Vec<heap::Entry>>,
+	heap_depth: u32,
+
+	/// Dedicated cursor used for heap reads, if any.
+	heap_cursor: Option<Arc<Mutex<Cursor<R, O>>>>,
+
+	/// Unpacked heaps, if heaps are packed.
+	packed_heaps: Option<Arc<PackedHeaps>>,
+
+	/// Unpacked heap being read, with its offset in the file.
+	///
+	/// Reads are then served from memory, at the same offsets as the packed
+	/// heap would be if it was not packed.
+	heap_data: Option<(Arc<[u8]>, u32)>,
+
+	byte_order: PhantomData<O>,
+}
+
+impl<R, O: ByteOrder> ByteOrdered for Cursor<R, O> {
+	type ByteOrder = O;
+}
+
+/// Heaps of a file with packed heaps (see [`Reader::with_codec`]), unpacked
+/// on first use.
+pub(crate) struct PackedHeaps {
+	unpack: heap::Unpack,
+	heaps: Mutex<HashMap<u32, Arc<[u8]>>>,
+}
+
+impl PackedHeaps {
+	pub(crate) fn new(unpack: heap::Unpack) -> Self {
+		Self {
+			unpack,
+			heaps: Mutex::new(HashMap::new()),
+		}
+	}
+}
+
+impl<R, O: ByteOrder> Cursor<R, O> {
+	/// Changes the byte order of this cursor, which must not have a
+	/// dedicated heap cursor.
+	fn with_byte_order<P: ByteOrder>(self) -> Cursor<R, P> {
+		debug_assert!(self.heap_cursor.is_none());
+		Cursor {
+			input: self.input,
+			current_offset: self.current_offset,
+			positioned: self.positioned,
+			options: self.options,
+			heap_entries: self.heap_entries,
+			heap_depth: self.heap_depth,
+			heap_cursor: None,
+			packed_heaps: self.packed_heaps,
+			heap_data: self.heap_data,
+			byte_order: PhantomData,
+		}
+	}
+
+	/// Reports that the value being decoded references the given heap entry.
+	///
+	/// Heap-backed types call this after decoding their inline entry so that
+	/// [`Reader::get_with_heap_entries`] can collect the heap data locations
+	/// read during a decode.
+	pub fn report_heap_entry(&mut self, entry: heap::Entry) {
+		if let Some(entries) = &mut self.heap_entries {
+			entries.push(entry)
+		}
+	}
+
+	/// Calls `f` one heap nesting level deeper.
+	///
+	/// Fails with [`io::ErrorKind::InvalidData`] if the maximum heap depth
+	/// is exceeded. Heap-backed types reading nested heap data should go
+	/// through this method, as [`Self::decode_from_heap`] does.
+	pub fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> io::Result<T>) -> io::Result<T> {
+		if self.heap_depth >= self.options.max_heap_depth {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"maximum heap depth exceeded",
+			));
+		}
+
+		self.heap_depth += 1;
+		let result = f(self);
+		self.heap_depth -= 1;
+		result
+	}
 }
 
-impl<R: io::Seek> Cursor<R> {
+impl<R: io::Seek, O: ByteOrder> Cursor<R, O> {
+	/// Returns the current offset of the cursor in the file.
+	pub fn offset(&self) -> u32 {
+		self.current_offset
+	}
+
+	/// Moves the cursor to the given offset.
+	///
+	/// No seek is performed on the underlying input if the cursor is already
+	/// at the requested position, unless the input was never seeked before.
 	pub fn seek(&mut self, offset: u32) -> io::Result<()> {
-		self.input.seek(io::SeekFrom::Start(offset as u64))?;
-		self.current_offset = offset;
+		if self.heap_data.is_some() {
+			self.current_offset = offset;
+			return Ok(());
+		}
+
+		if !self.positioned || self.current_offset != offset {
+			self.positioned = false;
+			self.input.seek(io::SeekFrom::Start(offset as u64))?;
+			self.current_offset = offset;
+			self.positioned = true;
+		}
+
 		Ok(())
 	}
 
 	pub fn pad(&mut self, padding: u32) -> io::Result<()> {
-		self.input.seek(io::SeekFrom::Current(padding as i64))?;
+		if self.heap_data.is_none() {
+			self.input.seek(io::SeekFrom::Current(padding as i64))?;
+		}
+
 		self.current_offset += padding;
 		Ok(())
 	}
 }
 
-impl<R: io::Read> Cursor<R> {
+impl<R: io::Seek + io::Read, O: ByteOrder> Cursor<R, O> {
+	/// Calls `f` with the cursor used for reads in the given heap.
+	///
+	/// This is the dedicated heap cursor if there is one, in which case this
+	/// cursor is left untouched. Otherwise this cursor is used, and moved
+	/// back to its current position once `f` returns.
+	fn with_heap_cursor<T>(
+		&mut self,
+		heap: HeapSection,
+		f: impl FnOnce(&mut Self) -> io::Result<T>,
+	) -> io::Result<T> {
+		match self.heap_cursor.clone() {
+			Some(heap_cursor) => {
+				let mut heap_cursor = heap_cursor.lock();
+				heap_cursor.heap_depth = self.heap_depth;
+				heap_cursor.heap_entries = self.heap_entries.take();
+				let result = heap_cursor.in_heap(heap, false, f);
+				self.heap_entries = heap_cursor.heap_entries.take();
+				result
+			}
+			None => self.in_heap(heap, true, f),
+		}
+	}
+
+	/// Calls `f` with this cursor reading the given heap, unpacking it first
+	/// if heaps are packed.
+	///
+	/// If `restore` is set, the cursor is moved back to its current position
+	/// once `f` returns.
+	fn in_heap<T>(
+		&mut self,
+		heap: HeapSection,
+		restore: bool,
+		f: impl FnOnce(&mut Self) -> io::Result<T>,
+	) -> io::Result<T> {
+		let saved_offset = self.current_offset;
+
+		let Some(packed_heaps) = self.packed_heaps.clone() else {
+			let t = f(self)?;
+			if restore {
+				self.seek(saved_offset)?;
+			}
+
+			return Ok(t);
+		};
+
+		let start = self.options.first_page_offset + heap.page_offset * self.options.page_len;
+		if matches!(&self.heap_data, Some((_, data_start)) if *data_start == start) {
+			// Nested read in the heap being read.
+			let t = f(self)?;
+			self.current_offset = saved_offset;
+			return Ok(t);
+		}
+
+		let saved_heap_data = self.heap_data.take();
+		let loaded = packed_heaps.heaps.lock().get(&start).cloned();
+		let data = match loaded {
+			Some(data) => data,
+			None => {
+				self.seek(start)?;
+				let data: Arc<[u8]> = (packed_heaps.unpack)(self, O::ENDIANNESS)?.into();
+				packed_heaps.heaps.lock().insert(start, data.clone());
+				data
+			}
+		};
+
+		// The input position is unknown from now on.
+		self.positioned = false;
+		self.heap_data = Some((data, start));
+		let result = f(self);
+		self.heap_data = saved_heap_data;
+		self.current_offset = saved_offset;
+		if restore && self.heap_data.is_none() {
+			self.seek(saved_offset)?;
+		}
+
+		result
+	}
+}
+
+impl<R: io::Read, O: ByteOrder> Cursor<R, O> {
 	pub fn read(&mut self, bytes: &mut [u8]) -> io::Result<()> {
-		self.input.read_exact(bytes)?;
-		self.current_offset += bytes.len() as u32;
-		Ok(())
+		io::Read::read_exact(self, bytes)
 	}
 
 	/// Decodes arbitrary data from the heap.
@@ -67,15 +331,101 @@ impl<R: io::Read> Cursor<R> {
 	where
 		R: io::Seek,
 	{
-		let saved_offset = self.current_offset;
-		self.seek(
-			self.options.first_page_offset
-				+ heap.page_offset * self.options.page_len
-				+ offset.unwrap(),
-		)?;
-		let t = T::decode(self, context)?;
-		self.seek(saved_offset)?;
-		Ok(t)
+		self.at_heap_offset(heap, offset, |cursor| T::decode(cursor, context))
+	}
+
+	/// Calls `f` with a cursor positioned at the given offset in the heap,
+	/// one heap nesting level deeper.
+	///
+	/// This allows heap data to hold values decoded with
+	/// [`DecodeFromHeap`], themselves referencing other heap data.
+	pub fn at_heap_offset<T>(
+		&mut self,
+		heap: HeapSection,
+		offset: Offset,
+		f: impl FnOnce(&mut Self) -> io::Result<T>,
+	) -> io::Result<T>
+	where
+		R: io::Seek,
+	{
+		self.with_heap_cursor(heap, |cursor| {
+			cursor.seek(
+				cursor.options.first_page_offset
+					+ heap.page_offset * cursor.options.page_len
+					+ offset.unwrap(),
+			)?;
+			cursor.nested(f)
+		})
+	}
+
+	/// Decodes the sequence of values stored in the given heap entry, passing
+	/// each of them to `f`.
+	pub fn decode_seq_from_heap<C, T: Decode<C>>(
+		&mut self,
+		context: &mut C,
+		heap: HeapSection,
+		entry: heap::Entry,
+		mut f: impl FnMut(T) -> io::Result<()>,
+	) -> io::Result<()>
+	where
+		R: io::Seek,
+	{
+		self.with_heap_cursor(heap, |cursor| {
+			cursor.seek(
+				cursor.options.first_page_offset
+					+ heap.page_offset * cursor.options.page_len
+					+ entry.offset.unwrap(),
+			)?;
+
+			cursor.nested(|cursor| {
+				for _ in 0..entry.len {
+					f(T::decode(cursor, context)?)?
+				}
+
+				Ok(())
+			})
+		})
+	}
+
+	/// Decodes a fixed-size value from the heap.
+	///
+	/// The `T::ENCODED_SIZE` bytes at the given offset are read at once
+	/// before being decoded, so the value can be referenced by a bare
+	/// [`Offset`] (see [`Heap::insert_sized`](crate::Heap::insert_sized)).
+	pub fn decode_from_heap_at<C, T: EncodeSized + Decode<C>>(
+		&mut self,
+		context: &mut C,
+		heap: HeapSection,
+		offset: Offset,
+	) -> io::Result<T>
+	where
+		R: io::Seek,
+	{
+		let mut bytes = vec![0u8; T::ENCODED_SIZE as usize];
+		self.read_from_heap(heap, offset, &mut bytes)?;
+		T::decode(&mut utils::Ordered::<_, O>::new(bytes.as_slice()), context)
+	}
+
+	/// Reads the length stored in front of the data referenced by a
+	/// [`heap::VarEntry`], and returns the entry of this data.
+	pub fn read_var_entry(
+		&mut self,
+		heap: HeapSection,
+		entry: heap::VarEntry,
+	) -> io::Result<heap::Entry>
+	where
+		R: io::Seek,
+	{
+		let (len, header_len) = self.with_heap_cursor(heap, |cursor| {
+			let start = cursor.options.first_page_offset
+				+ heap.page_offset * cursor.options.page_len
+				+ entry.0.unwrap();
+			cursor.seek(start)?;
+			let len = utils::decode_varint(cursor)?;
+			Ok((len, cursor.current_offset - start))
+		})?;
+		let len = u32::try_from(len).map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+		Ok(entry.data_entry(header_len, len))
 	}
 
 	/// Read arbitrary data from the heap.
@@ -88,53 +438,214 @@ impl<R: io::Read> Cursor<R> {
 	where
 		R: io::Seek,
 	{
-		let saved_offset = self.current_offset;
-		self.seek(
-			self.options.first_page_offset
-				+ heap.page_offset * self.options.page_len
-				+ offset.unwrap(),
-		)?;
-		self.input.read_exact(bytes)?;
-		self.seek(saved_offset)?;
-		Ok(())
+		self.with_heap_cursor(heap, |cursor| {
+			cursor.seek(
+				cursor.options.first_page_offset
+					+ heap.page_offset * cursor.options.page_len
+					+ offset.unwrap(),
+			)?;
+			cursor.read(bytes)
+		})
 	}
 }
 
-impl<R: io::Read> io::Read for Cursor<R> {
+impl<R: io::Read, O: ByteOrder> io::Read for Cursor<R, O> {
 	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if let Some((data, start)) = &self.heap_data {
+			let position = self
+				.current_offset
+				.checked_sub(*start)
+				.ok_or(io::ErrorKind::InvalidData)?;
+			let available = data.get(position as usize..).unwrap_or_default();
+			let len = buf.len().min(available.len());
+			buf[..len].copy_from_slice(&available[..len]);
+			self.current_offset += len as u32;
+			return Ok(len);
+		}
+
 		let len = self.input.read(buf)?;
 		self.current_offset += len as u32;
 		Ok(len)
 	}
 }
 
-pub struct Reader<R> {
-	cursor: Mutex<Cursor<R>>,
+pub struct Reader<R, O = BigEndian> {
+	cursor: Mutex<Cursor<R, O>>,
+	heap_cursor: Option<Arc<Mutex<Cursor<R, O>>>>,
 	options: Options,
 }
 
 impl<R> Reader<R> {
-	/// Creates a new reader.
+	/// Creates a new reader of a big-endian file.
 	///
-	/// It is assumed that the current input position is `first_page_offset`.
+	/// The input is moved to the position of the data on the first read,
+	/// whatever its current position. Files written with another byte order
+	/// are read using [`Self::with_byte_order`].
 	pub fn new(input: R, page_len: u32, first_page_offset: u32) -> Self {
-		let options = Options {
-			page_len,
-			first_page_offset,
-		};
+		let options = Options::new(page_len, first_page_offset);
 
 		Self {
 			cursor: Mutex::new(Cursor {
 				input,
 				current_offset: first_page_offset,
+				positioned: false,
 				options,
+				heap_entries: None,
+				heap_depth: 0,
+				heap_cursor: None,
+				packed_heaps: None,
+				heap_data: None,
+				byte_order: PhantomData,
 			}),
+			heap_cursor: None,
 			options,
 		}
 	}
 }
 
+impl<R, O: ByteOrder> Reader<R, O> {
+	/// Uses a dedicated input for heap reads.
+	///
+	/// The given input must read the same file as the main input (e.g. a
+	/// second handle to the same file). Heap reads then use their own cursor,
+	/// both when performed directly through the reader (such as
+	/// [`Self::decode_from_heap`]) and when decoding entries referencing heap
+	/// data. The main input is then only used to read pages.
+	pub fn with_heap_input(mut self, heap_input: R) -> Self {
+		let heap_cursor = Arc::new(Mutex::new(Cursor {
+			input: heap_input,
+			current_offset: self.options.first_page_offset,
+			positioned: false,
+			options: self.options,
+			heap_entries: None,
+			heap_depth: 0,
+			heap_cursor: None,
+			packed_heaps: self.cursor.get_mut().packed_heaps.clone(),
+			heap_data: None,
+			byte_order: PhantomData,
+		}));
+		self.cursor.get_mut().heap_cursor = Some(heap_cursor.clone());
+		self.heap_cursor = Some(heap_cursor);
+		self
+	}
+
+	/// Sets the maximum heap nesting depth (see [`Options::max_heap_depth`]).
+	pub fn with_max_heap_depth(mut self, max_heap_depth: u32) -> Self {
+		self.options.max_heap_depth = max_heap_depth;
+		self.cursor.get_mut().options.max_heap_depth = max_heap_depth;
+		if let Some(heap_cursor) = &self.heap_cursor {
+			heap_cursor.lock().options.max_heap_depth = max_heap_depth
+		}
+		self
+	}
+
+	/// Sets the byte order of the numbers encoded in the file.
+	///
+	/// Defaults to big-endian. This must match the byte order the file was
+	/// written with (see [`Encoder::with_byte_order`](crate::Encoder::with_byte_order)).
+	pub fn with_byte_order<P: ByteOrder>(self) -> Reader<R, P> {
+		let mut cursor = self.cursor.into_inner();
+		cursor.heap_cursor = None;
+		let heap_cursor = self.heap_cursor.map(|heap_cursor| {
+			// The main cursor was the only other owner of the heap cursor.
+			let heap_cursor = Arc::into_inner(heap_cursor).unwrap().into_inner();
+			Arc::new(Mutex::new(heap_cursor.with_byte_order()))
+		});
+		let mut cursor = cursor.with_byte_order();
+		cursor.heap_cursor = heap_cursor.clone();
+
+		Reader {
+			cursor: Mutex::new(cursor),
+			heap_cursor,
+			options: self.options,
+		}
+	}
+
+	/// Reads heaps packed with the given codec and checksum.
+	///
+	/// This must match the codec and checksum the file was written with (see
+	/// [`Encoder::with_codec`](crate::Encoder::with_codec)). Each heap is
+	/// unpacked and verified on its first read, then kept in memory.
+	pub fn with_codec<K: utils::Codec, H: utils::Checksum>(mut self) -> Self {
+		let packed_heaps = Arc::new(PackedHeaps::new(heap::unpack::<K, H>));
+		if let Some(heap_cursor) = &self.heap_cursor {
+			heap_cursor.lock().packed_heaps = Some(packed_heaps.clone())
+		}
+		self.cursor.get_mut().packed_heaps = Some(packed_heaps);
+		self
+	}
+
+	/// Returns the section starting at the given byte offset of the input and
+	/// containing `entry_count` entries.
+	///
+	/// This is useful when the location of a section is stored in a custom
+	/// header as a raw byte offset. Fails with [`io::ErrorKind::InvalidInput`]
+	/// if `byte_offset` does not start a page.
+	pub fn section_at_byte_offset<T>(
+		&self,
+		byte_offset: u32,
+		entry_count: u32,
+	) -> io::Result<Section<T>> {
+		match byte_offset.checked_sub(self.options.first_page_offset) {
+			Some(relative_offset) if relative_offset.is_multiple_of(self.options.page_len) => Ok(
+				Section::from_byte_offset(relative_offset, entry_count, self.options.page_len),
+			),
+			_ => Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"section byte offset is not page-aligned",
+			)),
+		}
+	}
+
+	fn heap_cursor(&self) -> &Mutex<Cursor<R, O>> {
+		self.heap_cursor.as_deref().unwrap_or(&self.cursor)
+	}
+}
+
 impl<R: io::Seek + io::Read> Reader<R> {
+	/// Opens a file starting with a [`header::Header`](crate::header::Header),
+	/// as written by [`Encoder::with_header`](crate::Encoder::with_header).
+	///
+	/// The page length and byte order are read from the header, so the file
+	/// is decoded correctly whatever the byte order it was written with. The
+	/// returned reader has the byte order of the file. It is assumed that the
+	/// current input position is the start of the file.
+	pub fn open(mut input: R) -> io::Result<AnyReader<R>> {
+		let header = crate::header::Header::read(&mut input)?;
+		let reader = Self::new(input, header.page_len, crate::header::LEN);
+		Ok(match header.byte_order {
+			Endianness::Big => AnyReader::BigEndian(reader),
+			Endianness::Little => AnyReader::LittleEndian(reader.with_byte_order()),
+		})
+	}
+}
+
+/// Reader of a file whose byte order is only known at run time.
+///
+/// This is returned by [`Reader::open`].
+pub enum AnyReader<R> {
+	BigEndian(Reader<R, BigEndian>),
+	LittleEndian(Reader<R, LittleEndian>),
+}
+
+impl<R: io::Seek + io::Read, O: ByteOrder> Reader<R, O> {
+	/// Returns a reader over a paged file embedded in the given heap entry.
+	///
+	/// The length of the entry is a length in bytes. The embedded file uses
+	/// its own page length, and `first_page_offset` is relative to the start
+	/// of the entry. Its sections and heaps are then read like those of any
+	/// other file.
+	pub fn sub_reader(
+		&self,
+		heap: HeapSection,
+		entry: heap::Entry,
+		page_len: u32,
+		first_page_offset: u32,
+	) -> Reader<SubReader<'_, R, O>> {
+		let input = SubReader::new(self, heap, entry, first_page_offset);
+		Reader::new(input, page_len, first_page_offset)
+	}
+
 	pub fn get_page<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
 		&self,
 		section: Section<T>,
@@ -159,6 +670,49 @@ impl<R: io::Seek + io::Read> Reader<R> {
 		})
 	}
 
+	/// Loads a page of plain-old-data entries without decoding them.
+	///
+	/// If the byte order of the file is the native byte order of the host
+	/// ([`NativeEndian`](utils::NativeEndian)), the page bytes are read at once and
+	/// reinterpreted as `T` values. This requires the memory layout of `T`
+	/// to match its encoding, which is the case for numbers, arrays of
+	/// numbers, and entries written by
+	/// [`section::Encoder::push_pod`](crate::section::Encoder::push_pod).
+	/// Otherwise, entries are decoded one by one as [`Self::get_page`] does.
+	#[cfg(feature = "bytemuck")]
+	pub fn get_page_pod<'a, T: EncodeSized + bytemuck::Pod + Decode<()>>(
+		&self,
+		section: Section<T>,
+		cache: &'a Cache<T>,
+		page_index: u32,
+	) -> Result<Ref<'a, T>, Error> {
+		assert_eq!(std::mem::size_of::<T>() as u32, T::ENCODED_SIZE);
+		let global_page_index = page_index + section.page_offset();
+		cache.get_or_insert(global_page_index, |page| {
+			let offset = self.options.first_page_offset
+				+ section.offset_of_page(self.options.page_len, page_index);
+			let entry_count = section.page_size(self.options.page_len, page_index);
+
+			let mut cursor = self.cursor.lock();
+			cursor.seek(offset)?;
+			if O::ENDIANNESS == Endianness::NATIVE {
+				page.read_pod(&mut *cursor, entry_count)?;
+				debug_assert!(
+					page.as_slice()
+						.first()
+						.is_none_or(pod_layout_matches_encoding),
+					"memory layout of plain-old-data entries does not match their encoding"
+				);
+			} else {
+				for _ in 0..entry_count {
+					page.push(T::decode(&mut *cursor, &mut ())?)
+				}
+			}
+
+			Ok(())
+		})
+	}
+
 	pub fn get<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
 		&self,
 		section: Section<T>,
@@ -176,83 +730,670 @@ impl<R: io::Seek + io::Read> Reader<R> {
 		}
 	}
 
-	pub fn pages<'a, 'c, T: EncodeSized>(
-		&'a self,
+	/// Returns the last `n` entries of the section, in order.
+	///
+	/// Fewer entries are returned if the section is shorter. Only the pages
+	/// holding the returned entries are loaded.
+	pub fn tail<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
 		section: Section<T>,
-		cache: &'c Cache<T>,
+		cache: &'a Cache<T>,
+		context: &mut C,
 		heap: HeapSection,
-	) -> Pages<'a, 'c, R, T> {
-		Pages::new(self, section, cache, heap)
-	}
+		n: u32,
+	) -> Result<Vec<Ref<'a, T, UnboundRef<T>>>, Error> {
+		let entry_count = section.entry_count();
+		let mut entry_index = entry_count.saturating_sub(n);
+		let mut result = Vec::with_capacity((entry_count - entry_index) as usize);
 
-	pub fn iter<'a, 'c, T: EncodeSized>(
-		&'a self,
-		section: Section<T>,
-		cache: &'c Cache<T>,
-		heap: HeapSection,
-	) -> Iter<'a, 'c, R, T> {
-		Iter {
-			pages: self.pages(section, cache, heap),
-			current_page: None,
+		while entry_index < entry_count {
+			let (page_index, start) = section.page_of_entry(self.options.page_len, entry_index);
+			let end = section.page_size(self.options.page_len, page_index);
+			let page = self.get_page(section, cache, context, heap, page_index)?;
+			for i in start..end {
+				result.push(page.clone_page().map(GetEntryBinder::new(i)))
+			}
+
+			entry_index += end - start;
 		}
+
+		Ok(result)
 	}
 
-	pub fn binary_search_by_key<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
+	/// Returns the given contiguous range of entries as a single slice.
+	///
+	/// Returns `None` if the range is empty, out of bounds, or if it spans
+	/// more than one page.
+	pub fn get_slice<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
 		&self,
 		section: Section<T>,
 		cache: &'a Cache<T>,
 		context: &mut C,
 		heap: HeapSection,
-		f: impl Fn(&T, &C) -> Ordering,
-	) -> Result<Option<Ref<'a, T, UnboundRef<T>>>, Error> {
-		let mut min = 0;
-		let mut max = section.page_count(self.options.page_len);
-
-		let mut page_index = max / 2;
+		range: Range<u32>,
+	) -> Result<Option<SliceRef<'a, T>>, Error> {
+		if range.start < range.end && range.end <= section.entry_count() {
+			let (page_index, start) = section.page_of_entry(self.options.page_len, range.start);
+			let (last_page_index, last) =
+				section.page_of_entry(self.options.page_len, range.end - 1);
 
-		while page_index < max {
-			let page = self.get_page(section, cache, context, heap, page_index)?;
-			match page.binary_search_by_key(context, &f) {
-				Ok(i) => return Ok(Some(page.map(GetEntryBinder::new(i)))),
-				Err(Ordering::Greater) => {
-					max = page_index;
-				}
-				Err(Ordering::Less) => {
-					min = page_index + 1;
-				}
-				Err(Ordering::Equal) => break,
+			if page_index == last_page_index {
+				let page = self.get_page(section, cache, context, heap, page_index)?;
+				return Ok(Some(page.map(GetSliceBinder::new(start..(last + 1)))));
 			}
-
-			page_index = (min + max) / 2;
 		}
 
 		Ok(None)
 	}
 
-	/// Decodes arbitrary data from the heap.
-	pub fn decode_from_heap<C, T: Decode<C>>(
-		&mut self,
+	/// Decodes the given entry, along with the heap entries it references.
+	///
+	/// The entry is decoded directly, bypassing the cache. Heap entries are
+	/// listed in the order they were read, which allows locating the heap
+	/// data backing `String` or `Vec` fields without decoding them again.
+	pub fn get_with_heap_entries<C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
 		context: &mut C,
 		heap: HeapSection,
-		offset: Offset,
-	) -> io::Result<T> {
-		let mut cursor = self.cursor.lock();
-		cursor.decode_from_heap(context, heap, offset)
+		entry_index: u32,
+	) -> Result<Option<(T, Vec<heap::Entry>)>, Error> {
+		if entry_index < section.entry_count() {
+			let (page_index, i) = section.page_of_entry(self.options.page_len, entry_index);
+			let offset = self.options.first_page_offset
+				+ section.offset_of_page(self.options.page_len, page_index)
+				+ i * T::ENCODED_SIZE;
+
+			let mut cursor = self.cursor.lock();
+			cursor.seek(offset)?;
+			cursor.heap_entries = Some(Vec::new());
+			let result = T::decode_from_heap(&mut cursor, context, heap);
+			let heap_entries = cursor.heap_entries.take().unwrap();
+			Ok(Some((result?, heap_entries)))
+		} else {
+			Ok(None)
+		}
 	}
-}
 
-pub struct Pages<'a, 'c, R, T> {
-	reader: &'a Reader<R>,
-	section: Section<T>,
+	/// Creates a navigator over the sections of this file, using the given
+	/// caches and heap.
+	pub fn navigate<'c>(&self, caches: &'c Caches, heap: HeapSection) -> Navigator<'_, 'c, R, O> {
+		Navigator::new(self, caches, heap)
+	}
+
+	pub fn pages<'a, 'c, T: EncodeSized>(
+		&'a self,
+		section: Section<T>,
+		cache: &'c Cache<T>,
+		heap: HeapSection,
+	) -> Pages<'a, 'c, R, T, O> {
+		Pages::new(self, section, cache, heap)
+	}
+
+	/// Checks that the entries of the given section fit in a page.
+	///
+	/// The entry size of a dynamic section is read from the file, so a
+	/// corrupt section may have no room for any entry in its pages.
+	fn check_dynamic_section<T>(&self, section: DynamicSection<T>) -> io::Result<()> {
+		if section.entry_size() > self.options.page_len {
+			Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"section entry size exceeds the page length",
+			))
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Returns the given page of a section whose entry size is chosen at run
+	/// time.
+	///
+	/// Fails with [`io::ErrorKind::InvalidData`] if the entry size of the
+	/// section is greater than the page length.
+	pub fn get_dynamic_page<'a, C, T: Decode<C>>(
+		&self,
+		section: DynamicSection<T>,
+		cache: &'a Cache<T>,
+		context: &mut C,
+		page_index: u32,
+	) -> Result<Ref<'a, T>, Error> {
+		self.check_dynamic_section(section)?;
+		let global_page_index = page_index + section.page_offset();
+		cache.get_or_insert(global_page_index, |page| {
+			let offset = self.options.first_page_offset
+				+ section.offset_of_page(self.options.page_len, page_index);
+			let entry_count = section.page_size(self.options.page_len, page_index);
+
+			let mut cursor = self.cursor.lock();
+			for i in 0..entry_count {
+				cursor.seek(offset + i * section.entry_size())?;
+				page.push(T::decode(&mut *cursor, context)?)
+			}
+
+			Ok(())
+		})
+	}
+
+	/// Returns the given entry of a section whose entry size is chosen at run
+	/// time.
+	///
+	/// Fails with [`io::ErrorKind::InvalidData`] if the entry size of the
+	/// section is greater than the page length.
+	pub fn get_dynamic<'a, C, T: Decode<C>>(
+		&self,
+		section: DynamicSection<T>,
+		cache: &'a Cache<T>,
+		context: &mut C,
+		entry_index: u32,
+	) -> Result<Option<Ref<'a, T, UnboundRef<T>>>, Error> {
+		self.check_dynamic_section(section)?;
+		if entry_index < section.entry_count() {
+			let (page_index, i) = section.page_of_entry(self.options.page_len, entry_index);
+			let page = self.get_dynamic_page(section, cache, context, page_index)?;
+			Ok(Some(page.map(GetEntryBinder::new(i))))
+		} else {
+			Ok(None)
+		}
+	}
+
+	/// Checks if the given entry is marked as deleted in the tombstones bitmap.
+	pub fn is_deleted(
+		&self,
+		tombstones: Tombstones,
+		tombstones_cache: &Cache<u64>,
+		heap: HeapSection,
+		entry_index: u32,
+	) -> Result<bool, Error> {
+		match self.get(
+			tombstones,
+			tombstones_cache,
+			&mut (),
+			heap,
+			entry_index / 64,
+		)? {
+			Some(word) => Ok(*word & (1 << (entry_index % 64)) != 0),
+			None => Ok(false),
+		}
+	}
+
+	/// Returns the given entry, unless it is marked as deleted.
+	#[allow(clippy::too_many_arguments)]
+	pub fn get_live<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &'a Cache<T>,
+		tombstones: Tombstones,
+		tombstones_cache: &Cache<u64>,
+		context: &mut C,
+		heap: HeapSection,
+		entry_index: u32,
+	) -> Result<Option<Ref<'a, T, UnboundRef<T>>>, Error> {
+		if self.is_deleted(tombstones, tombstones_cache, heap, entry_index)? {
+			Ok(None)
+		} else {
+			self.get(section, cache, context, heap, entry_index)
+		}
+	}
+
+	/// Iterates over the entries of the given section not marked as deleted.
+	pub fn iter_live<'a, 'c, T: EncodeSized>(
+		&'a self,
+		section: Section<T>,
+		cache: &'c Cache<T>,
+		tombstones: Tombstones,
+		tombstones_cache: &'c Cache<u64>,
+		heap: HeapSection,
+	) -> LiveIter<'a, 'c, R, T, O> {
+		LiveIter {
+			iter: self.iter(section, cache, heap),
+			tombstones,
+			tombstones_cache,
+			index: 0,
+		}
+	}
+
+	/// Iterates over the entries of the given section in the order given by
+	/// `permutation`, a section of entry indices.
+	///
+	/// The page of the last yielded entry is kept, so that consecutive
+	/// indices falling in the same page are read without going through the
+	/// cache. Fails with [`io::ErrorKind::InvalidData`] on out of bounds
+	/// indices.
+	pub fn iter_permuted<'a, 'c, T: EncodeSized>(
+		&'a self,
+		section: Section<T>,
+		cache: &'c Cache<T>,
+		permutation: Section<u32>,
+		permutation_cache: &'c Cache<u32>,
+		heap: HeapSection,
+	) -> PermutedIter<'a, 'c, R, T, O> {
+		PermutedIter {
+			reader: self,
+			section,
+			cache,
+			heap,
+			permutation: self.iter(permutation, permutation_cache, heap),
+			current_page: None,
+		}
+	}
+
+	/// Iterates over the pages of the given section, each page being yielded
+	/// as an iterator over its entries.
+	pub fn page_chunks<'a, 'c, T: EncodeSized>(
+		&'a self,
+		section: Section<T>,
+		cache: &'c Cache<T>,
+		heap: HeapSection,
+	) -> PageChunks<'a, 'c, R, T, O> {
+		PageChunks {
+			pages: self.pages(section, cache, heap),
+		}
+	}
+
+	pub fn iter<'a, 'c, T: EncodeSized>(
+		&'a self,
+		section: Section<T>,
+		cache: &'c Cache<T>,
+		heap: HeapSection,
+	) -> Iter<'a, 'c, R, T, O> {
+		Iter {
+			pages: self.pages(section, cache, heap),
+			current_page: None,
+		}
+	}
+
+	/// Iterates over the entries of the given section, skipping pages that
+	/// fail to decode.
+	///
+	/// Since pages are decoded as a whole, a corrupt entry makes its entire
+	/// page unavailable. Each such page is reported once, as an error paired
+	/// with the index of the page in the section, and iteration resumes with
+	/// the next page.
+	pub fn iter_lossy<'a, 'c, T: EncodeSized>(
+		&'a self,
+		section: Section<T>,
+		cache: &'c Cache<T>,
+		heap: HeapSection,
+	) -> LossyIter<'a, 'c, R, T, O> {
+		LossyIter {
+			pages: self.pages(section, cache, heap),
+			current_page: None,
+		}
+	}
+
+	/// Iterates over the entries of the given sections, in order, as a single
+	/// stream of entries.
+	pub fn chain_iter<'a, 'c, 's, T: EncodeSized>(
+		&'a self,
+		sections: &'s [Section<T>],
+		cache: &'c Cache<T>,
+		heap: HeapSection,
+	) -> ChainIter<'a, 'c, 's, R, T, O> {
+		ChainIter {
+			reader: self,
+			sections: sections.iter(),
+			cache,
+			heap,
+			current: None,
+		}
+	}
+
+	/// Iterates over the entries of the given section, mapping each of them
+	/// with `f`.
+	///
+	/// The entries are only borrowed while `f` is applied, so the returned
+	/// values do not hold any reference to the cache.
+	pub fn map_iter<'a, 'c, 'x, C, T: EncodeSized + DecodeFromHeap<C>, U, F: FnMut(&T) -> U>(
+		&'a self,
+		section: Section<T>,
+		cache: &'c Cache<T>,
+		context: &'x mut C,
+		heap: HeapSection,
+		f: F,
+	) -> MapIter<'a, 'c, 'x, R, C, T, F, O> {
+		MapIter {
+			iter: self.iter(section, cache, heap),
+			context,
+			f,
+		}
+	}
+
+	/// Iterates over owned copies of the entries of the given section.
+	///
+	/// The context is borrowed for the lifetime of the iterator, so that it
+	/// can be used as a regular [`Iterator`] even when `C` is not `()`.
+	pub fn into_owned_iter<'a, 'c, 'x, C, T: Clone + EncodeSized + DecodeFromHeap<C>>(
+		&'a self,
+		section: Section<T>,
+		cache: &'c Cache<T>,
+		context: &'x mut C,
+		heap: HeapSection,
+	) -> IntoOwnedIter<'a, 'c, 'x, R, C, T, O> {
+		self.map_iter(section, cache, context, heap, T::clone)
+	}
+
+	pub fn binary_search_by_key<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &'a Cache<T>,
+		context: &mut C,
+		heap: HeapSection,
+		f: impl Fn(&T, &C) -> Ordering,
+	) -> Result<Option<Ref<'a, T, UnboundRef<T>>>, Error> {
+		let mut min = 0;
+		let mut max = section.page_count(self.options.page_len);
+
+		let mut page_index = max / 2;
+
+		while page_index < max {
+			let page = self.get_page(section, cache, context, heap, page_index)?;
+			match page.binary_search_by_key(context, &f) {
+				Ok(i) => return Ok(Some(page.map(GetEntryBinder::new(i)))),
+				Err(Ordering::Greater) => {
+					max = page_index;
+				}
+				Err(Ordering::Less) => {
+					min = page_index + 1;
+				}
+				Err(Ordering::Equal) => break,
+			}
+
+			page_index = (min + max) / 2;
+		}
+
+		Ok(None)
+	}
+
+	/// Searches a section sorted by [`Keyed::paged_key`] for the entry with
+	/// the given key.
+	pub fn binary_search<'a, C, T: Keyed + EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &'a Cache<T>,
+		context: &mut C,
+		heap: HeapSection,
+		key: &T::Key,
+	) -> Result<Option<Ref<'a, T, UnboundRef<T>>>, Error> {
+		self.binary_search_by_key(section, cache, context, heap, |t, _| t.paged_cmp(key))
+	}
+
+	/// Searches a sorted section for the given key, remembering absent keys.
+	///
+	/// Behaves like [`Self::binary_search_by_key`], with `f` comparing an
+	/// entry to `key`. Keys found absent are recorded in `misses`, and later
+	/// searches for them in the same section return `Ok(None)` without
+	/// reading any page.
+	#[allow(clippy::too_many_arguments)]
+	pub fn binary_search_cached<'a, C, K: Clone + Eq + Hash, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &'a Cache<T>,
+		misses: &MissCache<K>,
+		context: &mut C,
+		heap: HeapSection,
+		key: &K,
+		f: impl Fn(&T, &K, &C) -> Ordering,
+	) -> Result<Option<Ref<'a, T, UnboundRef<T>>>, Error> {
+		if misses.contains(section, key) {
+			return Ok(None);
+		}
+
+		let result =
+			self.binary_search_by_key(section, cache, context, heap, |t, c| f(t, key, c))?;
+		if result.is_none() {
+			misses.insert(section, key.clone())
+		}
+
+		Ok(result)
+	}
+
+	/// Searches a sorted section using its page index.
+	///
+	/// The page index is searched first for the page that may contain the
+	/// entry, and only this page of the section is then decoded. Entries are
+	/// compared through their key, given by `key`, with `f` returning the
+	/// ordering of a key relative to the searched key.
+	#[allow(clippy::too_many_arguments)]
+	pub fn binary_search_indexed<'a, C, K, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &'a Cache<T>,
+		index: PageIndex<K>,
+		index_cache: &Cache<K>,
+		context: &mut C,
+		heap: HeapSection,
+		key: impl Fn(&T) -> K,
+		f: impl Fn(&K, &C) -> Ordering,
+	) -> Result<Option<Ref<'a, T, UnboundRef<T>>>, Error>
+	where
+		K: EncodeSized + DecodeFromHeap<C>,
+	{
+		let mut min = 0;
+		let mut max = index.entry_count();
+
+		while min < max {
+			let page_index = (min + max) / 2;
+			let page_key = self
+				.get(index, index_cache, context, heap, page_index)?
+				.unwrap();
+			if f(&page_key, context).is_gt() {
+				max = page_index
+			} else {
+				min = page_index + 1
+			}
+		}
+
+		match min.checked_sub(1) {
+			Some(page_index) => {
+				let page = self.get_page(section, cache, context, heap, page_index)?;
+				match page.as_slice().binary_search_by(|t| f(&key(t), context)) {
+					Ok(i) => Ok(Some(page.map(GetEntryBinder::new(i as u32)))),
+					Err(_) => Ok(None),
+				}
+			}
+			None => Ok(None),
+		}
+	}
+
+	/// Loads the given pages in the cache.
+	///
+	/// The page indices are global, as recorded by [`Cache::access_log`].
+	/// Duplicate indices and pages not belonging to the section are ignored.
+	pub fn warm_from_log<C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &Cache<T>,
+		context: &mut C,
+		heap: HeapSection,
+		page_indices: &[u32],
+	) -> Result<(), Error> {
+		let page_count = section.page_count(self.options.page_len);
+		let mut visited = HashSet::new();
+
+		for &global_page_index in page_indices {
+			if let Some(page_index) = global_page_index.checked_sub(section.page_offset()) {
+				if page_index < page_count && visited.insert(page_index) {
+					self.get_page(section, cache, context, heap, page_index)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Counts the entries of the given section satisfying the predicate `f`.
+	///
+	/// Every entry of the section is visited, using the cache to load pages.
+	pub fn count_where<C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &Cache<T>,
+		context: &mut C,
+		heap: HeapSection,
+		mut f: impl FnMut(&T, &C) -> bool,
+	) -> Result<u64, Error> {
+		let mut count = 0;
+		let mut pages = self.pages(section, cache, heap);
+
+		while let Some(page) = pages.next_with(context) {
+			count += page?.iter().filter(|t| f(t, context)).count() as u64;
+		}
+
+		Ok(count)
+	}
+
+	/// Folds every entry of the given section into an accumulator, in order.
+	///
+	/// Every entry of the section is visited, using the cache to load pages.
+	pub fn fold<C, T: EncodeSized + DecodeFromHeap<C>, A>(
+		&self,
+		section: Section<T>,
+		cache: &Cache<T>,
+		context: &mut C,
+		heap: HeapSection,
+		init: A,
+		mut f: impl FnMut(A, &T) -> A,
+	) -> Result<A, Error> {
+		let mut acc = init;
+		let mut pages = self.pages(section, cache, heap);
+
+		while let Some(page) = pages.next_with(context) {
+			acc = page?.iter().fold(acc, &mut f);
+		}
+
+		Ok(acc)
+	}
+
+	/// Writes every entry of the given section to `output`, formatted with
+	/// `f`.
+	///
+	/// Entries are written in order, using the cache to load pages.
+	#[allow(clippy::too_many_arguments)]
+	pub fn export<C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &Cache<T>,
+		context: &mut C,
+		heap: HeapSection,
+		mut output: impl io::Write,
+		mut f: impl FnMut(&T, &mut dyn io::Write) -> io::Result<()>,
+	) -> Result<(), Error> {
+		let mut pages = self.pages(section, cache, heap);
+
+		while let Some(page) = pages.next_with(context) {
+			for t in page?.iter() {
+				f(t, &mut output)?
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Decodes every entry of the given section, in order, passing each of
+	/// them to `f`, without using any cache.
+	fn decode_each<C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		context: &mut C,
+		heap: HeapSection,
+		mut f: impl FnMut(T),
+	) -> Result<(), Error> {
+		let mut cursor = self.cursor.lock();
+		for page_index in 0..section.page_count(self.options.page_len) {
+			let offset = self.options.first_page_offset
+				+ section.offset_of_page(self.options.page_len, page_index);
+			let entry_count = section.page_size(self.options.page_len, page_index);
+
+			cursor.seek(offset)?;
+			for _ in 0..entry_count {
+				f(T::decode_from_heap(&mut cursor, context, heap)?)
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Decodes every entry of the given section.
+	///
+	/// Entries are decoded directly, bypassing the cache. This is intended
+	/// for one-shot reads of small sections, for which the cache overhead
+	/// is not worth it.
+	pub fn decode_all<C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> Result<Vec<T>, Error> {
+		let mut result = Vec::with_capacity(section.entry_count() as usize);
+		self.decode_each(section, context, heap, |t| result.push(t))?;
+		Ok(result)
+	}
+
+	/// Decodes every entry of the given section into the given arena.
+	///
+	/// Entries are decoded directly, bypassing the cache. The arena is used
+	/// as decoding context so that heap-backed values such as
+	/// [`bumpalo::collections::String`] allocate from it.
+	#[cfg(feature = "bumpalo")]
+	pub fn decode_all_in<'b, T: EncodeSized + DecodeFromHeap<&'b bumpalo::Bump>>(
+		&self,
+		section: Section<T>,
+		arena: &'b bumpalo::Bump,
+		heap: HeapSection,
+	) -> Result<bumpalo::collections::Vec<'b, T>, Error> {
+		let mut result =
+			bumpalo::collections::Vec::with_capacity_in(section.entry_count() as usize, arena);
+		let mut context = arena;
+		self.decode_each(section, &mut context, heap, |t| result.push(t))?;
+		Ok(result)
+	}
+
+	/// Decodes arbitrary data from the heap.
+	pub fn decode_from_heap<C, T: Decode<C>>(
+		&self,
+		context: &mut C,
+		heap: HeapSection,
+		offset: Offset,
+	) -> io::Result<T> {
+		let mut cursor = self.heap_cursor().lock();
+		cursor.decode_from_heap(context, heap, offset)
+	}
+
+	/// Decodes a fixed-size value from the heap.
+	pub fn decode_from_heap_at<C, T: EncodeSized + Decode<C>>(
+		&self,
+		context: &mut C,
+		heap: HeapSection,
+		offset: Offset,
+	) -> io::Result<T> {
+		let mut cursor = self.heap_cursor().lock();
+		cursor.decode_from_heap_at(context, heap, offset)
+	}
+
+	/// Read arbitrary data from the heap.
+	pub fn read_from_heap(
+		&self,
+		heap: HeapSection,
+		offset: Offset,
+		bytes: &mut [u8],
+	) -> io::Result<()> {
+		let mut cursor = self.heap_cursor().lock();
+		cursor.read_from_heap(heap, offset, bytes)
+	}
+}
+
+pub struct Pages<'a, 'c, R, T, O = BigEndian> {
+	reader: &'a Reader<R, O>,
+	section: Section<T>,
 	cache: &'c Cache<T>,
 	heap: HeapSection,
 	page_count: u32,
 	page_index: u32,
 }
 
-impl<'a, 'c, R, T: EncodeSized> Pages<'a, 'c, R, T> {
+impl<'a, 'c, R, T: EncodeSized, O: ByteOrder> Pages<'a, 'c, R, T, O> {
 	fn new(
-		reader: &'a Reader<R>,
+		reader: &'a Reader<R, O>,
 		section: Section<T>,
 		cache: &'c Cache<T>,
 		heap: HeapSection,
@@ -270,8 +1411,8 @@ impl<'a, 'c, R, T: EncodeSized> Pages<'a, 'c, R, T> {
 	}
 }
 
-impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>> ContextualIterator<C>
-	for Pages<'a, 'c, R, T>
+impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>, O: ByteOrder>
+	ContextualIterator<C> for Pages<'a, 'c, R, T, O>
 {
 	type Item = Result<Ref<'c, T>, Error>;
 
@@ -296,8 +1437,8 @@ impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>> Cont
 	}
 }
 
-impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap> Iterator
-	for Pages<'a, 'c, R, T>
+impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap, O: ByteOrder> Iterator
+	for Pages<'a, 'c, R, T, O>
 {
 	type Item = Result<Ref<'c, T>, Error>;
 
@@ -306,13 +1447,39 @@ impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap> Iterator
 	}
 }
 
-pub struct Iter<'a, 'c, R, T> {
-	pages: Pages<'a, 'c, R, T>,
+pub struct PageChunks<'a, 'c, R, T, O = BigEndian> {
+	pages: Pages<'a, 'c, R, T, O>,
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>, O: ByteOrder>
+	ContextualIterator<C> for PageChunks<'a, 'c, R, T, O>
+{
+	type Item = Result<Ref<'c, T, page::UnboundIter<T>>, Error>;
+
+	fn next_with(&mut self, context: &mut C) -> Option<Self::Item> {
+		self.pages
+			.next_with(context)
+			.map(|page| page.map(|page| page.map(page::IterBinder::new())))
+	}
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap, O: ByteOrder> Iterator
+	for PageChunks<'a, 'c, R, T, O>
+{
+	type Item = Result<Ref<'c, T, page::UnboundIter<T>>, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_with(no_context_mut())
+	}
+}
+
+pub struct Iter<'a, 'c, R, T, O = BigEndian> {
+	pages: Pages<'a, 'c, R, T, O>,
 	current_page: Option<Ref<'c, T, page::UnboundIter<T>>>,
 }
 
-impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>> ContextualIterator<C>
-	for Iter<'a, 'c, R, T>
+impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>, O: ByteOrder>
+	ContextualIterator<C> for Iter<'a, 'c, R, T, O>
 {
 	type Item = Result<Ref<'c, T, UnboundRef<T>>, Error>;
 
@@ -333,8 +1500,150 @@ impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>> Cont
 	}
 }
 
-impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap> Iterator
-	for Iter<'a, 'c, R, T>
+impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap, O: ByteOrder> Iterator
+	for Iter<'a, 'c, R, T, O>
+{
+	type Item = Result<Ref<'c, T, UnboundRef<T>>, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_with(no_context_mut())
+	}
+}
+
+pub struct LossyIter<'a, 'c, R, T, O = BigEndian> {
+	pages: Pages<'a, 'c, R, T, O>,
+	current_page: Option<Ref<'c, T, page::UnboundIter<T>>>,
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>, O: ByteOrder>
+	ContextualIterator<C> for LossyIter<'a, 'c, R, T, O>
+{
+	type Item = Result<Ref<'c, T, UnboundRef<T>>, (Error, u32)>;
+
+	fn next_with(&mut self, context: &mut C) -> Option<Self::Item> {
+		loop {
+			match &mut self.current_page {
+				Some(page) => match page.next() {
+					Some(entry) => break Some(Ok(entry)),
+					None => self.current_page = None,
+				},
+				None => {
+					let page_index = self.pages.page_index;
+					match self.pages.next_with(context) {
+						Some(Ok(page)) => {
+							self.current_page = Some(page.map(page::IterBinder::new()))
+						}
+						Some(Err(e)) => {
+							self.pages.page_index += 1;
+							break Some(Err((e, page_index)));
+						}
+						None => break None,
+					}
+				}
+			}
+		}
+	}
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap, O: ByteOrder> Iterator
+	for LossyIter<'a, 'c, R, T, O>
+{
+	type Item = Result<Ref<'c, T, UnboundRef<T>>, (Error, u32)>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_with(no_context_mut())
+	}
+}
+
+pub struct ChainIter<'a, 'c, 's, R, T, O = BigEndian> {
+	reader: &'a Reader<R, O>,
+	sections: std::slice::Iter<'s, Section<T>>,
+	cache: &'c Cache<T>,
+	heap: HeapSection,
+	current: Option<Iter<'a, 'c, R, T, O>>,
+}
+
+impl<'a, 'c, 's, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>, O: ByteOrder>
+	ContextualIterator<C> for ChainIter<'a, 'c, 's, R, T, O>
+{
+	type Item = Result<Ref<'c, T, UnboundRef<T>>, Error>;
+
+	fn next_with(&mut self, context: &mut C) -> Option<Self::Item> {
+		loop {
+			match &mut self.current {
+				Some(iter) => match iter.next_with(context) {
+					Some(item) => break Some(item),
+					None => self.current = None,
+				},
+				None => match self.sections.next() {
+					Some(section) => {
+						self.current = Some(self.reader.iter(*section, self.cache, self.heap))
+					}
+					None => break None,
+				},
+			}
+		}
+	}
+}
+
+impl<'a, 'c, 's, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap, O: ByteOrder> Iterator
+	for ChainIter<'a, 'c, 's, R, T, O>
+{
+	type Item = Result<Ref<'c, T, UnboundRef<T>>, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_with(no_context_mut())
+	}
+}
+
+pub struct PermutedIter<'a, 'c, R, T, O = BigEndian> {
+	reader: &'a Reader<R, O>,
+	section: Section<T>,
+	cache: &'c Cache<T>,
+	heap: HeapSection,
+	permutation: Iter<'a, 'c, R, u32, O>,
+	current_page: Option<(u32, Ref<'c, T>)>,
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>, O: ByteOrder>
+	ContextualIterator<C> for PermutedIter<'a, 'c, R, T, O>
+{
+	type Item = Result<Ref<'c, T, UnboundRef<T>>, Error>;
+
+	fn next_with(&mut self, context: &mut C) -> Option<Self::Item> {
+		let entry_index = match self.permutation.next_with(context)? {
+			Ok(i) => *i,
+			Err(e) => return Some(Err(e)),
+		};
+
+		if entry_index >= self.section.entry_count() {
+			return Some(Err(io::Error::from(io::ErrorKind::InvalidData).into()));
+		}
+
+		let page_len = self.reader.options.page_len;
+		let (page_index, i) = self.section.page_of_entry(page_len, entry_index);
+		let page = match &self.current_page {
+			Some((current_index, page)) if *current_index == page_index => page.clone_page(),
+			_ => {
+				match self
+					.reader
+					.get_page(self.section, self.cache, context, self.heap, page_index)
+				{
+					Ok(page) => {
+						self.current_page = Some((page_index, page.clone_page()));
+						page
+					}
+					Err(e) => return Some(Err(e)),
+				}
+			}
+		};
+
+		Some(Ok(page.map(GetEntryBinder::new(i))))
+	}
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap, O: ByteOrder> Iterator
+	for PermutedIter<'a, 'c, R, T, O>
 {
 	type Item = Result<Ref<'c, T, UnboundRef<T>>, Error>;
 
@@ -348,3 +1657,83 @@ pub trait ContextualIterator<C> {
 
 	fn next_with(&mut self, context: &mut C) -> Option<Self::Item>;
 }
+
+pub struct MapIter<'a, 'c, 'x, R, C, T, F, O = BigEndian> {
+	iter: Iter<'a, 'c, R, T, O>,
+	context: &'x mut C,
+	f: F,
+}
+
+/// Iterator returned by [`Reader::into_owned_iter`].
+pub type IntoOwnedIter<'a, 'c, 'x, R, C, T, O = BigEndian> =
+	MapIter<'a, 'c, 'x, R, C, T, fn(&T) -> T, O>;
+
+impl<
+		'a,
+		'c,
+		'x,
+		R: io::Seek + io::Read,
+		C,
+		T: EncodeSized + DecodeFromHeap<C>,
+		U,
+		F,
+		O: ByteOrder,
+	> Iterator for MapIter<'a, 'c, 'x, R, C, T, F, O>
+where
+	F: FnMut(&T) -> U,
+{
+	type Item = Result<U, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.iter
+			.next_with(self.context)
+			.map(|entry| entry.map(|entry| (self.f)(&entry)))
+	}
+}
+
+pub struct LiveIter<'a, 'c, R, T, O = BigEndian> {
+	iter: Iter<'a, 'c, R, T, O>,
+	tombstones: Tombstones,
+	tombstones_cache: &'c Cache<u64>,
+	index: u32,
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, C, T: EncodeSized + DecodeFromHeap<C>, O: ByteOrder>
+	ContextualIterator<C> for LiveIter<'a, 'c, R, T, O>
+{
+	type Item = Result<Ref<'c, T, UnboundRef<T>>, Error>;
+
+	fn next_with(&mut self, context: &mut C) -> Option<Self::Item> {
+		loop {
+			let entry = match self.iter.next_with(context)? {
+				Ok(entry) => entry,
+				Err(e) => break Some(Err(e)),
+			};
+
+			let i = self.index;
+			self.index += 1;
+
+			let reader = self.iter.pages.reader;
+			match reader.is_deleted(
+				self.tombstones,
+				self.tombstones_cache,
+				self.iter.pages.heap,
+				i,
+			) {
+				Ok(true) => (),
+				Ok(false) => break Some(Ok(entry)),
+				Err(e) => break Some(Err(e)),
+			}
+		}
+	}
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized + DecodeFromHeap, O: ByteOrder> Iterator
+	for LiveIter<'a, 'c, R, T, O>
+{
+	type Item = Result<Ref<'c, T, UnboundRef<T>>, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_with(no_context_mut())
+	}
+}
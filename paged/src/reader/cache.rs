@@ -1,10 +1,18 @@
 use educe::Educe;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use sharded_slab::{pool, Pool};
 use std::marker::PhantomData;
-use std::{collections::HashMap, ops::Deref, sync::Arc};
-
-use crate::ContextualIterator;
+use std::{
+	collections::{HashMap, HashSet},
+	hash::Hash,
+	ops::Deref,
+	sync::{
+		atomic::{self, AtomicUsize},
+		Arc,
+	},
+};
+
+use crate::{ContextualIterator, Section};
 
 use super::{Error, Page};
 
@@ -13,14 +21,68 @@ use super::{Error, Page};
 pub struct Cache<T> {
 	index: RwLock<HashMap<u32, usize>>,
 	pool: Pool<Page<T>>,
+	access_log: Option<Mutex<Vec<u32>>>,
+
+	/// Number of cached pages.
+	len: AtomicUsize,
+
+	/// Maximum number of cached pages.
+	capacity: Option<usize>,
 }
 
 impl<T> Cache<T> {
+	/// Creates a new cache recording every page access.
+	///
+	/// The recorded log can be retrieved with [`Self::access_log`] and
+	/// replayed later with [`Reader::warm_from_log`](crate::Reader::warm_from_log).
+	pub fn with_access_log() -> Self {
+		Self {
+			access_log: Some(Mutex::new(Vec::new())),
+			..Default::default()
+		}
+	}
+
+	/// Creates a new cache holding at most `capacity` pages.
+	///
+	/// Loading a page in a full cache fails with [`Error::PoolExhausted`],
+	/// until pages are removed with [`Self::evict`].
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self {
+			capacity: Some(capacity),
+			..Default::default()
+		}
+	}
+
+	/// Returns the number of cached pages.
+	pub fn len(&self) -> usize {
+		self.len.load(atomic::Ordering::Relaxed)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the global index of every page accessed so far, in order.
+	///
+	/// Returns an empty log if the cache was not created with
+	/// [`Self::with_access_log`].
+	pub fn access_log(&self) -> Vec<u32> {
+		self.access_log
+			.as_ref()
+			.map(|log| log.lock().clone())
+			.unwrap_or_default()
+	}
+
 	fn index_of(&self, global_page_index: u32) -> Option<usize> {
 		self.index.read().get(&global_page_index).copied()
 	}
 
-	pub fn get(&self, global_page_index: u32) -> Option<Ref<T>> {
+	/// Checks if the given page is in the cache.
+	pub fn contains(&self, global_page_index: u32) -> bool {
+		self.index_of(global_page_index).is_some()
+	}
+
+	pub fn get(&self, global_page_index: u32) -> Option<Ref<'_, T>> {
 		self.index_of(global_page_index)
 			.map(|i| Ref::new(self.pool.get(i).unwrap()))
 	}
@@ -29,30 +91,66 @@ impl<T> Cache<T> {
 		&self,
 		global_page_index: u32,
 		init: impl FnOnce(&mut Page<T>) -> Result<(), Error>,
-	) -> Result<Ref<T>, Error> {
+	) -> Result<Ref<'_, T>, Error> {
+		let capacity = self.capacity.unwrap_or(usize::MAX);
+		self.len
+			.fetch_update(
+				atomic::Ordering::Relaxed,
+				atomic::Ordering::Relaxed,
+				|len| (len < capacity).then_some(len + 1),
+			)
+			.map_err(|_| Error::PoolExhausted)?;
+
 		let mut result = Ok(());
-		let i = self
-			.pool
-			.create_with(|page| result = init(page))
-			.ok_or(Error::OutOfMemory)?;
+		let i = match self.pool.create_with(|page| result = init(page)) {
+			Some(i) => i,
+			None => {
+				self.len.fetch_sub(1, atomic::Ordering::Relaxed);
+				return Err(Error::PoolExhausted);
+			}
+		};
 
 		match result {
 			Ok(()) => {
-				self.index.write().insert(global_page_index, i);
+				// The page may have been loaded concurrently in the meantime.
+				if let Some(old) = self.index.write().insert(global_page_index, i) {
+					self.pool.clear(old);
+					self.len.fetch_sub(1, atomic::Ordering::Relaxed);
+				}
+
 				Ok(Ref::new(self.pool.get(i).unwrap()))
 			}
 			Err(e) => {
 				self.pool.clear(i);
+				self.len.fetch_sub(1, atomic::Ordering::Relaxed);
 				Err(e)
 			}
 		}
 	}
 
+	/// Removes the given page from the cache.
+	///
+	/// The slot of the page is released for reuse once every reference to
+	/// the page is dropped. Returns `false` if the page was not in the cache.
+	pub fn evict(&self, global_page_index: u32) -> bool {
+		match self.index.write().remove(&global_page_index) {
+			Some(i) => {
+				self.len.fetch_sub(1, atomic::Ordering::Relaxed);
+				self.pool.clear(i)
+			}
+			None => false,
+		}
+	}
+
 	pub fn get_or_insert(
 		&self,
 		global_page_index: u32,
 		init: impl FnOnce(&mut Page<T>) -> Result<(), Error>,
-	) -> Result<Ref<T>, Error> {
+	) -> Result<Ref<'_, T>, Error> {
+		if let Some(log) = &self.access_log {
+			log.lock().push(global_page_index)
+		}
+
 		match self.get(global_page_index) {
 			Some(page) => Ok(page),
 			None => self.set(global_page_index, init),
@@ -60,6 +158,58 @@ impl<T> Cache<T> {
 	}
 }
 
+/// Keys known to be absent from sorted sections.
+///
+/// Used by [`Reader::binary_search_cached`](crate::Reader::binary_search_cached)
+/// so that repeated searches for an absent key do not read any page. A single
+/// cache can be shared between sections, keys being recorded per section.
+#[derive(Educe)]
+#[educe(Default)]
+pub struct MissCache<K> {
+	/// Absent keys, indexed by the page offset and entry count of their
+	/// section.
+	///
+	/// The page offset alone does not identify a section: an empty section
+	/// or a checkpoint shares it with the section written after it.
+	misses: RwLock<HashMap<(u32, u32), HashSet<K>>>,
+}
+
+impl<K: Eq + Hash> MissCache<K> {
+	/// Checks if the given key is known to be absent from the given section.
+	pub fn contains<T>(&self, section: Section<T>, key: &K) -> bool {
+		self.misses
+			.read()
+			.get(&section_id(section))
+			.is_some_and(|keys| keys.contains(key))
+	}
+
+	pub(crate) fn insert<T>(&self, section: Section<T>, key: K) {
+		self.misses
+			.write()
+			.entry(section_id(section))
+			.or_default()
+			.insert(key);
+	}
+
+	/// Returns the number of recorded absent keys.
+	pub fn len(&self) -> usize {
+		self.misses.read().values().map(HashSet::len).sum()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Forgets every recorded absent key.
+	pub fn clear(&self) {
+		self.misses.write().clear()
+	}
+}
+
+fn section_id<T>(section: Section<T>) -> (u32, u32) {
+	(section.page_offset(), section.entry_count())
+}
+
 pub trait Unbound {
 	type Bound<'a>
 	where
@@ -109,9 +259,9 @@ impl<T: UnboundIterator> UnboundContextualIterator for T {
 	type UnboundItem = <Self as UnboundIterator>::UnboundItem;
 }
 
-pub struct UnboundRef<T>(PhantomData<T>);
+pub struct UnboundRef<T: ?Sized>(PhantomData<T>);
 
-impl<T> Unbound for UnboundRef<T> {
+impl<T: ?Sized> Unbound for UnboundRef<T> {
 	type Bound<'a> = &'a T where Self: 'a;
 
 	unsafe fn transmute_lifetime<'a, 'b>(value: Self::Bound<'a>) -> Self::Bound<'b>
@@ -186,10 +336,22 @@ pub struct Ref<'a, T, U: 'a + Unbound = UnboundRef<Page<T>>> {
 
 pub type EntryRef<'a, T> = Ref<'a, T, UnboundRef<T>>;
 
+pub type SliceRef<'a, T> = Ref<'a, T, UnboundRef<[T]>>;
+
 impl<'a, T> Ref<'a, T> {
 	fn new(t: pool::Ref<'a, Page<T>>) -> Self {
 		Self::new_projection(t, IdentityBinder)
 	}
+
+	/// Clones this page reference.
+	///
+	/// Unlike `clone`, this does not require `T: 'static`.
+	pub(crate) fn clone_page(&self) -> Self {
+		Self {
+			t: self.t.clone(),
+			u: self.u,
+		}
+	}
 }
 
 impl<'a, T, U: Unbound> Ref<'a, T, U> {
@@ -253,7 +415,7 @@ impl<'a, T, U: Copy> Ref<'a, T, UnboundRef<U>> {
 	}
 }
 
-impl<'a, T, U> Deref for Ref<'a, T, UnboundRef<U>> {
+impl<'a, T, U: ?Sized> Deref for Ref<'a, T, UnboundRef<U>> {
 	type Target = U;
 
 	fn deref(&self) -> &Self::Target {
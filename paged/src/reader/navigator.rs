@@ -0,0 +1,128 @@
+use std::{
+	any::{Any, TypeId},
+	collections::HashMap,
+	io,
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+	utils::{BigEndian, ByteOrder},
+	DecodeFromHeap, EncodeSized, HeapSection, Section,
+};
+
+use super::{page::GetEntryBinder, Cache, Error, Iter, Reader, Ref, UnboundRef};
+
+/// Collection of caches, one per entry type.
+///
+/// Since pages are identified by their global index, a single cache can be
+/// shared by every section of the same type.
+#[derive(Default)]
+pub struct Caches {
+	map: Mutex<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+impl Caches {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the cache for entries of type `T`, creating it if necessary.
+	pub fn get<T: 'static + Send + Sync>(&self) -> &Cache<T> {
+		let mut map = self.map.lock();
+		let cache: *const Cache<T> = map
+			.entry(TypeId::of::<T>())
+			.or_insert_with(|| Box::<Cache<T>>::default())
+			.downcast_ref()
+			.unwrap();
+
+		// SAFETY: caches are boxed and never removed from the map, so they
+		//         live as long as `self`.
+		unsafe { &*cache }
+	}
+}
+
+/// Navigates the sections of a file, using one cache per entry type.
+pub struct Navigator<'a, 'c, R, O = BigEndian> {
+	reader: &'a Reader<R, O>,
+	caches: &'c Caches,
+	heap: HeapSection,
+}
+
+impl<'a, 'c, R, O: ByteOrder> Navigator<'a, 'c, R, O> {
+	pub fn new(reader: &'a Reader<R, O>, caches: &'c Caches, heap: HeapSection) -> Self {
+		Self {
+			reader,
+			caches,
+			heap,
+		}
+	}
+
+	/// Binds the given section to the reader, with the cache of its type.
+	pub fn section<T: 'static + Send + Sync>(
+		&self,
+		section: Section<T>,
+	) -> SectionReader<'a, 'c, R, T, O> {
+		SectionReader {
+			reader: self.reader,
+			section,
+			cache: self.caches.get(),
+			heap: self.heap,
+		}
+	}
+}
+
+/// Section bound to a reader, a cache and a heap.
+pub struct SectionReader<'a, 'c, R, T, O = BigEndian> {
+	reader: &'a Reader<R, O>,
+	section: Section<T>,
+	cache: &'c Cache<T>,
+	heap: HeapSection,
+}
+
+impl<'a, 'c, R, T, O: ByteOrder> SectionReader<'a, 'c, R, T, O> {
+	pub fn section(&self) -> Section<T> {
+		self.section
+	}
+
+	pub fn len(&self) -> u32 {
+		self.section.entry_count()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.section.entry_count() == 0
+	}
+}
+
+impl<'a, 'c, R: io::Seek + io::Read, T: EncodeSized, O: ByteOrder> SectionReader<'a, 'c, R, T, O> {
+	pub fn get_page<C>(&self, context: &mut C, page_index: u32) -> Result<Ref<'c, T>, Error>
+	where
+		T: DecodeFromHeap<C>,
+	{
+		self.reader
+			.get_page(self.section, self.cache, context, self.heap, page_index)
+	}
+
+	pub fn get<C>(
+		&self,
+		context: &mut C,
+		entry_index: u32,
+	) -> Result<Option<Ref<'c, T, UnboundRef<T>>>, Error>
+	where
+		T: DecodeFromHeap<C>,
+	{
+		if entry_index < self.section.entry_count() {
+			let (page_index, i) = self
+				.section
+				.page_of_entry(self.reader.options.page_len, entry_index);
+			let page = self.get_page(context, page_index)?;
+			Ok(Some(page.map(GetEntryBinder::new(i))))
+		} else {
+			Ok(None)
+		}
+	}
+
+	pub fn iter(&self) -> Iter<'a, 'c, R, T, O> {
+		self.reader.iter(self.section, self.cache, self.heap)
+	}
+}
@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, marker::PhantomData};
+use std::{cmp::Ordering, marker::PhantomData, ops::Range};
 
 use super::cache::{Binder, UnboundRef, UnboundSliceIter};
 
@@ -19,7 +19,11 @@ impl<T> Page<T> {
 		self.entries.get(i as usize)
 	}
 
-	pub fn iter(&self) -> Iter<T> {
+	pub fn as_slice(&self) -> &[T] {
+		&self.entries
+	}
+
+	pub fn iter(&self) -> Iter<'_, T> {
 		self.entries.iter()
 	}
 
@@ -45,6 +49,18 @@ impl<T> Page<T> {
 	pub fn push(&mut self, entry: T) {
 		self.entries.push(entry)
 	}
+
+	/// Reads `len` plain-old-data entries at once, reinterpreting the input
+	/// bytes as `T` values without decoding them.
+	#[cfg(feature = "bytemuck")]
+	pub fn read_pod(&mut self, input: &mut impl std::io::Read, len: u32) -> std::io::Result<()>
+	where
+		T: bytemuck::Pod,
+	{
+		let start = self.entries.len();
+		self.entries.resize(start + len as usize, T::zeroed());
+		input.read_exact(bytemuck::cast_slice_mut(&mut self.entries[start..]))
+	}
 }
 
 impl<T> sharded_slab::Clear for Page<T> {
@@ -80,6 +96,29 @@ impl<'a, T> Binder<'a, UnboundRef<Page<T>>, UnboundRef<T>> for GetEntryBinder<T>
 	}
 }
 
+pub struct GetSliceBinder<T> {
+	range: Range<u32>,
+	t: PhantomData<T>,
+}
+
+impl<T> GetSliceBinder<T> {
+	pub fn new(range: Range<u32>) -> Self {
+		Self {
+			range,
+			t: PhantomData,
+		}
+	}
+}
+
+impl<'a, T> Binder<'a, UnboundRef<Page<T>>, UnboundRef<[T]>> for GetSliceBinder<T> {
+	fn bind<'t>(self, page: &'t Page<T>) -> &'t [T]
+	where
+		'a: 't,
+	{
+		&page.as_slice()[self.range.start as usize..self.range.end as usize]
+	}
+}
+
 pub struct IterBinder<T>(PhantomData<T>);
 
 impl<T> Default for IterBinder<T> {
@@ -0,0 +1,162 @@
+use std::{
+	fs::File,
+	io::{self, BufReader},
+	marker::PhantomData,
+	os::unix::fs::FileExt,
+	sync::Arc,
+};
+
+use crate::{
+	heap,
+	heap::Offset,
+	utils::{BigEndian, ByteOrder, Checksum, Codec},
+	Decode, DecodeFromHeap, EncodeSized, HeapSection, Section,
+};
+
+use super::{page::GetEntryBinder, Cache, Cursor, Error, Options, PackedHeaps, Ref, UnboundRef};
+
+/// File input reading at an explicit position.
+///
+/// Each instance tracks its own position, so that instances reading the same
+/// file do not interfere with each other.
+pub struct PositionedInput<'a> {
+	file: &'a File,
+	position: u64,
+}
+
+impl<'a> io::Read for PositionedInput<'a> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let len = self.file.read_at(buf, self.position)?;
+		self.position += len as u64;
+		Ok(len)
+	}
+}
+
+impl<'a> io::Seek for PositionedInput<'a> {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		let position = match pos {
+			io::SeekFrom::Start(p) => Some(p),
+			io::SeekFrom::Current(d) => self.position.checked_add_signed(d),
+			io::SeekFrom::End(d) => self.file.metadata()?.len().checked_add_signed(d),
+		};
+
+		self.position = position.ok_or(io::ErrorKind::InvalidInput)?;
+		Ok(self.position)
+	}
+}
+
+/// File reader using positioned reads.
+///
+/// Unlike [`Reader`](super::Reader), no cursor is shared between reads: every
+/// page is loaded with its own cursor using positioned reads
+/// (`pread`), so that pages can be decoded concurrently without contention.
+pub struct PositionedReader<O = BigEndian> {
+	file: File,
+	options: Options,
+	packed_heaps: Option<Arc<PackedHeaps>>,
+	byte_order: PhantomData<O>,
+}
+
+impl PositionedReader {
+	/// Creates a new reader of a big-endian file.
+	pub fn new(file: File, page_len: u32, first_page_offset: u32) -> Self {
+		Self {
+			file,
+			options: Options::new(page_len, first_page_offset),
+			packed_heaps: None,
+			byte_order: PhantomData,
+		}
+	}
+}
+
+impl<O: ByteOrder> PositionedReader<O> {
+	/// Sets the byte order of the numbers encoded in the file (see
+	/// [`Reader::with_byte_order`](super::Reader::with_byte_order)).
+	pub fn with_byte_order<P: ByteOrder>(self) -> PositionedReader<P> {
+		PositionedReader {
+			file: self.file,
+			options: self.options,
+			packed_heaps: self.packed_heaps,
+			byte_order: PhantomData,
+		}
+	}
+
+	/// Reads heaps packed with the given codec and checksum (see
+	/// [`Reader::with_codec`](super::Reader::with_codec)).
+	pub fn with_codec<K: Codec, H: Checksum>(mut self) -> Self {
+		self.packed_heaps = Some(Arc::new(PackedHeaps::new(heap::unpack::<K, H>)));
+		self
+	}
+
+	fn cursor_at(&self, offset: u32) -> Cursor<BufReader<PositionedInput<'_>>, O> {
+		Cursor {
+			input: BufReader::with_capacity(
+				self.options.page_len as usize,
+				PositionedInput {
+					file: &self.file,
+					position: offset as u64,
+				},
+			),
+			current_offset: offset,
+			positioned: true,
+			options: self.options,
+			heap_entries: None,
+			heap_depth: 0,
+			heap_cursor: None,
+			packed_heaps: self.packed_heaps.clone(),
+			heap_data: None,
+			byte_order: PhantomData,
+		}
+	}
+
+	pub fn get_page<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &'a Cache<T>,
+		context: &mut C,
+		heap: HeapSection,
+		page_index: u32,
+	) -> Result<Ref<'a, T>, Error> {
+		let global_page_index = page_index + section.page_offset();
+		cache.get_or_insert(global_page_index, |page| {
+			let offset = self.options.first_page_offset
+				+ section.offset_of_page(self.options.page_len, page_index);
+			let entry_count = section.page_size(self.options.page_len, page_index);
+
+			let mut cursor = self.cursor_at(offset);
+			for _ in 0..entry_count {
+				page.push(T::decode_from_heap(&mut cursor, context, heap)?)
+			}
+
+			Ok(())
+		})
+	}
+
+	pub fn get<'a, C, T: EncodeSized + DecodeFromHeap<C>>(
+		&self,
+		section: Section<T>,
+		cache: &'a Cache<T>,
+		context: &mut C,
+		heap: HeapSection,
+		entry_index: u32,
+	) -> Result<Option<Ref<'a, T, UnboundRef<T>>>, Error> {
+		if entry_index < section.entry_count() {
+			let (page_index, i) = section.page_of_entry(self.options.page_len, entry_index);
+			let page = self.get_page(section, cache, context, heap, page_index)?;
+			Ok(Some(page.map(GetEntryBinder::new(i))))
+		} else {
+			Ok(None)
+		}
+	}
+
+	/// Decodes arbitrary data from the heap.
+	pub fn decode_from_heap<C, T: Decode<C>>(
+		&self,
+		context: &mut C,
+		heap: HeapSection,
+		offset: Offset,
+	) -> io::Result<T> {
+		let mut cursor = self.cursor_at(self.options.first_page_offset);
+		cursor.decode_from_heap(context, heap, offset)
+	}
+}
@@ -0,0 +1,76 @@
+use std::io;
+
+use crate::{
+	heap,
+	utils::{BigEndian, ByteOrder},
+	HeapSection,
+};
+
+use super::Reader;
+
+/// Input reading a heap entry of another reader's file.
+///
+/// Reads go through the heap cursor of the outer reader (see
+/// [`Reader::with_heap_input`]), at the offset of the entry plus the
+/// position of this input.
+pub struct SubReader<'a, R, O = BigEndian> {
+	reader: &'a Reader<R, O>,
+	heap: HeapSection,
+	entry: heap::Entry,
+	position: u64,
+}
+
+impl<'a, R, O: ByteOrder> SubReader<'a, R, O> {
+	pub(crate) fn new(
+		reader: &'a Reader<R, O>,
+		heap: HeapSection,
+		entry: heap::Entry,
+		position: u32,
+	) -> Self {
+		Self {
+			reader,
+			heap,
+			entry,
+			position: position as u64,
+		}
+	}
+
+	/// Returns the length of the range, in bytes.
+	pub fn len(&self) -> u32 {
+		self.entry.len
+	}
+
+	/// Checks if the range is empty.
+	pub fn is_empty(&self) -> bool {
+		self.entry.len == 0
+	}
+}
+
+impl<'a, R: io::Seek + io::Read, O: ByteOrder> io::Read for SubReader<'a, R, O> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let remaining = (self.entry.len as u64).saturating_sub(self.position);
+		let n = (buf.len() as u64).min(remaining) as usize;
+		if n == 0 {
+			return Ok(0);
+		}
+
+		let offset = self.entry.offset.advance(self.position as u32);
+		self.reader
+			.read_from_heap(self.heap, offset, &mut buf[..n])?;
+		self.position += n as u64;
+		Ok(n)
+	}
+}
+
+impl<'a, R, O: ByteOrder> io::Seek for SubReader<'a, R, O> {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		let position = match pos {
+			io::SeekFrom::Start(p) => Some(p),
+			io::SeekFrom::Current(d) => self.position.checked_add_signed(d),
+			io::SeekFrom::End(d) => (self.entry.len as u64).checked_add_signed(d),
+		};
+
+		self.position = position.ok_or(io::ErrorKind::InvalidInput)?;
+		Ok(self.position)
+	}
+}
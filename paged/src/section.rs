@@ -4,7 +4,8 @@ use educe::Educe;
 
 use crate::{
 	encode::{Encode, EncodeSized},
-	utils::CeilingDiv,
+	too_large_error,
+	utils::{BigEndian, ByteOrder, CeilingDiv, Input, Ordered, Output},
 	Decode, DecodeFromHeap, EncodeOnHeap, Heap,
 };
 
@@ -16,7 +17,37 @@ pub struct Section<T> {
 	t: PhantomData<T>,
 }
 
+/// Bitmap marking deleted entries of a section.
+///
+/// Bit `i % 64` of the word `i / 64` is set if entry `i` is deleted.
+/// Such a bitmap is built with [`Encoder::tombstones_from_iter`](crate::Encoder::tombstones_from_iter).
+pub type Tombstones = Section<u64>;
+
+/// Key of the first entry of each page of a sorted section.
+///
+/// Such an index is built with [`Encoder::indexed_section_from_iter`](crate::Encoder::indexed_section_from_iter).
+pub type PageIndex<K> = Section<K>;
+
 impl<T> Section<T> {
+	/// Creates a section from the byte offset of its first page, relative to
+	/// the first page of the file, and its number of entries.
+	///
+	/// # Panics
+	///
+	/// Panics if `byte_offset` is not a multiple of `page_len`.
+	pub fn from_byte_offset(byte_offset: u32, entry_count: u32, page_len: u32) -> Self {
+		assert!(
+			byte_offset.is_multiple_of(page_len),
+			"section byte offset ({byte_offset}) is not page-aligned"
+		);
+
+		Self {
+			page_offset: byte_offset / page_len,
+			entry_count,
+			t: PhantomData,
+		}
+	}
+
 	pub fn page_offset(&self) -> u32 {
 		self.page_offset
 	}
@@ -31,28 +62,39 @@ impl<T> Section<T> {
 }
 
 impl<T: EncodeSized> Section<T> {
+	/// Returns the number of entries stored in each page.
+	///
+	/// Zero-sized entries all fit in a single page.
+	pub fn entries_per_page(page_len: u32) -> u32 {
+		entries_per_page(page_len, T::ENCODED_SIZE)
+	}
+
 	pub fn page_count(&self, page_len: u32) -> u32 {
-		let entries_per_page = page_len / T::ENCODED_SIZE;
+		let entries_per_page = Self::entries_per_page(page_len);
 		self.entry_count.ceiling_div(entries_per_page)
 	}
 
 	pub fn page_size(&self, page_len: u32, i: u32) -> u32 {
-		let entries_per_page = page_len / T::ENCODED_SIZE;
+		let entries_per_page = Self::entries_per_page(page_len);
 		let past_entry_count = entries_per_page * i;
 		let rest_entry_count = self.entry_count - past_entry_count;
 		std::cmp::min(entries_per_page, rest_entry_count)
 	}
 
 	pub fn page_of_entry(&self, page_len: u32, i: u32) -> (u32, u32) {
-		let entries_per_page = page_len / T::ENCODED_SIZE;
+		let entries_per_page = Self::entries_per_page(page_len);
 		let page = i / entries_per_page;
 		let local_i = i % entries_per_page;
 		(page, local_i)
 	}
 }
 
+fn entries_per_page(page_len: u32, entry_size: u32) -> u32 {
+	page_len.checked_div(entry_size).unwrap_or(u32::MAX)
+}
+
 impl<C, T> Encode<C> for Section<T> {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
 		self.page_offset.encode(context, output)?;
 		self.entry_count.encode(context, output)?;
 		Ok(Self::ENCODED_SIZE)
@@ -60,11 +102,11 @@ impl<C, T> Encode<C> for Section<T> {
 }
 
 impl<C, T> EncodeOnHeap<C> for Section<T> {
-	fn encode_on_heap(
+	fn encode_on_heap<W: Output>(
 		&self,
 		context: &C,
-		_heap: &mut Heap,
-		output: &mut impl io::Write,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
 	) -> io::Result<u32> {
 		Self::encode(self, context, output)
 	}
@@ -75,8 +117,8 @@ impl<T> EncodeSized for Section<T> {
 }
 
 impl<C, T> DecodeFromHeap<C> for Section<T> {
-	fn decode_from_heap<R: io::Seek + io::Read>(
-		input: &mut crate::reader::Cursor<R>,
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut crate::reader::Cursor<R, O>,
 		context: &mut C,
 		_heap: crate::HeapSection,
 	) -> io::Result<Self> {
@@ -85,7 +127,7 @@ impl<C, T> DecodeFromHeap<C> for Section<T> {
 }
 
 impl<C, T> Decode<C> for Section<T> {
-	fn decode<R: io::Read>(input: &mut R, context: &mut C) -> io::Result<Self> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
 		Ok(Self {
 			page_offset: u32::decode(input, context)?,
 			entry_count: u32::decode(input, context)?,
@@ -94,31 +136,172 @@ impl<C, T> Decode<C> for Section<T> {
 	}
 }
 
-pub struct Encoder<'a, 'h, W, T> {
-	encoder: &'a mut super::Encoder<W>,
-	heap: &'h mut Heap,
+/// Section whose entry size is chosen at run time.
+///
+/// Entries are encoded with [`Encode`] and padded to the entry size of the
+/// section, which is stored along with the section.
+#[derive(Educe)]
+#[educe(Debug, Clone, Copy)]
+pub struct DynamicSection<T> {
 	page_offset: u32,
+	entry_count: u32,
+	entry_size: u32,
+	t: PhantomData<T>,
+}
+
+impl<T> DynamicSection<T> {
+	pub fn page_offset(&self) -> u32 {
+		self.page_offset
+	}
+
+	pub fn entry_count(&self) -> u32 {
+		self.entry_count
+	}
+
+	pub fn entry_size(&self) -> u32 {
+		self.entry_size
+	}
+
+	pub fn offset_of_page(&self, page_len: u32, i: u32) -> u32 {
+		(self.page_offset + i) * page_len
+	}
+
+	/// Returns the number of entries stored in each page.
+	pub fn entries_per_page(&self, page_len: u32) -> u32 {
+		entries_per_page(page_len, self.entry_size)
+	}
+
+	/// Returns the number of pages of the section.
+	///
+	/// # Panics
+	///
+	/// Panics if the entry size is greater than `page_len`, which
+	/// [`Reader`](crate::Reader) checks before using a section.
+	pub fn page_count(&self, page_len: u32) -> u32 {
+		self.entry_count
+			.ceiling_div(self.entries_per_page(page_len))
+	}
+
+	pub fn page_size(&self, page_len: u32, i: u32) -> u32 {
+		let entries_per_page = self.entries_per_page(page_len);
+		let past_entry_count = entries_per_page * i;
+		let rest_entry_count = self.entry_count - past_entry_count;
+		std::cmp::min(entries_per_page, rest_entry_count)
+	}
+
+	pub fn page_of_entry(&self, page_len: u32, i: u32) -> (u32, u32) {
+		let entries_per_page = self.entries_per_page(page_len);
+		(i / entries_per_page, i % entries_per_page)
+	}
+}
+
+impl<C, T> Encode<C> for DynamicSection<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.page_offset.encode(context, output)?;
+		self.entry_count.encode(context, output)?;
+		self.entry_size.encode(context, output)?;
+		Ok(Self::ENCODED_SIZE)
+	}
+}
+
+impl<C, T> EncodeOnHeap<C> for DynamicSection<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		Self::encode(self, context, output)
+	}
+}
+
+impl<T> EncodeSized for DynamicSection<T> {
+	const ENCODED_SIZE: u32 = 3 * u32::ENCODED_SIZE;
+}
+
+impl<C, T> DecodeFromHeap<C> for DynamicSection<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut crate::reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: crate::HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
+
+impl<C, T> Decode<C> for DynamicSection<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		Ok(Self {
+			page_offset: u32::decode(input, context)?,
+			entry_count: u32::decode(input, context)?,
+			entry_size: u32::decode(input, context)?,
+			t: PhantomData,
+		})
+	}
+}
+
+/// Checks that entries of the given size fit in a page.
+pub(crate) fn check_entry_size(entry_size: u32, page_len: u32) -> io::Result<()> {
+	if entry_size > page_len {
+		Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("entry size ({entry_size} bytes) exceeds the page length ({page_len} bytes)"),
+		))
+	} else {
+		Ok(())
+	}
+}
+
+/// Section encoder.
+///
+/// Dropping the encoder without calling [`end`](Self::end), for instance
+/// when returning early on an error, pads the last page of the section so
+/// that the following sections and heaps remain aligned on pages. The
+/// entries pushed so far are then left in the file without any [`Section`]
+/// referencing them, unless one was obtained through
+/// [`checkpoint`](Self::checkpoint).
+pub struct Encoder<'a, 'h, W: io::Write + io::Seek, T, O: ByteOrder = BigEndian> {
+	encoder: &'a mut super::Encoder<W, O>,
+	heap: &'h mut Heap<O>,
+	page_offset: u32,
+	entry_size: u32,
 	len: u32,
 	entry_count: u32,
 	empty_page: bool,
+	ended: bool,
 	t: PhantomData<T>,
 }
 
-impl<'a, 'h, W, T> Encoder<'a, 'h, W, T> {
+impl<'a, 'h, W: io::Write + io::Seek, T, O: ByteOrder> Encoder<'a, 'h, W, T, O> {
 	pub(crate) fn new(
-		encoder: &'a mut super::Encoder<W>,
-		heap: &'h mut Heap,
+		encoder: &'a mut super::Encoder<W, O>,
+		heap: &'h mut Heap<O>,
 		page_offset: u32,
-	) -> Self {
-		Self {
+	) -> io::Result<Self>
+	where
+		T: EncodeSized,
+	{
+		Self::with_entry_size(encoder, heap, page_offset, T::ENCODED_SIZE)
+	}
+
+	fn with_entry_size(
+		encoder: &'a mut super::Encoder<W, O>,
+		heap: &'h mut Heap<O>,
+		page_offset: u32,
+		entry_size: u32,
+	) -> io::Result<Self> {
+		check_entry_size(entry_size, encoder.page_len)?;
+		Ok(Self {
 			encoder,
 			heap,
 			page_offset,
+			entry_size,
 			len: 0,
 			entry_count: 0,
 			empty_page: true,
+			ended: false,
 			t: PhantomData,
-		}
+		})
 	}
 
 	pub fn page_count(&self) -> u32 {
@@ -135,37 +318,254 @@ impl<'a, 'h, W, T> Encoder<'a, 'h, W, T> {
 	}
 }
 
-impl<'a, 'h, W: io::Write + io::Seek, T> Encoder<'a, 'h, W, T> {
+impl<'a, 'h, W: io::Write + io::Seek, T, O: ByteOrder> Encoder<'a, 'h, W, T, O> {
 	pub fn push<C>(&mut self, context: &C, value: &T) -> io::Result<()>
 	where
 		T: EncodeOnHeap<C>,
 	{
-		let len = value.encode_on_heap(context, self.heap, &mut self.encoder.output)?;
+		self.begin_entry()?;
+		let len = value.encode_on_heap(
+			context,
+			self.heap,
+			&mut Ordered::<_, O>::new(&mut self.encoder.output),
+		)?;
+		self.end_entry(len)
+	}
+
+	/// Pushes an already encoded entry.
+	///
+	/// This allows copying entries from another file without decoding them,
+	/// as long as the heap data they reference is left unchanged.
+	///
+	/// # Panics
+	///
+	/// Panics if the length of `bytes` is not `T::ENCODED_SIZE`.
+	pub fn push_raw(&mut self, bytes: &[u8]) -> io::Result<()>
+	where
+		T: EncodeSized,
+	{
+		assert_eq!(bytes.len() as u32, T::ENCODED_SIZE);
+		self.begin_entry()?;
+		self.encoder.output.write_all(bytes)?;
+		self.end_entry(T::ENCODED_SIZE)
+	}
+
+	/// Pushes a plain-old-data entry using its native memory layout.
+	///
+	/// Entries pushed this way can be loaded without decoding using
+	/// [`Reader::get_page_pod`](crate::Reader::get_page_pod). Fails with
+	/// [`io::ErrorKind::InvalidInput`] unless the encoder uses the native byte
+	/// order of the host (see
+	/// [`Encoder::with_byte_order`](crate::Encoder::with_byte_order)), so
+	/// that the entries are stored in the byte order of the file.
+	#[cfg(feature = "bytemuck")]
+	pub fn push_pod(&mut self, value: &T) -> io::Result<()>
+	where
+		T: EncodeSized + bytemuck::Pod,
+	{
+		assert_eq!(std::mem::size_of::<T>() as u32, T::ENCODED_SIZE);
+		if O::ENDIANNESS != crate::utils::Endianness::NATIVE {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"plain-old-data entries require the native byte order",
+			));
+		}
+
+		self.begin_entry()?;
+		self.encoder.output.write_all(bytemuck::bytes_of(value))?;
+		self.end_entry(T::ENCODED_SIZE)
+	}
 
+	fn begin_entry(&mut self) -> io::Result<()> {
 		if self.empty_page {
-			self.encoder.page_count += 1;
+			self.encoder.claim_page()?;
 			self.empty_page = false;
 		}
 
-		self.len += len;
-		self.entry_count += 1;
+		Ok(())
+	}
+
+	fn end_entry(&mut self, len: u32) -> io::Result<()> {
+		self.len = self.len.checked_add(len).ok_or_else(too_large_error)?;
+		self.entry_count = self
+			.entry_count
+			.checked_add(1)
+			.ok_or_else(too_large_error)?;
 
 		let padding = self.padding();
-		if padding < T::ENCODED_SIZE {
+		if padding < self.entry_size {
 			self.encoder.pad(padding)?;
-			self.len += padding;
+			self.len = self.len.checked_add(padding).ok_or_else(too_large_error)?;
 			self.empty_page = true
 		}
 
 		Ok(())
 	}
 
-	pub fn end(self) -> io::Result<Section<T>> {
-		self.encoder.pad(self.padding())?;
+	/// Flushes the output and returns the section made of the entries pushed
+	/// so far.
+	///
+	/// The encoding can continue after a checkpoint. If it is interrupted,
+	/// the returned section can still be used to read the entries written
+	/// before the checkpoint.
+	pub fn checkpoint(&mut self) -> io::Result<Section<T>> {
+		self.encoder.output.flush()?;
+		Ok(Section {
+			page_offset: self.page_offset,
+			entry_count: self.entry_count,
+			t: PhantomData,
+		})
+	}
+
+	/// Ends the section.
+	///
+	/// A non-empty section of zero-sized entries still occupies a single
+	/// blank page, giving it its own page index in caches.
+	pub fn end(mut self) -> io::Result<Section<T>> {
+		self.finish()?;
 		Ok(Section {
 			page_offset: self.page_offset,
 			entry_count: self.entry_count,
 			t: PhantomData,
 		})
 	}
+
+	/// Ends the section and flushes its heap right after it.
+	///
+	/// See [`Encoder::flush_heap`](crate::Encoder::flush_heap).
+	pub fn end_with_heap(mut self) -> io::Result<(Section<T>, crate::HeapSection)> {
+		self.finish()?;
+		let heap = self.encoder.flush_heap(self.heap)?;
+		Ok((
+			Section {
+				page_offset: self.page_offset,
+				entry_count: self.entry_count,
+				t: PhantomData,
+			},
+			heap,
+		))
+	}
+
+	fn finish(&mut self) -> io::Result<()> {
+		self.ended = true;
+
+		if self.entry_size == 0 && !self.empty_page {
+			self.encoder.pad(self.encoder.page_len)?;
+		} else {
+			self.encoder.pad(self.padding())?;
+		}
+
+		self.encoder
+			.add_region(self.page_offset..self.encoder.page_count);
+		Ok(())
+	}
+}
+
+impl<'a, 'h, W: io::Write + io::Seek, T, O: ByteOrder> Drop for Encoder<'a, 'h, W, T, O> {
+	fn drop(&mut self) {
+		if !self.ended {
+			// Errors cannot be reported here. The following write will most
+			// likely fail the same way.
+			let _ = self.finish();
+		}
+	}
+}
+
+/// Encoder of a [`DynamicSection`].
+///
+/// Dropping it without calling [`end`](Self::end) behaves like dropping a
+/// [`section::Encoder`](Encoder).
+pub struct DynamicEncoder<'a, 'h, W: io::Write + io::Seek, T, O: ByteOrder = BigEndian>(
+	Encoder<'a, 'h, W, T, O>,
+);
+
+impl<'a, 'h, W: io::Write + io::Seek, T, O: ByteOrder> DynamicEncoder<'a, 'h, W, T, O> {
+	pub(crate) fn new(
+		encoder: &'a mut super::Encoder<W, O>,
+		heap: &'h mut Heap<O>,
+		page_offset: u32,
+		entry_size: u32,
+	) -> io::Result<Self> {
+		Encoder::with_entry_size(encoder, heap, page_offset, entry_size).map(Self)
+	}
+
+	pub fn entry_size(&self) -> u32 {
+		self.0.entry_size
+	}
+
+	pub fn page_count(&self) -> u32 {
+		self.0.page_count()
+	}
+}
+
+impl<'a, 'h, W: io::Write + io::Seek, T, O: ByteOrder> DynamicEncoder<'a, 'h, W, T, O> {
+	/// Pushes an entry, padded to the entry size of the section.
+	///
+	/// Fails with [`io::ErrorKind::InvalidInput`] if the encoded entry is
+	/// larger than the entry size.
+	pub fn push<C>(&mut self, context: &C, value: &T) -> io::Result<()>
+	where
+		T: Encode<C>,
+	{
+		let entry_size = self.0.entry_size;
+		let mut bytes = Vec::with_capacity(entry_size as usize);
+		value.encode(context, &mut Ordered::<_, O>::new(&mut bytes))?;
+		if bytes.len() > entry_size as usize {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"entry exceeds the section entry size",
+			));
+		}
+
+		bytes.resize(entry_size as usize, 0);
+		self.0.begin_entry()?;
+		self.0.encoder.output.write_all(&bytes)?;
+		self.0.end_entry(entry_size)
+	}
+
+	pub fn end(self) -> io::Result<DynamicSection<T>> {
+		let entry_size = self.0.entry_size;
+		let section = self.0.end()?;
+		Ok(DynamicSection {
+			page_offset: section.page_offset,
+			entry_count: section.entry_count,
+			entry_size,
+			t: PhantomData,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{reader::Cache, Heap, Reader};
+
+	#[test]
+	fn array_entries() {
+		let entries: Vec<[u32; 4]> = (0..50).map(|i| [i, i + 1, i + 2, i + 3]).collect();
+
+		let mut encoder = crate::Encoder::new(Cursor::new(Vec::new()), 64);
+		let mut heap = Heap::new();
+		let section = encoder
+			.section_from_iter(&mut heap, entries.iter())
+			.unwrap();
+		let heap = encoder.add_heap(heap).unwrap();
+		let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+		let cache = Cache::default();
+
+		let entry = reader.get(section, &cache, &mut (), heap, 3).unwrap();
+		assert_eq!(entry.map(|t| *t), Some([3, 4, 5, 6]));
+
+		let iterated: Vec<[u32; 4]> = reader
+			.iter(section, &cache, heap)
+			.map(|t| *t.unwrap())
+			.collect();
+		assert_eq!(iterated, entries);
+
+		let found = reader
+			.binary_search_by_key(section, &cache, &mut (), heap, |t, _| t[0].cmp(&42))
+			.unwrap();
+		assert_eq!(found.map(|t| *t), Some([42, 43, 44, 45]));
+	}
 }
@@ -1,6 +1,19 @@
-use std::io;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Read};
+use std::marker::PhantomData;
+use std::num::{
+	NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+	NonZeroU32, NonZeroU64, NonZeroU8,
+};
+use std::path::{Component, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::{Decode, Encode, EncodeSized};
+use crate::{
+	heap, reader, Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Heap, HeapSection,
+};
 
 pub trait CeilingDiv {
 	fn ceiling_div(self, other: Self) -> Self;
@@ -8,7 +21,7 @@ pub trait CeilingDiv {
 
 impl CeilingDiv for u32 {
 	fn ceiling_div(self, other: Self) -> Self {
-		(self + other - 1) / other
+		self.div_ceil(other)
 	}
 }
 
@@ -20,6 +33,64 @@ pub const fn max(a: u32, b: u32) -> u32 {
 	}
 }
 
+/// Writes `len` zero bytes, without allocating.
+pub fn write_padding(output: &mut impl io::Write, len: u32) -> io::Result<()> {
+	const ZEROS: [u8; 64] = [0; 64];
+	let mut len = len as usize;
+
+	while len > 0 {
+		let n = len.min(ZEROS.len());
+		output.write_all(&ZEROS[..n])?;
+		len -= n;
+	}
+
+	Ok(())
+}
+
+/// Encodes the given value as a LEB128 varint.
+///
+/// Returns the number of bytes written.
+pub fn encode_varint(mut value: u64, output: &mut impl io::Write) -> io::Result<u32> {
+	let mut len = 0;
+
+	loop {
+		let byte = (value & 0x7f) as u8;
+		value >>= 7;
+		len += 1;
+
+		if value == 0 {
+			output.write_all(&[byte])?;
+			break Ok(len);
+		}
+
+		output.write_all(&[byte | 0x80])?;
+	}
+}
+
+/// Decodes a LEB128 varint.
+pub fn decode_varint(input: &mut impl io::Read) -> io::Result<u64> {
+	let mut result = 0u64;
+	let mut shift = 0;
+
+	loop {
+		let mut byte = [0u8];
+		input.read_exact(&mut byte)?;
+		let byte = byte[0];
+
+		if shift == 63 && byte > 1 {
+			break Err(io::ErrorKind::InvalidData.into());
+		}
+
+		result |= ((byte & 0x7f) as u64) << shift;
+
+		if byte & 0x80 == 0 {
+			break Ok(result);
+		}
+
+		shift += 7;
+	}
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Inline<T>(pub T);
 
@@ -38,14 +109,14 @@ impl<T> std::ops::DerefMut for Inline<T> {
 }
 
 impl<C, T: Encode<C>> Encode<C> for Inline<Vec<T>> {
-	fn encode(&self, context: &C, output: &mut impl io::Write) -> io::Result<u32> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
 		(self.0.len() as u32).encode(context, output)?;
 		Ok(u32::ENCODED_SIZE + self.0.as_slice().encode(context, output)?)
 	}
 }
 
 impl<C, T: Decode<C>> Decode<C> for Inline<Vec<T>> {
-	fn decode<R: io::Read>(input: &mut R, context: &mut C) -> io::Result<Self> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
 		let len = u32::decode(input, context)?;
 		let mut result = Vec::with_capacity(len as usize);
 
@@ -56,3 +127,1361 @@ impl<C, T: Decode<C>> Decode<C> for Inline<Vec<T>> {
 		Ok(Self(result))
 	}
 }
+
+/// Signed integer encoded as a zigzag LEB128 varint.
+///
+/// Values of small magnitude, positive or negative, take a single byte. The
+/// encoded size depends on the value, so this does not implement
+/// [`EncodeSized`]: it can only be used where [`Inline`] can, for instance
+/// as heap data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Zigzag<T>(pub T);
+
+impl<T> std::ops::Deref for Zigzag<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> std::ops::DerefMut for Zigzag<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+macro_rules! zigzag {
+	($($ty:ty : $unsigned:ty),*) => {
+		$(
+			impl<C> Encode<C> for Zigzag<$ty> {
+				fn encode(&self, _context: &C, output: &mut impl Output) -> io::Result<u32> {
+					let value = ((self.0 << 1) ^ (self.0 >> (<$ty>::BITS - 1))) as $unsigned;
+					encode_varint(value as u64, output)
+				}
+			}
+
+			impl<C> Decode<C> for Zigzag<$ty> {
+				fn decode<R: Input>(input: &mut R, _context: &mut C) -> io::Result<Self> {
+					let value = <$unsigned>::try_from(decode_varint(input)?)
+						.map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+					Ok(Self((value >> 1) as $ty ^ -((value & 1) as $ty)))
+				}
+			}
+		)*
+	};
+}
+
+zigzag!(i16: u16, i32: u32, i64: u64);
+
+/// Vector of at most `N` elements, stored inline.
+///
+/// The vector is encoded as a length byte followed by its elements, padded
+/// to `N` elements so that `ENCODED_SIZE` is constant. Unlike `Vec<T>`, no
+/// heap access is needed to read it back. Encoding fails with
+/// [`io::ErrorKind::InvalidInput`] if the vector holds more than `N`
+/// elements, or more than 255.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InlineVec<T, const N: u32>(pub Vec<T>);
+
+impl<T, const N: u32> InlineVec<T, N> {
+	fn encoded_len(&self) -> io::Result<u8> {
+		if self.0.len() > N as usize {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"too many elements for inline vector",
+			));
+		}
+
+		u8::try_from(self.0.len()).map_err(|_| io::ErrorKind::InvalidInput.into())
+	}
+}
+
+impl<T, const N: u32> std::ops::Deref for InlineVec<T, N> {
+	type Target = Vec<T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, const N: u32> std::ops::DerefMut for InlineVec<T, N> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T: EncodeSized, const N: u32> EncodeSized for InlineVec<T, N> {
+	const ENCODED_SIZE: u32 = 1 + N * T::ENCODED_SIZE;
+}
+
+impl<C, T: EncodeSized + Encode<C>, const N: u32> Encode<C> for InlineVec<T, N> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		let len = self.encoded_len()?;
+		len.encode(context, output)?;
+		for t in &self.0 {
+			t.encode(context, output)?;
+		}
+
+		crate::encode::pad(output, (N - len as u32) * T::ENCODED_SIZE)?;
+		Ok(Self::ENCODED_SIZE)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>, const N: u32> EncodeOnHeap<C> for InlineVec<T, N> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let len = self.encoded_len()?;
+		len.encode(context, output)?;
+		for t in &self.0 {
+			t.encode_on_heap(context, heap, output)?;
+		}
+
+		crate::encode::pad(output, (N - len as u32) * T::ENCODED_SIZE)?;
+		Ok(Self::ENCODED_SIZE)
+	}
+}
+
+impl<C, T: EncodeSized + Decode<C>, const N: u32> Decode<C> for InlineVec<T, N> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		let len = u8::decode(input, context)? as u32;
+		if len > N {
+			return Err(io::ErrorKind::InvalidData.into());
+		}
+
+		let mut result = Vec::with_capacity(len as usize);
+		for _ in 0..len {
+			result.push(T::decode(input, context)?)
+		}
+
+		crate::decode::pad(input, (N - len) * T::ENCODED_SIZE)?;
+		Ok(Self(result))
+	}
+}
+
+impl<C, T: EncodeSized + DecodeFromHeap<C>, const N: u32> DecodeFromHeap<C> for InlineVec<T, N> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let len = u8::decode(input, context)? as u32;
+		if len > N {
+			return Err(io::ErrorKind::InvalidData.into());
+		}
+
+		let mut result = Vec::with_capacity(len as usize);
+		for _ in 0..len {
+			result.push(T::decode_from_heap(input, context, heap)?)
+		}
+
+		input.pad((N - len) * T::ENCODED_SIZE)?;
+		Ok(Self(result))
+	}
+}
+
+/// Optional heap vector, without discriminant.
+///
+/// Unlike `Option<Vec<T>>`, the absence of value is encoded using the
+/// [`heap::Entry::NONE`] sentinel, so that `ENCODED_SIZE` is the one of a
+/// heap entry. `Some(vec![])` is still distinct from `None`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OptionVec<T>(pub Option<Vec<T>>);
+
+impl<T> std::ops::Deref for OptionVec<T> {
+	type Target = Option<Vec<T>>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> std::ops::DerefMut for OptionVec<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T> EncodeSized for OptionVec<T> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>> EncodeOnHeap<C> for OptionVec<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		match &self.0 {
+			Some(v) => v.encode_on_heap(context, heap, output),
+			None => heap::Entry::NONE.encode(context, output),
+		}
+	}
+}
+
+impl<C, T: Decode<C>> DecodeFromHeap<C> for OptionVec<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+
+		if entry.is_none() {
+			Ok(Self(None))
+		} else {
+			input.report_heap_entry(entry);
+			let mut result = Vec::with_capacity(entry.len as usize);
+			input.decode_seq_from_heap(context, heap, entry, |t| {
+				result.push(t);
+				Ok(())
+			})?;
+			Ok(Self(Some(result)))
+		}
+	}
+}
+
+/// Heap value referenced by a [`heap::VarEntry`].
+///
+/// Implemented for `String` and `Vec<T>`. The entry only takes 4 bytes
+/// instead of 8, the length being stored on the heap as a varint, which
+/// packs more short strings or vectors in each page.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VarLen<T>(pub T);
+
+impl<T> std::ops::Deref for VarLen<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> std::ops::DerefMut for VarLen<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl EncodeSized for VarLen<String> {
+	const ENCODED_SIZE: u32 = heap::VarEntry::ENCODED_SIZE;
+}
+
+impl<C> EncodeOnHeap<C> for VarLen<String> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let len = u32::try_from(self.0.len()).map_err(|_| crate::too_large_error())?;
+		let entry = heap.insert_with_len(context, self.0.as_str(), len)?;
+		entry.encode(context, output)
+	}
+}
+
+impl<C> DecodeFromHeap<C> for VarLen<String> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let var_entry = heap::VarEntry::decode(input, context)?;
+		let entry = input.read_var_entry(heap, var_entry)?;
+		input.report_heap_entry(var_entry.full_entry(entry));
+		let mut bytes = vec![0u8; entry.len as usize];
+		input.read_from_heap(heap, entry.offset, bytes.as_mut_slice())?;
+		String::from_utf8(bytes)
+			.map(Self)
+			.map_err(|_| io::ErrorKind::InvalidData.into())
+	}
+}
+
+impl<T> EncodeSized for VarLen<Vec<T>> {
+	const ENCODED_SIZE: u32 = heap::VarEntry::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>> EncodeOnHeap<C> for VarLen<Vec<T>> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let len = u32::try_from(self.0.len()).map_err(|_| crate::too_large_error())?;
+		let entry = heap.insert_with_len(context, self.0.as_slice(), len)?;
+		entry.encode(context, output)
+	}
+}
+
+impl<C, T: Decode<C>> DecodeFromHeap<C> for VarLen<Vec<T>> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let var_entry = heap::VarEntry::decode(input, context)?;
+		let entry = input.read_var_entry(heap, var_entry)?;
+		input.report_heap_entry(var_entry.full_entry(entry));
+		let mut result = Vec::with_capacity(entry.len as usize);
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			result.push(t);
+			Ok(())
+		})?;
+		Ok(Self(result))
+	}
+}
+
+/// Heap collection holding at most `MAX` elements.
+///
+/// Implemented for `Vec<T>` and `HashMap<K, V, S>`, with their heap layout.
+/// Decoding fails with [`io::ErrorKind::InvalidData`] if the encoded element
+/// count exceeds `MAX`, before anything is allocated, which guards against
+/// crafted files. Encoding fails with [`io::ErrorKind::InvalidInput`] if the
+/// collection is too large.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bounded<T, const MAX: u32>(pub T);
+
+impl<T, const MAX: u32> Bounded<T, MAX> {
+	fn check_encoded_len(len: usize) -> io::Result<()> {
+		if len > MAX as usize {
+			Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"too many elements for bounded collection",
+			))
+		} else {
+			Ok(())
+		}
+	}
+
+	fn check_decoded_len(entry: heap::Entry) -> io::Result<()> {
+		if entry.len > MAX {
+			Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"too many elements for bounded collection",
+			))
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<T, const MAX: u32> std::ops::Deref for Bounded<T, MAX> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, const MAX: u32> std::ops::DerefMut for Bounded<T, MAX> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T, const MAX: u32> EncodeSized for Bounded<T, MAX> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>, const MAX: u32> EncodeOnHeap<C> for Bounded<Vec<T>, MAX> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		Self::check_encoded_len(self.0.len())?;
+		self.0.encode_on_heap(context, heap, output)
+	}
+}
+
+impl<C, T: Decode<C>, const MAX: u32> DecodeFromHeap<C> for Bounded<Vec<T>, MAX> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		Self::check_decoded_len(entry)?;
+		input.report_heap_entry(entry);
+		let mut result = Vec::with_capacity(entry.len as usize);
+		input.decode_seq_from_heap(context, heap, entry, |t| {
+			result.push(t);
+			Ok(())
+		})?;
+		Ok(Self(result))
+	}
+}
+
+impl<C, K: Encode<C>, V: Encode<C>, S, const MAX: u32> EncodeOnHeap<C>
+	for Bounded<HashMap<K, V, S>, MAX>
+{
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		Self::check_encoded_len(self.0.len())?;
+		self.0.encode_on_heap(context, heap, output)
+	}
+}
+
+impl<C, K: Eq + Hash + Decode<C>, V: Decode<C>, S: Default + BuildHasher, const MAX: u32>
+	DecodeFromHeap<C> for Bounded<HashMap<K, V, S>, MAX>
+{
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		Self::check_decoded_len(entry)?;
+		input.report_heap_entry(entry);
+		let mut result = HashMap::with_capacity_and_hasher(entry.len as usize, S::default());
+		input.decode_seq_from_heap(context, heap, entry, |(k, v)| {
+			result.insert(k, v);
+			Ok(())
+		})?;
+		Ok(Self(result))
+	}
+}
+
+/// Type whose encoding never produces a given byte pattern, its niche
+/// ([`EncodeSized::NICHE`]).
+///
+/// `Option<T>` encodes `None` with the niche of `T`, without discriminant, so
+/// that its `ENCODED_SIZE` is the one of `T`. Implementors must define
+/// [`EncodeSized::NICHE`].
+pub trait Nicheable: EncodeSized {}
+
+impl Nicheable for String {}
+
+impl Nicheable for Cow<'static, str> {}
+
+impl Nicheable for Box<str> {}
+
+impl<T> Nicheable for Vec<T> {}
+
+impl<T> Nicheable for Box<[T]> {}
+
+impl<T> Nicheable for VecDeque<T> {}
+
+impl Nicheable for bool {}
+
+impl Nicheable for char {}
+
+impl Nicheable for NonZeroI8 {}
+
+impl Nicheable for NonZeroI16 {}
+
+impl Nicheable for NonZeroI32 {}
+
+impl Nicheable for NonZeroI64 {}
+
+impl Nicheable for NonZeroI128 {}
+
+impl Nicheable for NonZeroU8 {}
+
+impl Nicheable for NonZeroU16 {}
+
+impl Nicheable for NonZeroU32 {}
+
+impl Nicheable for NonZeroU64 {}
+
+impl Nicheable for NonZeroU128 {}
+
+/// Optional value, without discriminant.
+///
+/// This has the encoding of `Option<T>`, while ensuring that `T` has a
+/// niche (see [`Nicheable`]), so that `ENCODED_SIZE` is the one of `T`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NicheOption<T>(pub Option<T>);
+
+impl<T> std::ops::Deref for NicheOption<T> {
+	type Target = Option<T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> std::ops::DerefMut for NicheOption<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T: Nicheable> EncodeSized for NicheOption<T> {
+	const ENCODED_SIZE: u32 = Option::<T>::ENCODED_SIZE;
+}
+
+impl<C, T: Nicheable + Encode<C>> Encode<C> for NicheOption<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.0.encode(context, output)
+	}
+}
+
+impl<C, T: Nicheable + EncodeOnHeap<C>> EncodeOnHeap<C> for NicheOption<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.0.encode_on_heap(context, heap, output)
+	}
+}
+
+impl<C, T: Nicheable + Decode<C>> Decode<C> for NicheOption<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		Option::decode(input, context).map(Self)
+	}
+}
+
+impl<C, T: Nicheable + DecodeFromHeap<C>> DecodeFromHeap<C> for NicheOption<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		Option::decode_from_heap(input, context, heap).map(Self)
+	}
+}
+
+/// Checksum algorithm used by [`Checked`], and by encoders and readers
+/// storing packed heaps (see [`Encoder::with_codec`](crate::Encoder::with_codec)).
+pub trait Checksum {
+	/// Checksum value, stored after the checked bytes.
+	type Output: EncodeSized + Encode + Decode<()> + PartialEq;
+
+	/// Computes the checksum of the given bytes.
+	fn checksum(bytes: &[u8]) -> Self::Output;
+
+	/// Checks that the given bytes match the expected checksum.
+	fn verify(bytes: &[u8], expected: &Self::Output) -> bool {
+		Self::checksum(bytes) == *expected
+	}
+}
+
+/// CRC-32 checksum.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Crc32;
+
+impl Checksum for Crc32 {
+	type Output = u32;
+
+	fn checksum(bytes: &[u8]) -> u32 {
+		crc32fast::hash(bytes)
+	}
+}
+
+/// Empty checksum, for codecs used without integrity checks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NoChecksum;
+
+impl Checksum for NoChecksum {
+	type Output = ();
+
+	fn checksum(_bytes: &[u8]) {}
+}
+
+/// Value followed by a checksum of its encoding.
+///
+/// The checksum is computed with `H`, CRC-32 by default, and verified on
+/// decode, failing with [`io::ErrorKind::InvalidData`] on mismatch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checked<T, H = Crc32>(pub T, pub PhantomData<H>);
+
+impl<T, H> Checked<T, H> {
+	pub fn new(value: T) -> Self {
+		Self(value, PhantomData)
+	}
+}
+
+impl<T, H> std::ops::Deref for Checked<T, H> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, H> std::ops::DerefMut for Checked<T, H> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T: EncodeSized, H: Checksum> EncodeSized for Checked<T, H> {
+	const ENCODED_SIZE: u32 = T::ENCODED_SIZE + H::Output::ENCODED_SIZE;
+}
+
+fn write_checked<H: Checksum>(output: &mut impl Output, bytes: &[u8]) -> io::Result<u32> {
+	output.write_all(bytes)?;
+	let len = H::checksum(bytes).encode(&(), output)?;
+	Ok(bytes.len() as u32 + len)
+}
+
+fn check<H: Checksum>(bytes: &[u8], input: &mut impl Input) -> io::Result<()> {
+	if H::verify(bytes, &H::Output::decode(input, &mut ())?) {
+		Ok(())
+	} else {
+		Err(io::ErrorKind::InvalidData.into())
+	}
+}
+
+impl<C, T: Encode<C>, H: Checksum> Encode<C> for Checked<T, H> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		let mut bytes = Ordered::like(output, Vec::new());
+		self.0.encode(context, &mut bytes)?;
+		write_checked::<H>(output, &bytes.0)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>, H: Checksum> EncodeOnHeap<C> for Checked<T, H> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let mut bytes = Ordered::like(output, Vec::new());
+		self.0.encode_on_heap(context, heap, &mut bytes)?;
+		write_checked::<H>(output, &bytes.0)
+	}
+}
+
+impl<C, T: EncodeSized + Decode<C>, H: Checksum> Decode<C> for Checked<T, H> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		let mut bytes = vec![0u8; T::ENCODED_SIZE as usize];
+		input.read_exact(&mut bytes)?;
+		check::<H>(&bytes, input)?;
+		T::decode(&mut Ordered::like(input, bytes.as_slice()), context).map(Self::new)
+	}
+}
+
+impl<C, T: EncodeSized + DecodeFromHeap<C>, H: Checksum> DecodeFromHeap<C> for Checked<T, H> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let offset = input.offset();
+		let mut bytes = vec![0u8; T::ENCODED_SIZE as usize];
+		input.read(&mut bytes)?;
+		check::<H>(&bytes, input)?;
+
+		input.seek(offset)?;
+		let t = T::decode_from_heap(input, context, heap)?;
+		input.pad(H::Output::ENCODED_SIZE)?;
+		Ok(Self::new(t))
+	}
+}
+
+/// Compression algorithm used by [`Compressed`], and by encoders and readers
+/// storing packed heaps (see [`Encoder::with_codec`](crate::Encoder::with_codec)).
+pub trait Codec {
+	fn compress(bytes: &[u8]) -> io::Result<Vec<u8>>;
+
+	fn decompress(bytes: &[u8]) -> io::Result<Vec<u8>>;
+}
+
+/// Codec leaving data uncompressed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Identity;
+
+impl Codec for Identity {
+	fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+		Ok(bytes.to_vec())
+	}
+
+	fn decompress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+		Ok(bytes.to_vec())
+	}
+}
+
+/// LZ4 block compression, with the uncompressed size prepended.
+#[cfg(feature = "lz4_flex")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lz4;
+
+#[cfg(feature = "lz4_flex")]
+impl Codec for Lz4 {
+	fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+		Ok(lz4_flex::compress_prepend_size(bytes))
+	}
+
+	fn decompress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+		lz4_flex::decompress_size_prepended(bytes)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+	}
+}
+
+/// Value stored on the heap, compressed with `K`.
+///
+/// The encoding of the value is compressed and stored on the heap, the entry
+/// only holding a reference to it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Compressed<T, K>(pub T, pub PhantomData<K>);
+
+impl<T, K> Compressed<T, K> {
+	pub fn new(value: T) -> Self {
+		Self(value, PhantomData)
+	}
+}
+
+impl<T, K> std::ops::Deref for Compressed<T, K> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, K> std::ops::DerefMut for Compressed<T, K> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T, K> EncodeSized for Compressed<T, K> {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>, K: Codec> EncodeOnHeap<C> for Compressed<T, K> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let mut bytes = Ordered::like(output, Vec::new());
+		self.0.encode(context, &mut bytes)?;
+		let compressed = K::compress(&bytes.0)?;
+		let entry = heap
+			.insert(context, compressed.as_slice())?
+			.sized(compressed.len() as u32);
+		entry.encode(context, output)
+	}
+}
+
+impl<C, T: Decode<C>, K: Codec> DecodeFromHeap<C> for Compressed<T, K> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = heap::Entry::decode(input, context)?;
+		input.report_heap_entry(entry);
+		let mut compressed = Vec::with_capacity(entry.len as usize);
+		input.decode_seq_from_heap(context, heap, entry, |b: u8| {
+			compressed.push(b);
+			Ok(())
+		})?;
+
+		let bytes = K::decompress(&compressed)?;
+		T::decode(&mut Ordered::like(input, bytes.as_slice()), context).map(Self::new)
+	}
+}
+
+/// Context providing the reference instant of [`RelInstant`] values.
+pub trait Epoch {
+	fn epoch(&self) -> Instant;
+}
+
+impl Epoch for Instant {
+	fn epoch(&self) -> Instant {
+		*self
+	}
+}
+
+/// Instant encoded relatively to an epoch provided by the context.
+///
+/// The instant is stored as a `u64` number of nanoseconds elapsed since the
+/// epoch. Encoding an instant earlier than the epoch fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RelInstant(pub Instant);
+
+impl std::ops::Deref for RelInstant {
+	type Target = Instant;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl std::ops::DerefMut for RelInstant {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl EncodeSized for RelInstant {
+	const ENCODED_SIZE: u32 = u64::ENCODED_SIZE;
+}
+
+impl<C: Epoch> Encode<C> for RelInstant {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		let nanos = self
+			.0
+			.checked_duration_since(context.epoch())
+			.and_then(|d| u64::try_from(d.as_nanos()).ok())
+			.ok_or(io::ErrorKind::InvalidInput)?;
+		nanos.encode(context, output)
+	}
+}
+
+impl<C: Epoch> EncodeOnHeap<C> for RelInstant {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.encode(context, output)
+	}
+}
+
+impl<C: Epoch> Decode<C> for RelInstant {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		let nanos = u64::decode(input, context)?;
+		context
+			.epoch()
+			.checked_add(Duration::from_nanos(nanos))
+			.map(Self)
+			.ok_or_else(|| io::ErrorKind::InvalidData.into())
+	}
+}
+
+impl<C: Epoch> DecodeFromHeap<C> for RelInstant {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
+
+/// Table of shared values, used by [`Interned`] values during decoding.
+///
+/// Values are identified by their encoding rather than by `Eq`, so that
+/// values that compare equal but are encoded differently (such as `0.0` and
+/// `-0.0`) are kept apart.
+#[derive(Debug)]
+pub struct Interner<T> {
+	values: HashMap<Vec<u8>, Arc<T>>,
+}
+
+impl<T> Default for Interner<T> {
+	fn default() -> Self {
+		Self {
+			values: HashMap::new(),
+		}
+	}
+}
+
+impl<T> Interner<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the number of distinct values interned so far.
+	pub fn len(&self) -> usize {
+		self.values.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty()
+	}
+
+	/// Returns the shared instance of the value with the given encoding,
+	/// inserting `value` if necessary.
+	pub fn intern(&mut self, encoded: Vec<u8>, value: T) -> Arc<T> {
+		self.values
+			.entry(encoded)
+			.or_insert_with(|| Arc::new(value))
+			.clone()
+	}
+}
+
+/// Context providing the [`Interner`] of [`Interned`] values.
+pub trait Interning<T> {
+	fn interner(&mut self) -> &mut Interner<T>;
+}
+
+impl<T> Interning<T> for Interner<T> {
+	fn interner(&mut self) -> &mut Interner<T> {
+		self
+	}
+}
+
+/// Shared value.
+///
+/// The wire format is the one of `T`. When decoding, values with the same
+/// encoding resolve to the same [`Arc`] using the interner provided by the
+/// context, so that repeated values, including identical heap data, are only
+/// stored once in memory.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interned<T>(pub Arc<T>);
+
+impl<T> std::ops::Deref for Interned<T> {
+	type Target = Arc<T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> std::ops::DerefMut for Interned<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T: EncodeSized> EncodeSized for Interned<T> {
+	const ENCODED_SIZE: u32 = T::ENCODED_SIZE;
+}
+
+impl<C, T: Encode<C>> Encode<C> for Interned<T> {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.0.encode(context, output)
+	}
+}
+
+impl<C, T: EncodeOnHeap<C>> EncodeOnHeap<C> for Interned<T> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.0.encode_on_heap(context, heap, output)
+	}
+}
+
+impl<C: Interning<T>, T: Encode<C> + Decode<C>> Decode<C> for Interned<T> {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		let value = T::decode(input, context)?;
+		let mut encoded = Ordered::like(input, Vec::new());
+		value.encode(context, &mut encoded)?;
+		Ok(Self(context.interner().intern(encoded.0, value)))
+	}
+}
+
+impl<C: Interning<T>, T: EncodeOnHeap<C> + DecodeFromHeap<C>> DecodeFromHeap<C> for Interned<T> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let value = T::decode_from_heap(input, context, heap)?;
+
+		// The heap data follows the fixed-size inline data, starting from an
+		// empty heap, so equal encodings give equal buffers.
+		let mut encoded = Ordered::like(input, Vec::new());
+		let mut value_heap = Heap::new();
+		value.encode_on_heap(context, &mut value_heap, &mut encoded)?;
+		encoded.0.extend_from_slice(value_heap.as_bytes());
+		Ok(Self(context.interner().intern(encoded.0, value)))
+	}
+}
+
+/// Path stored as a sequence of components, independently of the separator
+/// of the host.
+///
+/// Components are normalized as follows:
+///   - the root directory is stored as a leading empty component;
+///   - current directory components (`.`) are dropped;
+///   - parent directory components are stored as `..`;
+///   - other components are stored as UTF-8 strings.
+///
+/// Encoding fails with [`io::ErrorKind::InvalidInput`] if the path has a
+/// non-UTF-8 component or a prefix (such as a Windows drive letter), which
+/// has no portable meaning. On decode, components are joined with the local
+/// separator, and any component containing a `/` or `\` separator, or an
+/// empty component other than the root, fails with
+/// [`io::ErrorKind::InvalidData`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortablePath(pub PathBuf);
+
+impl std::ops::Deref for PortablePath {
+	type Target = PathBuf;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl std::ops::DerefMut for PortablePath {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+/// Path component, encoded as a varint length followed by its bytes.
+struct PathComponent(String);
+
+impl<C> Encode<C> for PathComponent {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		let len = encode_varint(self.0.len() as u64, output)?;
+		Ok(len + self.0.encode(context, output)?)
+	}
+}
+
+impl<C> Decode<C> for PathComponent {
+	fn decode<R: Input>(input: &mut R, _context: &mut C) -> io::Result<Self> {
+		let len = decode_varint(input)?;
+		let mut bytes = Vec::new();
+		input.take(len).read_to_end(&mut bytes)?;
+		if bytes.len() as u64 != len {
+			return Err(io::ErrorKind::UnexpectedEof.into());
+		}
+
+		String::from_utf8(bytes)
+			.map(Self)
+			.map_err(|_| io::ErrorKind::InvalidData.into())
+	}
+}
+
+impl EncodeSized for PortablePath {
+	const ENCODED_SIZE: u32 = heap::Entry::ENCODED_SIZE;
+}
+
+impl<C> EncodeOnHeap<C> for PortablePath {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let mut components = Vec::new();
+		for c in self.0.components() {
+			let c = match c {
+				Component::Prefix(_) => return Err(io::ErrorKind::InvalidInput.into()),
+				Component::RootDir => String::new(),
+				Component::CurDir => continue,
+				Component::ParentDir => "..".to_owned(),
+				Component::Normal(c) => c.to_str().ok_or(io::ErrorKind::InvalidInput)?.to_owned(),
+			};
+
+			components.push(PathComponent(c))
+		}
+
+		components.encode_on_heap(context, heap, output)
+	}
+}
+
+impl<C> DecodeFromHeap<C> for PortablePath {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let components = Vec::<PathComponent>::decode_from_heap(input, context, heap)?;
+		let mut path = PathBuf::new();
+		for (i, PathComponent(c)) in components.into_iter().enumerate() {
+			if c.is_empty() && i == 0 {
+				path.push(std::path::MAIN_SEPARATOR_STR)
+			} else if c.is_empty() || c.contains(['/', '\\']) {
+				return Err(io::ErrorKind::InvalidData.into());
+			} else {
+				path.push(c)
+			}
+		}
+
+		Ok(Self(path))
+	}
+}
+
+/// Number with a fixed-size binary representation.
+pub trait Number: Copy {
+	/// Binary representation.
+	type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+	fn to_be_bytes(self) -> Self::Bytes;
+
+	fn to_le_bytes(self) -> Self::Bytes;
+
+	fn from_be_bytes(bytes: Self::Bytes) -> Self;
+
+	fn from_le_bytes(bytes: Self::Bytes) -> Self;
+}
+
+macro_rules! number {
+	($($ty:ty),*) => {
+		$(
+			impl Number for $ty {
+				type Bytes = [u8; std::mem::size_of::<$ty>()];
+
+				fn to_be_bytes(self) -> Self::Bytes {
+					<$ty>::to_be_bytes(self)
+				}
+
+				fn to_le_bytes(self) -> Self::Bytes {
+					<$ty>::to_le_bytes(self)
+				}
+
+				fn from_be_bytes(bytes: Self::Bytes) -> Self {
+					<$ty>::from_be_bytes(bytes)
+				}
+
+				fn from_le_bytes(bytes: Self::Bytes) -> Self {
+					<$ty>::from_le_bytes(bytes)
+				}
+			}
+		)*
+	};
+}
+
+number!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+/// Byte order of encoded numbers.
+///
+/// Encoders and readers carry the byte order of their file as a type
+/// parameter, defaulting to [`BigEndian`], so that every number of a file is
+/// encoded with the same byte order. Mixing byte orders within a file is not
+/// supported, except through [`Endian`] values.
+pub trait ByteOrder: 'static + Send + Sync {
+	/// Byte order, as a run time value.
+	const ENDIANNESS: Endianness;
+
+	fn to_bytes<T: Number>(value: T) -> T::Bytes;
+
+	fn from_bytes<T: Number>(bytes: T::Bytes) -> T;
+}
+
+/// Big-endian byte order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BigEndian;
+
+impl ByteOrder for BigEndian {
+	const ENDIANNESS: Endianness = Endianness::Big;
+
+	fn to_bytes<T: Number>(value: T) -> T::Bytes {
+		value.to_be_bytes()
+	}
+
+	fn from_bytes<T: Number>(bytes: T::Bytes) -> T {
+		T::from_be_bytes(bytes)
+	}
+}
+
+/// Little-endian byte order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LittleEndian;
+
+impl ByteOrder for LittleEndian {
+	const ENDIANNESS: Endianness = Endianness::Little;
+
+	fn to_bytes<T: Number>(value: T) -> T::Bytes {
+		value.to_le_bytes()
+	}
+
+	fn from_bytes<T: Number>(bytes: T::Bytes) -> T {
+		T::from_le_bytes(bytes)
+	}
+}
+
+/// Byte order of the host.
+#[cfg(target_endian = "big")]
+pub type NativeEndian = BigEndian;
+
+/// Byte order of the host.
+#[cfg(target_endian = "little")]
+pub type NativeEndian = LittleEndian;
+
+/// Byte order as a run time value.
+///
+/// This is how the byte order of a file is recorded in its
+/// [`Header`](crate::header::Header).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Endianness {
+	#[default]
+	Big,
+	Little,
+}
+
+impl Endianness {
+	/// Byte order of the host.
+	pub const NATIVE: Self = NativeEndian::ENDIANNESS;
+}
+
+/// Input or output encoding numbers with the byte order `Self::ByteOrder`.
+pub trait ByteOrdered {
+	type ByteOrder: ByteOrder;
+}
+
+impl<T: ?Sized + ByteOrdered> ByteOrdered for &mut T {
+	type ByteOrder = T::ByteOrder;
+}
+
+/// Output of [`Encode`] and [`EncodeOnHeap`] implementations.
+pub trait Output: io::Write + ByteOrdered {}
+
+impl<T: ?Sized + io::Write + ByteOrdered> Output for T {}
+
+/// Input of [`Decode`] implementations.
+pub trait Input: io::Read + ByteOrdered {}
+
+impl<T: ?Sized + io::Read + ByteOrdered> Input for T {}
+
+/// Input or output encoding numbers with the byte order `O`.
+///
+/// This is used to encode values to, or decode values from, any reader or
+/// writer, such as a byte buffer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ordered<T, O = BigEndian>(pub T, pub PhantomData<O>);
+
+impl<T, O> Ordered<T, O> {
+	pub fn new(inner: T) -> Self {
+		Self(inner, PhantomData)
+	}
+
+	/// Wraps `inner` with the byte order of the given input or output.
+	pub fn like(_other: &impl ByteOrdered<ByteOrder = O>, inner: T) -> Self {
+		Self::new(inner)
+	}
+
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T, O: ByteOrder> ByteOrdered for Ordered<T, O> {
+	type ByteOrder = O;
+}
+
+impl<T: io::Write, O> io::Write for Ordered<T, O> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.write(buf)
+	}
+
+	fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+		self.0.write_all(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.0.flush()
+	}
+}
+
+impl<T: io::Read, O> io::Read for Ordered<T, O> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.0.read(buf)
+	}
+
+	fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+		self.0.read_exact(buf)
+	}
+}
+
+impl<T: io::Seek, O> io::Seek for Ordered<T, O> {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.0.seek(pos)
+	}
+}
+
+/// Encodes a number with the byte order of the output.
+///
+/// Returns the number of bytes written.
+pub fn encode_int<T: Number, W: Output>(value: T, output: &mut W) -> io::Result<u32> {
+	encode_int_with::<W::ByteOrder, T>(value, output)
+}
+
+/// Decodes a number with the byte order of the input.
+pub fn decode_int<T: Number, R: Input>(input: &mut R) -> io::Result<T> {
+	decode_int_with::<R::ByteOrder, T>(input)
+}
+
+/// Encodes a number with the byte order `O`.
+///
+/// Returns the number of bytes written.
+pub fn encode_int_with<O: ByteOrder, T: Number>(
+	value: T,
+	output: &mut impl io::Write,
+) -> io::Result<u32> {
+	let bytes = O::to_bytes(value);
+	output.write_all(bytes.as_ref())?;
+	Ok(bytes.as_ref().len() as u32)
+}
+
+/// Decodes a number with the byte order `O`.
+pub fn decode_int_with<O: ByteOrder, T: Number>(input: &mut impl io::Read) -> io::Result<T> {
+	let mut bytes = T::Bytes::default();
+	input.read_exact(bytes.as_mut())?;
+	Ok(O::from_bytes(bytes))
+}
+
+/// Number encoded with the byte order `O`.
+///
+/// Other numbers use the byte order of the file (see [`ByteOrder`]),
+/// including the offsets and lengths describing sections and heap entries.
+/// This is meant for individual values shared with tools expecting a fixed
+/// byte order, whatever the byte order of the file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Endian<T, O = BigEndian>(pub T, pub PhantomData<O>);
+
+impl<T, E> Endian<T, E> {
+	pub fn new(value: T) -> Self {
+		Self(value, PhantomData)
+	}
+}
+
+impl<T, E> std::ops::Deref for Endian<T, E> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, E> std::ops::DerefMut for Endian<T, E> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T: EncodeSized, E> EncodeSized for Endian<T, E> {
+	const ENCODED_SIZE: u32 = T::ENCODED_SIZE;
+}
+
+impl<C, T: Number, E: ByteOrder> Encode<C> for Endian<T, E> {
+	fn encode(&self, _context: &C, output: &mut impl Output) -> io::Result<u32> {
+		encode_int_with::<E, T>(self.0, output)
+	}
+}
+
+impl<C, T: Number + EncodeSized, E: ByteOrder> EncodeOnHeap<C> for Endian<T, E> {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.encode(context, output)
+	}
+}
+
+impl<C, T: Number, E: ByteOrder> Decode<C> for Endian<T, E> {
+	fn decode<R: Input>(input: &mut R, _context: &mut C) -> io::Result<Self> {
+		decode_int_with::<E, T>(input).map(Self::new)
+	}
+}
+
+impl<C, T: Number, E: ByteOrder> DecodeFromHeap<C> for Endian<T, E> {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut C,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		Self::decode(input, context)
+	}
+}
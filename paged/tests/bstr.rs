@@ -0,0 +1,30 @@
+use std::io::{self, Cursor};
+
+use bstr::BString;
+use paged::{reader::Cache, Encoder, Error, Heap, Reader, Section};
+
+#[test]
+fn invalid_utf8() {
+	let entries: Vec<BString> = vec![
+		BString::from("valid"),
+		BString::from(&b"invalid \xff\xfe bytes"[..]),
+		BString::from(""),
+		BString::from(&b"\xc3"[..]),
+	];
+	assert!(String::from_utf8(entries[1].to_vec()).is_err());
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	assert_eq!(reader.decode_all(section, &mut (), heap).unwrap(), entries);
+
+	// The same entries cannot be read as strings.
+	let strings =
+		Section::<String>::from_byte_offset(section.page_offset() * 64, section.entry_count(), 64);
+	let cache = Cache::default();
+	let err = reader.get(strings, &cache, &mut (), heap, 1).err().unwrap();
+	assert!(matches!(err, Error::IO(e) if e.kind() == io::ErrorKind::InvalidData));
+}
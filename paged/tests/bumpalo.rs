@@ -0,0 +1,43 @@
+use std::{io::Cursor, time::Instant};
+
+use bumpalo::{collections, Bump};
+use paged::{Encoder, Heap, Reader, Section};
+
+#[test]
+fn arena_decode() {
+	let entries: Vec<(String, Vec<u32>)> = (0..2000)
+		.map(|i| (format!("entry number {i}"), (0..(i % 20)).collect()))
+		.collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 4096);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 4096, 0);
+
+	let start = Instant::now();
+	let decoded = reader.decode_all(section, &mut (), heap).unwrap();
+	let default_time = start.elapsed();
+	assert_eq!(decoded, entries);
+
+	// Arena types are encoded like their standard counterparts.
+	type ArenaEntry<'b> = (collections::String<'b>, collections::Vec<'b, u32>);
+	let arena_section = Section::<ArenaEntry>::from_byte_offset(
+		section.page_offset() * 4096,
+		section.entry_count(),
+		4096,
+	);
+
+	let arena = Bump::new();
+	let start = Instant::now();
+	let decoded = reader.decode_all_in(arena_section, &arena, heap).unwrap();
+	let arena_time = start.elapsed();
+	eprintln!("default decode: {default_time:?}, arena decode: {arena_time:?}");
+
+	assert_eq!(decoded.len(), entries.len());
+	for ((s, v), (expected_s, expected_v)) in decoded.iter().zip(&entries) {
+		assert_eq!(s.as_str(), expected_s);
+		assert_eq!(v.as_slice(), expected_v.as_slice());
+	}
+	assert!(arena.allocated_bytes() >= entries.iter().map(|(s, _)| s.len()).sum());
+}
@@ -0,0 +1,56 @@
+use std::io::Cursor;
+
+use paged::{
+	reader::Cache,
+	utils::{BigEndian, Endian, LittleEndian},
+	Encoder, Heap, Reader,
+};
+
+#[test]
+fn little_endian_file() {
+	let numbers: Vec<u32> = (0..100).map(|i| i * 0x01020304).collect();
+	let strings: Vec<String> = (0..100).map(|i| format!("string {i}")).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64).with_byte_order::<LittleEndian>();
+	let mut heap = Heap::new();
+	let numbers_section = encoder
+		.section_from_iter(&mut heap, numbers.iter())
+		.unwrap();
+	let strings_section = encoder
+		.section_from_iter(&mut heap, strings.iter())
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let bytes = encoder.end().into_inner();
+	assert_eq!(bytes[4..8], 0x01020304u32.to_le_bytes());
+
+	let reader = Reader::new(Cursor::new(bytes.clone()), 64, 0).with_byte_order::<LittleEndian>();
+	let cache = Cache::default();
+	assert_eq!(
+		reader.decode_all(numbers_section, &mut (), heap).unwrap(),
+		numbers
+	);
+	let entry = reader
+		.get(strings_section, &cache, &mut (), heap, 42)
+		.unwrap();
+	assert_eq!(*entry.unwrap(), strings[42]);
+
+	// Read as big-endian, the same bytes give swapped numbers.
+	let reader = Reader::new(Cursor::new(bytes), 64, 0);
+	let swapped = reader.decode_all(numbers_section, &mut (), heap).unwrap();
+	assert_eq!(swapped[1], numbers[1].swap_bytes());
+}
+
+#[test]
+fn endian_values_keep_their_byte_order() {
+	let values: Vec<Endian<u32, BigEndian>> = (0..10).map(Endian::new).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64).with_byte_order::<LittleEndian>();
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let bytes = encoder.end().into_inner();
+	assert_eq!(bytes[4..8], 1u32.to_be_bytes());
+
+	let reader = Reader::new(Cursor::new(bytes), 64, 0).with_byte_order::<LittleEndian>();
+	assert_eq!(reader.decode_all(section, &mut (), heap).unwrap(), values);
+}
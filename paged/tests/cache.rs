@@ -0,0 +1,37 @@
+use std::io::Cursor;
+
+use paged::{
+	reader::{Cache, Error},
+	Encoder, Heap, Reader,
+};
+
+#[test]
+fn pool_exhausted() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let values: Vec<u32> = (0..64).collect();
+	let section = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::with_capacity(2);
+	for page_index in 0..2 {
+		reader
+			.get_page(section, &cache, &mut (), heap, page_index)
+			.unwrap();
+	}
+	assert_eq!(cache.len(), 2);
+
+	// Cached pages are still available.
+	reader.get_page(section, &cache, &mut (), heap, 1).unwrap();
+	assert!(matches!(
+		reader.get_page(section, &cache, &mut (), heap, 2),
+		Err(Error::PoolExhausted)
+	));
+
+	assert!(cache.evict(section.page_offset()));
+	let page = reader.get_page(section, &cache, &mut (), heap, 2).unwrap();
+	assert_eq!(page.get(0), Some(&32));
+	assert_eq!(cache.len(), 2);
+	assert!(!cache.contains(section.page_offset()));
+}
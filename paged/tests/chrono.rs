@@ -0,0 +1,68 @@
+use std::io::{self, Cursor};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use paged::{
+	reader::Cache,
+	utils::{BigEndian, Ordered},
+	Decode, DecodeFromHeap, EncodeOnHeap, EncodeSized, Encoder, Error, Heap, Reader, Section,
+};
+
+fn round_trip<T: EncodeOnHeap + DecodeFromHeap>(entries: &[T]) -> Vec<T> {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	reader.decode_all(section, &mut (), heap).unwrap()
+}
+
+#[test]
+fn date_times() {
+	assert_eq!(DateTime::<Utc>::ENCODED_SIZE, 12);
+
+	let times: Vec<DateTime<Utc>> = [
+		"2023-11-14T22:13:20.123456789Z",
+		"1970-01-01T00:00:00Z",
+		"1969-12-31T23:59:59.5Z",
+		"1066-10-14T09:00:00Z",
+	]
+	.into_iter()
+	.map(|t| t.parse().unwrap())
+	.collect();
+	assert_eq!(round_trip(&times), times);
+
+	let naive: Vec<NaiveDateTime> = times.iter().map(DateTime::naive_utc).collect();
+	assert_eq!(round_trip(&naive), naive);
+}
+
+#[test]
+fn dates() {
+	let dates: Vec<NaiveDate> = [(2024, 2, 29), (1970, 1, 1), (1, 1, 1), (-44, 3, 15)]
+		.into_iter()
+		.map(|(y, m, d)| NaiveDate::from_ymd_opt(y, m, d).unwrap())
+		.collect();
+	assert_eq!(round_trip(&dates), dates);
+}
+
+#[test]
+fn out_of_range() {
+	// Number of days far past the last date supported by `chrono`.
+	let bytes = i32::MAX.to_be_bytes();
+	let err =
+		NaiveDate::decode(&mut Ordered::<_, BigEndian>::new(&bytes[..]), &mut ()).unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+	// Invalid sub-second nanoseconds.
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section::<DateTime<Utc>>(&mut heap).unwrap();
+	let mut raw = 0i64.to_be_bytes().to_vec();
+	raw.extend(u32::MAX.to_be_bytes());
+	section.push_raw(&raw).unwrap();
+	let section: Section<DateTime<Utc>> = section.end().unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	let err = reader.get(section, &cache, &mut (), heap, 0).err().unwrap();
+	assert!(matches!(err, Error::IO(e) if e.kind() == io::ErrorKind::InvalidData));
+}
@@ -0,0 +1,127 @@
+use std::io::{self, Cursor};
+
+use paged::{
+	reader::Cache,
+	utils::{Checksum, Codec, Identity},
+	Encoder, Error, Heap, HeapSection, Reader, Section,
+};
+
+/// Sum of the bytes, modulo 2^32.
+struct Sum;
+
+impl Checksum for Sum {
+	type Output = u32;
+
+	fn checksum(bytes: &[u8]) -> u32 {
+		bytes
+			.iter()
+			.fold(0u32, |sum, b| sum.wrapping_add(*b as u32))
+	}
+}
+
+/// Codec reversing the bytes, so that reading unpacked heaps fails.
+struct Reverse;
+
+impl Codec for Reverse {
+	fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+		Ok(bytes.iter().rev().copied().collect())
+	}
+
+	fn decompress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+		Self::compress(bytes)
+	}
+}
+
+struct File {
+	bytes: Vec<u8>,
+	names: Section<String>,
+	lists: Section<Vec<u32>>,
+	heap: HeapSection,
+	extra: paged::heap::Offset,
+}
+
+fn names() -> Vec<String> {
+	(0..100).map(|i| format!("name number {i}")).collect()
+}
+
+fn lists() -> Vec<Vec<u32>> {
+	(0..50).map(|i| (0..i).collect()).collect()
+}
+
+fn encode<K: Codec, H: Checksum>() -> File {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64).with_codec::<K, H>();
+	let mut heap = Heap::new();
+	let names = encoder
+		.section_from_iter(&mut heap, names().iter())
+		.unwrap();
+	let lists = encoder
+		.section_from_iter(&mut heap, lists().iter())
+		.unwrap();
+	let extra = heap.insert(&(), &0x0123_4567_89ab_cdefu64).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	File {
+		bytes: encoder.end().into_inner(),
+		names,
+		lists,
+		heap,
+		extra,
+	}
+}
+
+fn check<R: io::Seek + io::Read>(reader: &Reader<R>, file: &File) {
+	let cache = Cache::default();
+	let decoded: Vec<String> = reader
+		.iter(file.names, &cache, file.heap)
+		.map(|t| (*t.unwrap()).clone())
+		.collect();
+	assert_eq!(decoded, names());
+
+	let cache = Cache::default();
+	let decoded: Vec<Vec<u32>> = reader
+		.iter(file.lists, &cache, file.heap)
+		.map(|t| (*t.unwrap()).clone())
+		.collect();
+	assert_eq!(decoded, lists());
+
+	let extra: u64 = reader
+		.decode_from_heap(&mut (), file.heap, file.extra)
+		.unwrap();
+	assert_eq!(extra, 0x0123_4567_89ab_cdef);
+}
+
+#[test]
+fn identity_codec() {
+	let file = encode::<Identity, Sum>();
+	let reader = Reader::new(Cursor::new(file.bytes.clone()), 64, 0).with_codec::<Identity, Sum>();
+	check(&reader, &file);
+
+	let reader = Reader::new(Cursor::new(file.bytes.clone()), 64, 0)
+		.with_heap_input(Cursor::new(file.bytes.clone()))
+		.with_codec::<Identity, Sum>();
+	check(&reader, &file);
+}
+
+#[test]
+fn codec_is_applied() {
+	let file = encode::<Reverse, Sum>();
+	let reader = Reader::new(Cursor::new(file.bytes.clone()), 64, 0).with_codec::<Reverse, Sum>();
+	check(&reader, &file);
+
+	// Read as unpacked, the first name is garbage.
+	let reader = Reader::new(Cursor::new(file.bytes), 64, 0);
+	let cache = Cache::default();
+	let first = reader.get(file.names, &cache, &mut (), file.heap, 0);
+	assert!(!matches!(first, Ok(Some(name)) if *name == names()[0]));
+}
+
+#[test]
+fn checksum_mismatch() {
+	let mut file = encode::<Identity, Sum>();
+	let heap_start = (file.heap.page_offset * 64) as usize;
+	file.bytes[heap_start + 20] ^= 1;
+
+	let reader = Reader::new(Cursor::new(file.bytes), 64, 0).with_codec::<Identity, Sum>();
+	let cache = Cache::default();
+	let first = reader.get(file.names, &cache, &mut (), file.heap, 0);
+	assert!(matches!(first, Err(Error::IO(e)) if e.kind() == io::ErrorKind::InvalidData));
+}
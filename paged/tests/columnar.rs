@@ -0,0 +1,47 @@
+use std::io::{self, Cursor};
+
+use paged::{columnar, reader::Cache, Encoder, Heap, Reader};
+
+#[test]
+fn round_trip() {
+	let entries: Vec<(u32, u64, bool)> = (0..100).map(|i| (i, i as u64 * 3, i % 2 == 0)).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_columnar_section::<(u32, u64, bool)>(&mut heap);
+	for entry in &entries {
+		section.push(&(), entry).unwrap();
+	}
+	let (a_section, b_section, c_section) = section.end().unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let a: Vec<u32> = reader.decode_all(a_section, &mut (), heap).unwrap();
+	let b: Vec<u64> = reader.decode_all(b_section, &mut (), heap).unwrap();
+	let c: Vec<bool> = reader.decode_all(c_section, &mut (), heap).unwrap();
+	let decoded: Vec<_> = a
+		.into_iter()
+		.zip(b)
+		.zip(c)
+		.map(|((a, b), c)| (a, b, c))
+		.collect();
+	assert_eq!(decoded, entries);
+
+	// Columns are regular sections.
+	let cache = Cache::default();
+	assert_eq!(
+		reader
+			.get(b_section, &cache, &mut (), heap, 42)
+			.unwrap()
+			.map(|t| *t),
+		Some(126)
+	);
+}
+
+#[test]
+fn column_size_mismatch() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let e = columnar::write_column::<_, u32, _>(&mut encoder, &mut heap, &[0; 10], 3).unwrap_err();
+	assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,556 @@
+use std::io::{self, Cursor};
+
+use paged::{
+	reader::{Cache, Caches},
+	utils::{BigEndian, Ordered},
+	Encoder, Error, Heap, HeapSection, Keyed, Paged, Reader, Section,
+};
+
+fn round_trip<T>(entries: &[T]) -> Vec<T>
+where
+	T: paged::EncodeOnHeap + paged::DecodeFromHeap,
+{
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	reader.decode_all(section, &mut (), heap).unwrap()
+}
+
+#[derive(Paged, Debug, PartialEq)]
+#[paged(heap)]
+enum Justification {
+	None,
+	Stated(String),
+	Derived(u32, Vec<u32>),
+}
+
+#[test]
+fn heap_enum() {
+	let entries: Vec<Justification> = (0..30)
+		.map(|i| match i % 3 {
+			0 => Justification::None,
+			1 => Justification::Stated(format!("statement {i}")),
+			_ => Justification::Derived(i, (0..i).collect()),
+		})
+		.collect();
+
+	assert_eq!(round_trip(&entries), entries);
+}
+
+/// More variants than a `u8` discriminant can hold, with the discriminant
+/// stored as a varint on the heap, along with fields themselves stored on
+/// the heap.
+#[derive(Paged, Debug, PartialEq)]
+#[paged(heap, repr = varint)]
+#[rustfmt::skip]
+enum VarintDiscriminant {
+	V0(u32, u64),
+	V1(String), V2, V3, V4, V5, V6, V7, V8, V9, V10, V11, V12,
+	V13, V14, V15, V16, V17, V18, V19, V20, V21, V22, V23, V24,
+	V25, V26, V27, V28, V29, V30, V31, V32, V33, V34, V35, V36,
+	V37, V38, V39, V40, V41, V42, V43, V44, V45, V46, V47, V48,
+	V49, V50, V51, V52, V53, V54, V55, V56, V57, V58, V59, V60,
+	V61, V62, V63, V64, V65, V66, V67, V68, V69, V70, V71, V72,
+	V73, V74, V75, V76, V77, V78, V79, V80, V81, V82, V83, V84,
+	V85, V86, V87, V88, V89, V90, V91, V92, V93, V94, V95, V96,
+	V97, V98, V99, V100, V101, V102, V103, V104, V105, V106, V107, V108,
+	V109, V110, V111, V112, V113, V114, V115, V116, V117, V118, V119, V120,
+	V121, V122, V123, V124, V125, V126, V127, V128, V129, V130, V131, V132,
+	V133, V134, V135, V136, V137, V138, V139, V140, V141, V142, V143, V144,
+	V145, V146, V147, V148, V149, V150, V151, V152, V153, V154, V155, V156,
+	V157, V158, V159, V160, V161, V162, V163, V164, V165, V166, V167, V168,
+	V169, V170, V171, V172, V173, V174, V175, V176, V177, V178, V179, V180,
+	V181, V182, V183, V184, V185, V186, V187, V188, V189, V190, V191, V192,
+	V193, V194, V195, V196, V197, V198, V199, V200, V201, V202, V203, V204,
+	V205, V206, V207, V208, V209, V210, V211, V212, V213, V214, V215, V216,
+	V217, V218, V219, V220, V221, V222, V223, V224, V225, V226, V227, V228,
+	V229, V230, V231, V232, V233, V234, V235, V236, V237, V238, V239, V240,
+	V241, V242, V243, V244, V245, V246, V247, V248, V249, V250, V251, V252,
+	V253, V254, V255, V256, V257, V258, V259, V260, V261, V262, V263, V264,
+	V265, V266, V267, V268, V269, V270, V271, V272, V273, V274, V275, V276,
+	V277, V278, V279, V280, V281, V282, V283, V284, V285, V286, V287, V288,
+	V289, V290, V291, V292, V293, V294, V295, V296, V297, V298, V299, V300,
+	V301, V302, V303, V304, V305, V306, V307, V308, V309, V310, V311, V312,
+	V313, V314, V315, V316, V317, V318, V319, V320, V321, V322, V323, V324,
+	V325, V326, V327, V328, V329, V330, V331, V332, V333, V334, V335, V336,
+	V337, V338, V339, V340, V341, V342, V343, V344, V345, V346, V347, V348,
+	V349, V350, V351, V352, V353, V354, V355, V356, V357, V358, V359, V360,
+	V361, V362, V363, V364, V365, V366, V367, V368, V369, V370, V371, V372,
+	V373, V374, V375, V376, V377, V378, V379, V380, V381, V382, V383, V384,
+	V385, V386, V387, V388, V389, V390, V391, V392, V393, V394, V395, V396,
+	V397, V398, V399 { values: Vec<u32>, name: String },
+}
+
+#[test]
+fn varint_discriminant() {
+	use VarintDiscriminant::*;
+	let entries = vec![
+		V0(1, 2),
+		V1("first".to_string()),
+		V127,
+		V128,
+		V255,
+		V256,
+		V399 {
+			values: vec![1, 2, 3],
+			name: "last".to_string(),
+		},
+	];
+	assert_eq!(round_trip(&entries), entries);
+}
+
+/// More variants than a `u8` discriminant can hold.
+#[derive(Paged, Debug, PartialEq)]
+#[paged(repr = u16)]
+#[rustfmt::skip]
+enum WideDiscriminant {
+	V0(u32),
+	V1, V2, V3, V4, V5, V6, V7, V8, V9, V10, V11, V12,
+	V13, V14, V15, V16, V17, V18, V19, V20, V21, V22, V23, V24,
+	V25, V26, V27, V28, V29, V30, V31, V32, V33, V34, V35, V36,
+	V37, V38, V39, V40, V41, V42, V43, V44, V45, V46, V47, V48,
+	V49, V50, V51, V52, V53, V54, V55, V56, V57, V58, V59, V60,
+	V61, V62, V63, V64, V65, V66, V67, V68, V69, V70, V71, V72,
+	V73, V74, V75, V76, V77, V78, V79, V80, V81, V82, V83, V84,
+	V85, V86, V87, V88, V89, V90, V91, V92, V93, V94, V95, V96,
+	V97, V98, V99, V100, V101, V102, V103, V104, V105, V106, V107, V108,
+	V109, V110, V111, V112, V113, V114, V115, V116, V117, V118, V119, V120,
+	V121, V122, V123, V124, V125, V126, V127, V128, V129, V130, V131, V132,
+	V133, V134, V135, V136, V137, V138, V139, V140, V141, V142, V143, V144,
+	V145, V146, V147, V148, V149, V150, V151, V152, V153, V154, V155, V156,
+	V157, V158, V159, V160, V161, V162, V163, V164, V165, V166, V167, V168,
+	V169, V170, V171, V172, V173, V174, V175, V176, V177, V178, V179, V180,
+	V181, V182, V183, V184, V185, V186, V187, V188, V189, V190, V191, V192,
+	V193, V194, V195, V196, V197, V198, V199, V200, V201, V202, V203, V204,
+	V205, V206, V207, V208, V209, V210, V211, V212, V213, V214, V215, V216,
+	V217, V218, V219, V220, V221, V222, V223, V224, V225, V226, V227, V228,
+	V229, V230, V231, V232, V233, V234, V235, V236, V237, V238, V239, V240,
+	V241, V242, V243, V244, V245, V246, V247, V248, V249, V250, V251, V252,
+	V253, V254, V255, V256, V257, V258, V259, V260, V261, V262, V263, V264,
+	V265, V266, V267, V268, V269, V270, V271, V272, V273, V274, V275, V276,
+	V277, V278, V279, V280, V281, V282, V283, V284, V285, V286, V287, V288,
+	V289, V290, V291, V292, V293, V294, V295, V296, V297, V298, V299,
+}
+
+#[test]
+fn wide_discriminant() {
+	use WideDiscriminant::*;
+	assert_eq!(
+		<WideDiscriminant as paged::EncodeSized>::ENCODED_SIZE,
+		2 + <u32 as paged::EncodeSized>::ENCODED_SIZE
+	);
+	let entries = vec![V0(42), V1, V255, V256, V299];
+	assert_eq!(round_trip(&entries), entries);
+}
+
+#[derive(Paged, Debug, PartialEq)]
+#[non_exhaustive]
+enum Cause {
+	Stated(u32),
+	Entailed(u32),
+	#[paged(unknown)]
+	Unknown,
+}
+
+/// Future version of [`Cause`], with a new variant.
+#[derive(Paged, Debug, PartialEq)]
+enum NextCause {
+	Stated(u32),
+	Entailed(u32),
+	Unknown,
+	Inferred(u32),
+}
+
+#[test]
+fn non_exhaustive_enum() {
+	let entries = vec![
+		NextCause::Stated(1),
+		NextCause::Inferred(5),
+		NextCause::Entailed(2),
+	];
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let section =
+		Section::<Cause>::from_byte_offset(section.page_offset() * 64, section.entry_count(), 64);
+	assert_eq!(
+		reader.decode_all(section, &mut (), heap).unwrap(),
+		[Cause::Stated(1), Cause::Unknown, Cause::Entailed(2)]
+	);
+}
+
+#[derive(Paged, Debug, PartialEq)]
+struct Present;
+
+#[test]
+fn zero_sized_entries() {
+	assert_eq!(<Present as paged::EncodeSized>::ENCODED_SIZE, 0);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let entries = [Present, Present, Present, Present, Present];
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let next = encoder.section_from_iter(&mut heap, &entries[..2]).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	assert_ne!(section.page_offset(), next.page_offset());
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	assert!(reader
+		.get(section, &cache, &mut (), heap, 3)
+		.unwrap()
+		.is_some());
+	assert!(reader
+		.get(section, &cache, &mut (), heap, 5)
+		.unwrap()
+		.is_none());
+	assert_eq!(reader.iter(section, &cache, heap).count(), 5);
+	assert_eq!(reader.iter(next, &cache, heap).count(), 2);
+}
+
+/// Generic struct with its own where clause, and a generic parameter named
+/// like the context parameter of the derived impls.
+#[derive(Paged, Debug, PartialEq)]
+#[paged(
+	encode_sized_bounds(T: paged::EncodeSized, C: paged::EncodeSized),
+	encode_bounds(
+		T: paged::Encode<_C> + paged::EncodeOnHeap<_C>,
+		C: paged::Encode<_C> + paged::EncodeOnHeap<_C>
+	),
+	decode_bounds(
+		T: paged::Decode<_C> + paged::DecodeFromHeap<_C>,
+		C: paged::Decode<_C> + paged::DecodeFromHeap<_C>
+	)
+)]
+struct Wrapper<T, C>
+where
+	T: Clone,
+	C: Copy,
+{
+	inner: T,
+	context: C,
+}
+
+#[test]
+fn where_clause() {
+	let entries: Vec<Wrapper<u64, u32>> = (0..10)
+		.map(|i| Wrapper {
+			inner: i as u64 * 1000,
+			context: i,
+		})
+		.collect();
+
+	assert_eq!(round_trip(&entries), entries);
+}
+
+#[derive(Paged, Debug, PartialEq)]
+#[paged(heap, key = id)]
+struct Iri {
+	value: String,
+	id: u32,
+}
+
+#[test]
+fn binary_search() {
+	let entries: Vec<Iri> = (0..100)
+		.map(|i| Iri {
+			value: format!("https://example.org/{i}"),
+			id: i * 2,
+		})
+		.collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+
+	for id in [0, 42, 198] {
+		let found = reader
+			.binary_search(section, &cache, &mut (), heap, &id)
+			.unwrap()
+			.unwrap();
+		assert_eq!(*found, entries[id as usize / 2]);
+
+		let found = reader
+			.binary_search_by_key(section, &cache, &mut (), heap, |t, _| t.paged_cmp(&id))
+			.unwrap()
+			.unwrap();
+		assert_eq!(found.paged_key(), &id);
+	}
+
+	for id in [1, 43, 200] {
+		assert!(reader
+			.binary_search(section, &cache, &mut (), heap, &id)
+			.unwrap()
+			.is_none());
+	}
+}
+
+#[test]
+fn reported_heap_entries() {
+	let entries: Vec<Iri> = (0..10)
+		.map(|i| Iri {
+			value: format!("https://example.org/{i}"),
+			id: i,
+		})
+		.collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap_bytes = heap.as_bytes().to_vec();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let (iri, heap_entries) = reader
+		.get_with_heap_entries(section, &mut (), heap, 5)
+		.unwrap()
+		.unwrap();
+	assert_eq!(iri, entries[5]);
+
+	// The `value` string is the only heap data of an `Iri`.
+	let value = b"https://example.org/5";
+	let offset = heap_bytes
+		.windows(value.len())
+		.position(|w| w == value)
+		.unwrap();
+	assert_eq!(heap_entries.len(), 1);
+	assert_eq!(heap_entries[0].offset.unwrap() as usize, offset);
+	assert_eq!(heap_entries[0].len as usize, value.len());
+}
+
+#[derive(Paged)]
+struct Header {
+	interpretation: Interpretation,
+	data: HeapSection,
+}
+
+#[derive(Paged)]
+struct Interpretation {
+	iris: Section<Iri>,
+}
+
+#[test]
+fn navigate() {
+	let iris: Vec<Iri> = (0..100)
+		.map(|i| Iri {
+			value: format!("https://example.org/{i}"),
+			id: i,
+		})
+		.collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let interpretation = Interpretation {
+		iris: encoder.section_from_iter(&mut heap, &iris).unwrap(),
+	};
+	let data = encoder.add_heap(heap).unwrap();
+	let header = Header {
+		interpretation,
+		data,
+	};
+	let mut heap = Heap::new();
+	let root = encoder.section_from_iter(&mut heap, [&header]).unwrap();
+	let root_heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::default();
+	let header = reader
+		.get(root, &cache, &mut (), root_heap, 0)
+		.unwrap()
+		.unwrap();
+	let caches = Caches::new();
+	let navigator = reader.navigate(&caches, header.data);
+	let iris_reader = navigator.section(header.interpretation.iris);
+	assert_eq!(iris_reader.len(), 100);
+	for i in [5, 50, 99] {
+		let iri = iris_reader.get(&mut (), i).unwrap().unwrap();
+		assert_eq!(*iri, iris[i as usize]);
+	}
+	assert!(iris_reader.get(&mut (), 100).unwrap().is_none());
+
+	// The cache of the type is shared by every section reader.
+	assert_eq!(caches.get::<Iri>().len(), 3);
+}
+
+#[derive(Paged, Debug, PartialEq)]
+enum RecordType {
+	#[paged(tag = b'A')]
+	Address(u32),
+	#[paged(tag = b'B')]
+	Blank,
+}
+
+#[test]
+fn byte_tags() {
+	let entries = vec![
+		RecordType::Address(7),
+		RecordType::Blank,
+		RecordType::Address(u32::MAX),
+	];
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let bytes = encoder.end().into_inner();
+	assert_eq!(bytes[0], b'A');
+	assert_eq!(bytes[5], b'B');
+
+	let reader = Reader::new(Cursor::new(bytes), 64, 0);
+	assert_eq!(reader.decode_all(section, &mut (), heap).unwrap(), entries);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section::<RecordType>(&mut heap).unwrap();
+	section.push_raw(&[b'C', 0, 0, 0, 1]).unwrap();
+	let section = section.end().unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	let err = reader.get(section, &cache, &mut (), heap, 0).err().unwrap();
+	assert!(matches!(err, Error::IO(e) if e.kind() == io::ErrorKind::InvalidData));
+}
+
+/// User type named like a heap type of the library.
+#[derive(Paged, Debug, PartialEq)]
+struct Bounded {
+	min: u32,
+	max: u32,
+}
+
+#[derive(Paged, Debug, PartialEq)]
+struct Limits {
+	id: u32,
+	bounds: Bounded,
+}
+
+#[test]
+fn user_type_named_like_heap_type() {
+	let limits = Limits {
+		id: 1,
+		bounds: Bounded { min: 2, max: 3 },
+	};
+
+	// Inline types implement `Encode` and `Decode`.
+	let mut bytes = Ordered::<_, BigEndian>::new(Vec::new());
+	paged::Encode::encode(&limits, &(), &mut bytes).unwrap();
+	let decoded: Limits = paged::Decode::decode(
+		&mut Ordered::<_, BigEndian>::new(bytes.0.as_slice()),
+		&mut (),
+	)
+	.unwrap();
+	assert_eq!(decoded, limits);
+}
+
+#[derive(Paged, Debug, PartialEq)]
+struct Tagged {
+	id: u32,
+	tags: std::collections::BTreeSet<u32>,
+	name: Option<String>,
+}
+
+#[test]
+fn inferred_heap() {
+	let entries: Vec<Tagged> = (0..10)
+		.map(|i| Tagged {
+			id: i,
+			tags: (0..i).collect(),
+			name: (i % 2 == 0).then(|| format!("name {i}")),
+		})
+		.collect();
+
+	assert_eq!(round_trip(&entries), entries);
+}
+
+fn check_span(span: &Span) -> io::Result<()> {
+	if span.start <= span.end {
+		Ok(())
+	} else {
+		Err(io::Error::new(io::ErrorKind::InvalidData, "reversed span"))
+	}
+}
+
+#[derive(Paged, Debug, PartialEq)]
+#[paged(validate = check_span)]
+struct Span {
+	start: u32,
+	end: u32,
+}
+
+fn check_counted(counted: &Counted) -> io::Result<()> {
+	if counted.len as usize == counted.items.len() {
+		Ok(())
+	} else {
+		Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"length mismatch",
+		))
+	}
+}
+
+#[derive(Paged, Debug, PartialEq)]
+#[paged(heap, validate = "check_counted")]
+struct Counted {
+	len: u32,
+	items: Vec<u32>,
+}
+
+#[test]
+fn validated_sized_decode() {
+	let spans = vec![Span { start: 1, end: 4 }, Span { start: 7, end: 7 }];
+	assert_eq!(round_trip(&spans), spans);
+
+	let mut bytes = Ordered::<_, BigEndian>::new(Vec::new());
+	paged::Encode::encode(&Span { start: 5, end: 2 }, &(), &mut bytes).unwrap();
+	let err = <Span as paged::Decode<()>>::decode(
+		&mut Ordered::<_, BigEndian>::new(bytes.0.as_slice()),
+		&mut (),
+	)
+	.unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+	// Also checked when read from a section.
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder
+		.section_from_iter(&mut heap, &[Span { start: 5, end: 2 }])
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	let err = reader.get(section, &cache, &mut (), heap, 0).err().unwrap();
+	assert!(matches!(err, Error::IO(e) if e.kind() == io::ErrorKind::InvalidData));
+}
+
+#[test]
+fn validated_heap_decode() {
+	let entries: Vec<Counted> = (0..5)
+		.map(|i| Counted {
+			len: i,
+			items: (0..i).collect(),
+		})
+		.collect();
+	assert_eq!(round_trip(&entries), entries);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let corrupted = Counted {
+		len: 3,
+		items: vec![1, 2],
+	};
+	let section = encoder
+		.section_from_iter(&mut heap, std::slice::from_ref(&corrupted))
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	let err = reader.get(section, &cache, &mut (), heap, 0).err().unwrap();
+	assert!(matches!(err, Error::IO(e) if e.kind() == io::ErrorKind::InvalidData));
+}
@@ -0,0 +1,47 @@
+use std::io::{self, Cursor};
+
+use paged::{
+	reader::Cache,
+	utils::{BigEndian, Ordered},
+	Decode, DynamicSection, Encoder, Heap, Reader,
+};
+
+#[test]
+fn entry_size_larger_than_page() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_dynamic_section::<u32>(8, &mut heap).unwrap();
+	for i in 0..20u32 {
+		section.push(&(), &i).unwrap();
+	}
+	let valid = section.end().unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	// Corrupt header: page offset 0, 20 entries of 100 bytes.
+	let header: Vec<u8> = [0u32, 20, 100]
+		.iter()
+		.flat_map(|n| n.to_be_bytes())
+		.collect();
+	let section = DynamicSection::<u32>::decode(
+		&mut Ordered::<_, BigEndian>::new(header.as_slice()),
+		&mut (),
+	)
+	.unwrap();
+	let cache = Cache::default();
+
+	let entry = reader.get_dynamic(valid, &cache, &mut (), 3).unwrap();
+	assert_eq!(entry.map(|t| *t), Some(3));
+	let cache = Cache::default();
+
+	for result in [
+		reader.get_dynamic(section, &cache, &mut (), 3).map(|_| ()),
+		reader
+			.get_dynamic_page(section, &cache, &mut (), 0)
+			.map(|_| ()),
+	] {
+		match result {
+			Err(paged::reader::Error::IO(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+			_ => panic!("expected an invalid data error"),
+		}
+	}
+}
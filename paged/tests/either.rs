@@ -0,0 +1,47 @@
+use std::io::Cursor;
+
+use either::Either;
+use paged::{DecodeFromHeap, EncodeOnHeap, EncodeSized, Encoder, Heap, Reader};
+
+fn round_trip<T: EncodeOnHeap + DecodeFromHeap>(entries: &[T]) -> Vec<T> {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	reader.decode_all(section, &mut (), heap).unwrap()
+}
+
+#[test]
+fn inline_sides() {
+	type Value = Either<u8, (u64, u32)>;
+	assert_eq!(Value::ENCODED_SIZE, 13);
+
+	let entries: Vec<Value> = (0..20)
+		.map(|i| {
+			if i % 3 == 0 {
+				Either::Left(i as u8)
+			} else {
+				Either::Right((u64::MAX - i, i as u32))
+			}
+		})
+		.collect();
+	assert_eq!(round_trip(&entries), entries);
+}
+
+#[test]
+fn heap_sides() {
+	type Value = Either<String, u16>;
+	assert_eq!(Value::ENCODED_SIZE, 9);
+
+	let entries: Vec<Value> = (0..20)
+		.map(|i| {
+			if i % 2 == 0 {
+				Either::Left(format!("value {i}"))
+			} else {
+				Either::Right(i)
+			}
+		})
+		.collect();
+	assert_eq!(round_trip(&entries), entries);
+}
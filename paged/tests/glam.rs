@@ -0,0 +1,41 @@
+use glam::{Mat4, Vec3};
+use paged::{
+	utils::{BigEndian, Ordered},
+	Decode, Encode, EncodeSized,
+};
+
+/// Encodes the given value, checks its bytes and decodes it back.
+fn check<T: Encode + Decode<()> + EncodeSized + PartialEq + std::fmt::Debug>(
+	value: T,
+	components: &[f32],
+) {
+	let mut bytes = Ordered::<_, BigEndian>::new(Vec::new());
+	let len = value.encode(&(), &mut bytes).unwrap();
+	assert_eq!(len, T::ENCODED_SIZE);
+	assert_eq!(len as usize, components.len() * 4);
+
+	let expected: Vec<u8> = components.iter().flat_map(|c| c.to_be_bytes()).collect();
+	assert_eq!(bytes.0, expected);
+
+	let decoded = T::decode(
+		&mut Ordered::<_, BigEndian>::new(bytes.0.as_slice()),
+		&mut (),
+	)
+	.unwrap();
+	assert_eq!(decoded, value);
+}
+
+#[test]
+fn vec3() {
+	check(Vec3::new(1.0, -2.5, 3.25), &[1.0, -2.5, 3.25]);
+}
+
+#[test]
+fn mat4() {
+	let components: [f32; 16] = std::array::from_fn(|i| i as f32 * 1.5);
+	let mat = Mat4::from_cols_array(&components);
+	assert_eq!(mat.x_axis.y, 1.5);
+
+	// Components are encoded column by column.
+	check(mat, &components);
+}
@@ -0,0 +1,71 @@
+use std::io::{self, Cursor};
+
+use paged::{
+	reader::{AnyReader, Cache},
+	utils::{BigEndian, ByteOrder, LittleEndian},
+	Encoder, Heap, HeapSection, Reader, Section,
+};
+
+struct File {
+	bytes: Vec<u8>,
+	numbers: Section<u32>,
+	strings: Section<String>,
+	heap: HeapSection,
+}
+
+fn write<O: ByteOrder>() -> File {
+	let mut encoder = Encoder::<_, O>::with_header(Cursor::new(Vec::new()), 64).unwrap();
+	let mut heap = Heap::new();
+	let numbers: Vec<u32> = (0..100).map(|i| i * 1000).collect();
+	let strings: Vec<String> = (0..100).map(|i| format!("string {i}")).collect();
+	let numbers = encoder
+		.section_from_iter(&mut heap, numbers.iter())
+		.unwrap();
+	let strings = encoder
+		.section_from_iter(&mut heap, strings.iter())
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	File {
+		bytes: encoder.end().into_inner(),
+		numbers,
+		strings,
+		heap,
+	}
+}
+
+fn check<R: io::Seek + io::Read, O: ByteOrder>(reader: &Reader<R, O>, file: &File) {
+	let cache = Cache::default();
+	let entry = reader
+		.get(file.numbers, &cache, &mut (), file.heap, 42)
+		.unwrap();
+	assert_eq!(entry.map(|t| *t), Some(42000));
+
+	let cache = Cache::default();
+	let entry = reader
+		.get(file.strings, &cache, &mut (), file.heap, 42)
+		.unwrap();
+	assert_eq!(entry.as_deref().map(String::as_str), Some("string 42"));
+}
+
+#[test]
+fn detect_byte_order() {
+	let file = write::<LittleEndian>();
+	match Reader::open(Cursor::new(file.bytes.clone())).unwrap() {
+		AnyReader::LittleEndian(reader) => check(&reader, &file),
+		AnyReader::BigEndian(_) => panic!("expected a little-endian file"),
+	}
+
+	let file = write::<BigEndian>();
+	match Reader::open(Cursor::new(file.bytes.clone())).unwrap() {
+		AnyReader::BigEndian(reader) => check(&reader, &file),
+		AnyReader::LittleEndian(_) => panic!("expected a big-endian file"),
+	}
+}
+
+#[test]
+fn invalid_header() {
+	let mut bytes = write::<LittleEndian>().bytes;
+	bytes[0] = b'X';
+	let err = Reader::open(Cursor::new(bytes)).err().unwrap();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,175 @@
+use std::io::{self, Cursor};
+
+use paged::{
+	heap::{Entry, Offset},
+	reader::{self, Cache},
+	utils::{ByteOrder, Ordered, Output},
+	Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Encoder, Error, Heap, HeapSection,
+	Reader,
+};
+
+#[test]
+fn sized_records_by_offset() {
+	let records: Vec<(u32, u64)> = (0..30).map(|i| (i, u64::MAX - i as u64)).collect();
+
+	let mut heap = Heap::new();
+	let mut offsets = Vec::new();
+	for (i, record) in records.iter().enumerate() {
+		// Unrelated data between records.
+		heap.insert(&(), "x".repeat(i).as_str()).unwrap();
+		offsets.push(heap.insert_sized(&(), record).unwrap());
+	}
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let section = encoder
+		.section_from_iter(&mut heap, offsets.iter())
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::default();
+	for (i, record) in records.iter().enumerate() {
+		let offset: Offset = *reader
+			.get(section, &cache, &mut (), heap, i as u32)
+			.unwrap()
+			.unwrap();
+		assert_eq!(offset, offsets[i]);
+		let decoded: (u32, u64) = reader.decode_from_heap_at(&mut (), heap, offset).unwrap();
+		assert_eq!(&decoded, record);
+	}
+}
+
+/// Linked list stored in the heap, each node referencing the next.
+#[derive(Debug, PartialEq)]
+struct Node(Option<Box<Node>>);
+
+impl Node {
+	fn chain(len: usize) -> Self {
+		(0..len).fold(Node(None), |next, _| Node(Some(Box::new(next))))
+	}
+}
+
+impl EncodeSized for Node {
+	const ENCODED_SIZE: u32 = Entry::ENCODED_SIZE;
+}
+
+impl EncodeOnHeap for Node {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &(),
+		heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		let entry = match &self.0 {
+			Some(next) => {
+				let mut bytes = Ordered::like(output, Vec::new());
+				next.encode_on_heap(context, heap, &mut bytes)?;
+				heap.insert(context, bytes.0.as_slice())?.sized(0)
+			}
+			None => Entry::NONE,
+		};
+
+		entry.encode(context, output)
+	}
+}
+
+impl DecodeFromHeap for Node {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut (),
+		heap: HeapSection,
+	) -> io::Result<Self> {
+		let entry = Entry::decode(input, context)?;
+		if entry.is_none() {
+			Ok(Self(None))
+		} else {
+			let next = input.at_heap_offset(heap, entry.offset, |input| {
+				Self::decode_from_heap(input, context, heap)
+			})?;
+			Ok(Self(Some(Box::new(next))))
+		}
+	}
+}
+
+fn assert_too_deep(result: Result<impl std::fmt::Debug, Error>) {
+	assert!(matches!(result, Err(Error::IO(e)) if e.kind() == io::ErrorKind::InvalidData));
+}
+
+#[test]
+fn max_heap_depth() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let nodes = [Node::chain(3), Node::chain(5)];
+	let section = encoder.section_from_iter(&mut heap, &nodes).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let bytes = encoder.end().into_inner();
+
+	let reader = Reader::new(Cursor::new(bytes.clone()), 64, 0);
+	assert_eq!(reader.decode_all(section, &mut (), heap).unwrap(), nodes);
+
+	// Both nodes are on the same page, which fails to load.
+	let reader = Reader::new(Cursor::new(bytes), 64, 0).with_max_heap_depth(4);
+	let cache = Cache::default();
+	assert_too_deep(reader.get(section, &cache, &mut (), heap, 0).map(|_| ()));
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder
+		.section_from_iter(&mut heap, &[Node::chain(3)])
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0).with_max_heap_depth(3);
+	let cache = Cache::default();
+	assert_eq!(
+		*reader
+			.get(section, &cache, &mut (), heap, 0)
+			.unwrap()
+			.unwrap(),
+		Node::chain(3)
+	);
+}
+
+#[test]
+fn crafted_heap_cycle() {
+	// A heap entry referencing itself, as if infinitely nested.
+	let mut heap = Heap::new();
+	let offset = heap.insert(&(), &[0u8; 8][..]).unwrap();
+	assert_eq!(offset.unwrap(), 0);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut empty_heap = Heap::new();
+	let mut section = encoder.begin_section::<Node>(&mut empty_heap).unwrap();
+	section.push_raw(&[0u8; 8]).unwrap();
+	let section = section.end().unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	assert_too_deep(reader.get(section, &cache, &mut (), heap, 0).map(|_| ()));
+}
+
+#[test]
+fn inline_heaps() {
+	let first: Vec<String> = (0..20).map(|i| format!("first {i}")).collect();
+	let second: Vec<String> = (0..30).map(|i| "second".repeat(i)).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section::<String>(&mut heap).unwrap();
+	for s in &first {
+		section.push(&(), s).unwrap();
+	}
+	let (a, a_heap) = section.end_with_heap().unwrap();
+
+	let b = encoder.section_from_iter(&mut heap, &second).unwrap();
+	let b_heap = encoder.flush_heap(&mut heap).unwrap();
+
+	// Each heap lands right after its section.
+	assert_eq!(a_heap.page_offset, a.page_offset() + a.page_count(64));
+	assert_eq!(b.page_offset(), a_heap.page_offset + a_heap.page_count);
+	assert_eq!(b_heap.page_offset, b.page_offset() + b.page_count(64));
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	assert_eq!(reader.decode_all(a, &mut (), a_heap).unwrap(), first);
+	assert_eq!(reader.decode_all(b, &mut (), b_heap).unwrap(), second);
+}
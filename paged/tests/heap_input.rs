@@ -0,0 +1,89 @@
+use std::{
+	io,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	thread,
+};
+
+use paged::{reader::Cache, Encoder, Heap, Reader};
+
+/// In-memory input recording the end of the furthest read made through it.
+struct Input {
+	data: Arc<Vec<u8>>,
+	position: u64,
+	read_end: Arc<AtomicUsize>,
+}
+
+impl Input {
+	fn new(data: Arc<Vec<u8>>) -> (Self, Arc<AtomicUsize>) {
+		let read_end = Arc::new(AtomicUsize::new(0));
+		let input = Self {
+			data,
+			position: 0,
+			read_end: read_end.clone(),
+		};
+
+		(input, read_end)
+	}
+}
+
+impl io::Read for Input {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let start = (self.position as usize).min(self.data.len());
+		let len = buf.len().min(self.data.len() - start);
+		buf[..len].copy_from_slice(&self.data[start..(start + len)]);
+		self.position += len as u64;
+		self.read_end.fetch_max(start + len, Ordering::Relaxed);
+		Ok(len)
+	}
+}
+
+impl io::Seek for Input {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.position = match pos {
+			io::SeekFrom::Start(p) => p,
+			io::SeekFrom::Current(d) => self.position.checked_add_signed(d).unwrap(),
+			io::SeekFrom::End(d) => (self.data.len() as u64).checked_add_signed(d).unwrap(),
+		};
+
+		Ok(self.position)
+	}
+}
+
+#[test]
+fn concurrent_heap_reads() {
+	let values: Vec<String> = (0..1000).map(|i| format!("value number {i}")).collect();
+
+	let mut encoder = Encoder::new(io::Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let data = Arc::new(encoder.end().into_inner());
+
+	let (input, main_read_end) = Input::new(data.clone());
+	let (heap_input, heap_read_end) = Input::new(data);
+	let reader = Reader::new(input, 64, 0).with_heap_input(heap_input);
+	let cache = Cache::default();
+
+	thread::scope(|s| {
+		for t in 0..8u32 {
+			let reader = &reader;
+			let cache = &cache;
+			let values = &values;
+			s.spawn(move || {
+				for i in 0..values.len() as u32 {
+					let i = (i * 7 + t * 131) % values.len() as u32;
+					let entry = reader.get(section, cache, &mut (), heap, i).unwrap();
+					assert_eq!(*entry.unwrap(), values[i as usize]);
+				}
+			});
+		}
+	});
+
+	// Heap data is only ever read through the dedicated heap input.
+	let heap_start = (heap.page_offset * 64) as usize;
+	assert!(main_read_end.load(Ordering::Relaxed) <= heap_start);
+	assert!(heap_read_end.load(Ordering::Relaxed) > heap_start);
+}
@@ -0,0 +1,66 @@
+use std::io::{self, Cursor};
+
+use paged::{
+	utils::{BigEndian, InlineVec, Ordered},
+	Decode, Encode, EncodeSized, Encoder, Heap, Reader,
+};
+
+fn round_trip<T>(entries: &[T]) -> Vec<T>
+where
+	T: paged::EncodeOnHeap + paged::DecodeFromHeap,
+{
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	reader.decode_all(section, &mut (), heap).unwrap()
+}
+
+#[test]
+fn lengths() {
+	assert_eq!(InlineVec::<u32, 4>::ENCODED_SIZE, 17);
+
+	let entries: Vec<InlineVec<u32, 4>> = vec![
+		InlineVec(vec![]),
+		InlineVec(vec![1, 2]),
+		InlineVec(vec![1, 2, 3, 4]),
+	];
+	assert_eq!(round_trip(&entries), entries);
+
+	let mut bytes = Ordered::<_, BigEndian>::new(Vec::new());
+	for entry in &entries {
+		assert_eq!(entry.encode(&(), &mut bytes).unwrap(), 17);
+	}
+	let input = &mut Ordered::<_, BigEndian>::new(bytes.0.as_slice());
+	for entry in &entries {
+		assert_eq!(&InlineVec::<u32, 4>::decode(input, &mut ()).unwrap(), entry);
+	}
+}
+
+#[test]
+fn heap_elements() {
+	let entries: Vec<InlineVec<String, 2>> = vec![
+		InlineVec(vec![]),
+		InlineVec(vec!["a".to_string()]),
+		InlineVec(vec!["b".to_string(), "c".to_string()]),
+	];
+	assert_eq!(round_trip(&entries), entries);
+}
+
+#[test]
+fn over_capacity() {
+	let value: InlineVec<u32, 4> = InlineVec(vec![1, 2, 3, 4, 5]);
+	let e = value
+		.encode(&(), &mut Ordered::<_, BigEndian>::new(Vec::new()))
+		.unwrap_err();
+	assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+
+	// A length byte over the capacity.
+	let mut bytes = vec![5];
+	bytes.resize(InlineVec::<u32, 4>::ENCODED_SIZE as usize, 0);
+	let e =
+		InlineVec::<u32, 4>::decode(&mut Ordered::<_, BigEndian>::new(bytes.as_slice()), &mut ())
+			.unwrap_err();
+	assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
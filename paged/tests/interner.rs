@@ -0,0 +1,80 @@
+use std::{io::Cursor, sync::Arc};
+
+use paged::{
+	reader::Cache,
+	utils::{Interned, Interner},
+	Encoder, Heap, Reader,
+};
+
+fn decode_all<T>(entries: &[Interned<T>], interner: &mut Interner<T>) -> Vec<Interned<T>>
+where
+	Interned<T>: paged::EncodeOnHeap + paged::DecodeFromHeap<Interner<T>>,
+{
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	reader.decode_all(section, interner, heap).unwrap()
+}
+
+#[test]
+fn shared_heap_values() {
+	let entries: Vec<Interned<String>> = ["a", "b", "a", "c", "b", "a"]
+		.into_iter()
+		.map(|s| Interned(Arc::new(s.to_string())))
+		.collect();
+
+	let mut interner = Interner::new();
+	let decoded = decode_all(&entries, &mut interner);
+	assert_eq!(decoded, entries);
+	assert_eq!(interner.len(), 3);
+	assert!(Arc::ptr_eq(&decoded[0], &decoded[2]));
+	assert!(Arc::ptr_eq(&decoded[0], &decoded[5]));
+	assert!(!Arc::ptr_eq(&decoded[0], &decoded[1]));
+}
+
+#[test]
+fn keyed_by_encoding() {
+	// `0.0` and `-0.0` are equal, but encoded differently.
+	let entries: Vec<Interned<f64>> = [0.0, -0.0, 0.0, -0.0]
+		.into_iter()
+		.map(|f| Interned(Arc::new(f)))
+		.collect();
+
+	let mut interner = Interner::new();
+	let decoded = decode_all(&entries, &mut interner);
+	assert_eq!(interner.len(), 2);
+	assert!(decoded[0].is_sign_positive());
+	assert!(decoded[1].is_sign_negative());
+	assert!(Arc::ptr_eq(&decoded[0], &decoded[2]));
+	assert!(Arc::ptr_eq(&decoded[1], &decoded[3]));
+}
+
+/// IRIs, sharing their text once decoded.
+type Iri = Interned<String>;
+
+#[test]
+fn owned_iter_with_context() {
+	let iris: Vec<Iri> = (0..40)
+		.map(|i| Interned(Arc::new(format!("https://example.org/{}", i % 10))))
+		.collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &iris).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::default();
+	let mut interner = Interner::new();
+	let mut decoded = Vec::new();
+	for iri in reader.into_owned_iter(section, &cache, &mut interner, heap) {
+		let iri: Iri = iri.unwrap();
+		decoded.push(iri)
+	}
+
+	assert_eq!(decoded, iris);
+	assert_eq!(interner.len(), 10);
+	assert!(Arc::ptr_eq(&decoded[0], &decoded[10]));
+}
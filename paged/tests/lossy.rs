@@ -0,0 +1,34 @@
+use std::{io::Cursor, num::NonZeroU32};
+
+use paged::{reader::Cache, Encoder, Error, Heap, Reader};
+
+#[test]
+fn skip_corrupt_page() {
+	let values: Vec<NonZeroU32> = (1..=48).map(|i| NonZeroU32::new(i).unwrap()).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let mut bytes = encoder.end().into_inner();
+
+	// Zero the first entry of the second page.
+	bytes[64..68].fill(0);
+
+	let reader = Reader::new(Cursor::new(bytes), 64, 0);
+	let cache = Cache::default();
+	let mut entries = Vec::new();
+	let mut errors = Vec::new();
+	for item in reader.iter_lossy(section, &cache, heap) {
+		match item {
+			Ok(entry) => entries.push(*entry),
+			Err((e, page_index)) => errors.push((e, page_index)),
+		}
+	}
+
+	assert_eq!(entries, [&values[..16], &values[32..]].concat());
+	assert_eq!(errors.len(), 1);
+	let (e, page_index) = &errors[0];
+	assert_eq!(*page_index, 1);
+	assert!(matches!(e, Error::IO(e) if e.kind() == std::io::ErrorKind::InvalidData));
+}
@@ -0,0 +1,112 @@
+use std::io::Cursor;
+
+use paged::{
+	reader::{Cache, MissCache},
+	Encoder, Heap, Reader,
+};
+
+fn cmp(t: &u32, k: &u32, _: &()) -> std::cmp::Ordering {
+	t.cmp(k)
+}
+
+#[test]
+fn empty_section_and_next_section() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let empty = encoder
+		.section_from_iter(&mut heap, std::iter::empty::<&u32>())
+		.unwrap();
+	let values: Vec<u32> = (0..40).collect();
+	let next = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	assert_eq!(empty.page_offset(), next.page_offset());
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	let misses = MissCache::default();
+
+	let found = reader
+		.binary_search_cached(empty, &cache, &misses, &mut (), heap, &12, cmp)
+		.unwrap();
+	assert!(found.is_none());
+
+	let found = reader
+		.binary_search_cached(next, &cache, &misses, &mut (), heap, &12, cmp)
+		.unwrap();
+	assert_eq!(found.map(|t| *t), Some(12));
+}
+
+#[test]
+fn checkpoint_and_final_section() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section::<u32>(&mut heap).unwrap();
+	for i in 0..20 {
+		section.push(&(), &i).unwrap();
+	}
+	let checkpoint = section.checkpoint().unwrap();
+	for i in 20..40 {
+		section.push(&(), &i).unwrap();
+	}
+	let full = section.end().unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	assert_eq!(checkpoint.page_offset(), full.page_offset());
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	// Pages are cached by global index, and the last page of the checkpoint
+	// is shorter than in the final section: they cannot share a page cache.
+	let checkpoint_cache = Cache::default();
+	let cache = Cache::default();
+	let misses = MissCache::default();
+
+	let found = reader
+		.binary_search_cached(
+			checkpoint,
+			&checkpoint_cache,
+			&misses,
+			&mut (),
+			heap,
+			&30,
+			cmp,
+		)
+		.unwrap();
+	assert!(found.is_none());
+	assert!(misses.contains(checkpoint, &30));
+	assert!(!misses.contains(full, &30));
+
+	let found = reader
+		.binary_search_cached(full, &cache, &misses, &mut (), heap, &30, cmp)
+		.unwrap();
+	assert_eq!(found.map(|t| *t), Some(30));
+}
+
+#[test]
+fn repeated_miss_does_not_access_pages() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let values: Vec<u32> = (0..40).map(|i| i * 2).collect();
+	let section = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::with_access_log();
+	let misses = MissCache::default();
+
+	let found = reader
+		.binary_search_cached(section, &cache, &misses, &mut (), heap, &13, cmp)
+		.unwrap();
+	assert!(found.is_none());
+	assert!(misses.contains(section, &13));
+	let accesses = cache.access_log();
+	let loaded = cache.len();
+	assert!(!accesses.is_empty());
+
+	// The second search is answered by the miss cache alone: no page is
+	// accessed or loaded.
+	let found = reader
+		.binary_search_cached(section, &cache, &misses, &mut (), heap, &13, cmp)
+		.unwrap();
+	assert!(found.is_none());
+	assert_eq!(cache.access_log(), accesses);
+	assert_eq!(cache.len(), loaded);
+}
@@ -0,0 +1,101 @@
+use std::{
+	io::{self, Cursor},
+	net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use paged::{
+	utils::{BigEndian, Ordered},
+	Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Encoder, Heap, Reader,
+};
+
+fn round_trip<T: EncodeOnHeap + DecodeFromHeap>(entries: &[T]) -> Vec<T> {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	reader.decode_all(section, &mut (), heap).unwrap()
+}
+
+fn encode<T: Encode<()>>(value: &T) -> Vec<u8> {
+	let mut output = Ordered::<_, BigEndian>::new(Vec::new());
+	value.encode(&(), &mut output).unwrap();
+	output.0
+}
+
+fn addresses() -> Vec<IpAddr> {
+	vec![
+		Ipv4Addr::new(192, 168, 1, 1).into(),
+		Ipv6Addr::LOCALHOST.into(),
+		Ipv4Addr::UNSPECIFIED.into(),
+		"2001:db8::ff00:42:8329".parse().unwrap(),
+		Ipv4Addr::BROADCAST.into(),
+		Ipv6Addr::UNSPECIFIED.into(),
+	]
+}
+
+#[test]
+fn sizes() {
+	assert_eq!(Ipv4Addr::ENCODED_SIZE, 4);
+	assert_eq!(Ipv6Addr::ENCODED_SIZE, 16);
+	assert_eq!(IpAddr::ENCODED_SIZE, 17);
+	assert_eq!(SocketAddr::ENCODED_SIZE, 19);
+
+	for ip in addresses() {
+		assert_eq!(encode(&ip).len(), 17);
+		assert_eq!(encode(&SocketAddr::new(ip, 80)).len(), 19);
+	}
+}
+
+#[test]
+fn mixed_addresses() {
+	let ips = addresses();
+	assert_eq!(round_trip(&ips), ips);
+
+	let v4: Vec<Ipv4Addr> = ips
+		.iter()
+		.filter_map(|ip| match ip {
+			IpAddr::V4(a) => Some(*a),
+			IpAddr::V6(_) => None,
+		})
+		.collect();
+	assert_eq!(round_trip(&v4), v4);
+
+	let v6: Vec<Ipv6Addr> = ips
+		.iter()
+		.filter_map(|ip| match ip {
+			IpAddr::V4(_) => None,
+			IpAddr::V6(a) => Some(*a),
+		})
+		.collect();
+	assert_eq!(round_trip(&v6), v6);
+
+	let sockets: Vec<SocketAddr> = ips
+		.iter()
+		.zip([0, 80, 443, 8080, u16::MAX, 1])
+		.map(|(ip, port)| SocketAddr::new(*ip, port))
+		.collect();
+	assert_eq!(round_trip(&sockets), sockets);
+}
+
+#[test]
+fn network_byte_order() {
+	let socket = SocketAddr::new(Ipv4Addr::new(10, 0, 0, 1).into(), 0x1f90);
+	let mut expected = vec![0, 10, 0, 0, 1];
+	expected.extend([0; 12]);
+	expected.extend([0x1f, 0x90]);
+	assert_eq!(encode(&socket), expected);
+
+	let ip = IpAddr::from(Ipv6Addr::LOCALHOST);
+	let mut expected = vec![1];
+	expected.extend(Ipv6Addr::LOCALHOST.octets());
+	assert_eq!(encode(&ip), expected);
+}
+
+#[test]
+fn invalid_discriminant() {
+	let mut bytes = encode(&IpAddr::from(Ipv4Addr::LOCALHOST));
+	bytes[0] = 2;
+	let err = IpAddr::decode(&mut Ordered::<_, BigEndian>::new(&bytes[..]), &mut ()).unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,81 @@
+use std::io::Cursor;
+
+use ordered_float::OrderedFloat;
+use paged::{
+	utils::{BigEndian, Ordered},
+	Encode, Encoder, Heap, Reader,
+};
+
+fn values() -> Vec<OrderedFloat<f64>> {
+	[
+		f64::NEG_INFINITY,
+		-1e300,
+		-2.5,
+		-f64::MIN_POSITIVE,
+		-0.0,
+		0.0,
+		f64::MIN_POSITIVE,
+		1.0,
+		2.5,
+		1e300,
+		f64::INFINITY,
+		f64::NAN,
+	]
+	.into_iter()
+	.map(OrderedFloat)
+	.collect()
+}
+
+fn bytes_of<T: Encode>(value: &T) -> Vec<u8> {
+	let mut bytes = Ordered::<_, BigEndian>::new(Vec::new());
+	value.encode(&(), &mut bytes).unwrap();
+	bytes.0
+}
+
+#[test]
+fn round_trip() {
+	let values = values();
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &values).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let decoded = reader.decode_all(section, &mut (), heap).unwrap();
+	assert_eq!(decoded, values);
+	assert!(decoded[4].is_sign_negative());
+	assert!(decoded[5].is_sign_positive());
+}
+
+/// Checks that comparing the encoded bytes of the given values matches
+/// comparing the values.
+///
+/// `0.0` and `-0.0` are equal but encoded differently, so only strict
+/// inequalities are compared.
+fn check_order<T: Encode + Ord>(values: &[T]) {
+	let encoded: Vec<Vec<u8>> = values.iter().map(bytes_of).collect();
+	for (a, a_bytes) in values.iter().zip(&encoded) {
+		for (b, b_bytes) in values.iter().zip(&encoded) {
+			if a < b {
+				assert!(a_bytes < b_bytes)
+			}
+
+			if a_bytes < b_bytes {
+				assert!(a <= b)
+			}
+		}
+	}
+}
+
+#[test]
+fn byte_order_matches_sort_order() {
+	let values = values();
+	assert!(values.windows(2).all(|w| w[0] <= w[1]));
+	check_order(&values);
+
+	// Negative zero is encoded right before positive zero.
+	assert!(bytes_of(&values[4]) < bytes_of(&values[5]));
+
+	let values: Vec<OrderedFloat<f32>> = values.iter().map(|f| OrderedFloat(f.0 as f32)).collect();
+	check_order(&values);
+}
@@ -0,0 +1,32 @@
+use std::io::{self, Cursor};
+
+use paged::{Encoder, Heap};
+
+fn assert_too_large(e: io::Error) {
+	assert_eq!(e.kind(), io::ErrorKind::Other);
+	assert_eq!(e.to_string(), "file too large");
+}
+
+#[test]
+fn section_past_last_page() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder
+		.begin_section_at::<u32>(&mut heap, u32::MAX)
+		.unwrap();
+	assert_too_large(section.push(&(), &0).unwrap_err());
+}
+
+#[test]
+fn heap_past_last_page() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	heap.insert(&(), "value").unwrap();
+	let mut empty_heap = Heap::new();
+	encoder
+		.begin_section_at::<u32>(&mut empty_heap, u32::MAX)
+		.unwrap()
+		.end()
+		.unwrap();
+	assert_too_large(encoder.add_heap(heap).unwrap_err());
+}
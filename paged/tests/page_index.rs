@@ -0,0 +1,62 @@
+use std::{collections::HashSet, io::Cursor};
+
+use paged::{reader::Cache, Encoder, Heap, Reader};
+
+fn pages<T>(cache: &Cache<T>) -> HashSet<u32> {
+	cache.access_log().into_iter().collect()
+}
+
+#[test]
+fn indexed_search_decodes_fewer_pages() {
+	// 8 entries per page, 128 pages.
+	let entries: Vec<u64> = (0..1024).map(|i| i * 2).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let (section, index) = encoder
+		.indexed_section_from_iter(&mut heap, entries.iter(), |t| *t)
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	// The index is shared by all searches.
+	let index_cache = Cache::with_access_log();
+	let mut plain_decodes = 0;
+	let mut indexed_decodes = 0;
+
+	for searched in (0..2048).step_by(67) {
+		let plain_cache = Cache::with_access_log();
+		let plain = reader
+			.binary_search_by_key(section, &plain_cache, &mut (), heap, |t, _| {
+				t.cmp(&searched)
+			})
+			.unwrap()
+			.map(|t| *t);
+
+		let cache = Cache::with_access_log();
+		let indexed = reader
+			.binary_search_indexed(
+				section,
+				&cache,
+				index,
+				&index_cache,
+				&mut (),
+				heap,
+				|t| *t,
+				|k, _| k.cmp(&searched),
+			)
+			.unwrap()
+			.map(|t| *t);
+
+		assert_eq!(indexed, plain);
+		assert_eq!(indexed.is_some(), searched % 2 == 0);
+		assert_eq!(pages(&cache).len(), 1);
+
+		plain_decodes += pages(&plain_cache).len();
+		indexed_decodes += 1;
+	}
+
+	let index_decodes = pages(&index_cache).len();
+	assert!(index_decodes as u32 <= index.page_count(64));
+	assert!(indexed_decodes + index_decodes < plain_decodes / 2);
+}
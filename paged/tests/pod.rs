@@ -0,0 +1,110 @@
+use std::io::{self, Cursor};
+
+use paged::{
+	reader::Cache,
+	utils::{self, ByteOrder, Input, NativeEndian, Output},
+	Decode, Encode, EncodeOnHeap, EncodeSized, Encoder, Heap, Reader,
+};
+
+/// Byte order other than the native one.
+#[cfg(target_endian = "little")]
+type ForeignEndian = utils::BigEndian;
+
+/// Byte order other than the native one.
+#[cfg(target_endian = "big")]
+type ForeignEndian = utils::LittleEndian;
+
+fn compare_pages<O: ByteOrder>() {
+	let entries: Vec<[u32; 4]> = (0..50).map(|i| [i, i << 8, i << 16, i << 24]).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64).with_byte_order::<O>();
+	let mut heap = Heap::new();
+	let section = encoder
+		.section_from_iter(&mut heap, entries.iter())
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0).with_byte_order::<O>();
+
+	let pod_cache = Cache::default();
+	let cache = Cache::default();
+	for page_index in 0..section.page_count(64) {
+		let pod_page = reader
+			.get_page_pod(section, &pod_cache, page_index)
+			.unwrap();
+		let page = reader
+			.get_page(section, &cache, &mut (), heap, page_index)
+			.unwrap();
+		assert_eq!(pod_page.as_slice(), page.as_slice());
+	}
+}
+
+#[test]
+fn native_byte_order() {
+	compare_pages::<NativeEndian>()
+}
+
+#[test]
+fn foreign_byte_order() {
+	compare_pages::<ForeignEndian>()
+}
+
+#[test]
+fn push_pod_requires_native_byte_order() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64).with_byte_order::<ForeignEndian>();
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section::<u32>(&mut heap).unwrap();
+	let err = section.push_pod(&1).unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+/// Number whose encoding does not match its memory layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+struct Swapped(u32);
+
+unsafe impl bytemuck::Zeroable for Swapped {}
+unsafe impl bytemuck::Pod for Swapped {}
+
+impl EncodeSized for Swapped {
+	const ENCODED_SIZE: u32 = 4;
+}
+
+impl<C> Encode<C> for Swapped {
+	fn encode(&self, context: &C, output: &mut impl Output) -> io::Result<u32> {
+		self.0.swap_bytes().encode(context, output)
+	}
+}
+
+impl<C> EncodeOnHeap<C> for Swapped {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		Encode::encode(self, context, output)
+	}
+}
+
+impl<C> Decode<C> for Swapped {
+	fn decode<R: Input>(input: &mut R, context: &mut C) -> io::Result<Self> {
+		u32::decode(input, context).map(|n| Self(n.swap_bytes()))
+	}
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "memory layout")]
+fn mismatched_layout() {
+	let entries: Vec<Swapped> = (1..10).map(Swapped).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64).with_byte_order::<NativeEndian>();
+	let mut heap = Heap::new();
+	let section = encoder
+		.section_from_iter(&mut heap, entries.iter())
+		.unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0)
+		.with_byte_order::<NativeEndian>();
+
+	let _ = reader.get_page_pod(section, &Cache::default(), 0);
+}
@@ -0,0 +1,84 @@
+#![cfg(unix)]
+
+use std::{
+	fs::File,
+	io::{self, Cursor},
+	sync::Barrier,
+	thread,
+};
+
+use paged::{
+	reader::{self, Cache, PositionedReader},
+	utils::{ByteOrder, Output},
+	Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Encoder, Heap, HeapSection,
+};
+
+/// Number waiting on the decoding context before being decoded.
+///
+/// Pages of such numbers are only decoded once every thread started decoding
+/// its own page, which would never happen if decoding was serialized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Synced(u32);
+
+impl EncodeSized for Synced {
+	const ENCODED_SIZE: u32 = u32::ENCODED_SIZE;
+}
+
+impl<C> EncodeOnHeap<C> for Synced {
+	fn encode_on_heap<W: Output>(
+		&self,
+		context: &C,
+		_heap: &mut Heap<W::ByteOrder>,
+		output: &mut W,
+	) -> io::Result<u32> {
+		self.0.encode(context, output)
+	}
+}
+
+impl DecodeFromHeap<&Barrier> for Synced {
+	fn decode_from_heap<R: io::Seek + io::Read, O: ByteOrder>(
+		input: &mut reader::Cursor<R, O>,
+		context: &mut &Barrier,
+		_heap: HeapSection,
+	) -> io::Result<Self> {
+		let n = u32::decode(input, context)?;
+		if n % 16 == 0 {
+			context.wait();
+		}
+
+		Ok(Self(n))
+	}
+}
+
+#[test]
+fn concurrent_pages() {
+	const THREADS: u32 = 4;
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let entries: Vec<Synced> = (0..(THREADS * 16)).map(Synced).collect();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+
+	let path = std::env::temp_dir().join(format!("paged-positioned-{}", std::process::id()));
+	std::fs::write(&path, encoder.end().into_inner()).unwrap();
+	let reader = PositionedReader::new(File::open(&path).unwrap(), 64, 0);
+	std::fs::remove_file(&path).unwrap();
+
+	let cache = Cache::default();
+	let barrier = Barrier::new(THREADS as usize);
+	thread::scope(|s| {
+		for page_index in 0..THREADS {
+			let (reader, cache, barrier) = (&reader, &cache, &barrier);
+			s.spawn(move || {
+				let page = reader
+					.get_page(section, cache, &mut &*barrier, heap, page_index)
+					.unwrap();
+				let expected: Vec<Synced> = (page_index * 16..(page_index + 1) * 16)
+					.map(Synced)
+					.collect();
+				assert_eq!(page.as_slice(), expected.as_slice());
+			});
+		}
+	});
+}
@@ -0,0 +1,384 @@
+use std::io::{self, Cursor};
+
+use paged::{reader::Cache, Encoder, Error, Heap, HeapSection, Reader, Section};
+
+/// Writes the numbers `0..len`, 16 per page.
+fn numbers(len: u32) -> (Reader<Cursor<Vec<u8>>>, Section<u32>, HeapSection) {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder
+		.section_from_iter(&mut heap, &(0..len).collect::<Vec<_>>())
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	(reader, section, heap)
+}
+
+#[test]
+fn count_where() {
+	let (reader, section, heap) = numbers(100);
+	let cache = Cache::default();
+
+	let count = reader
+		.count_where(section, &cache, &mut (), heap, |n, _| n % 2 == 0)
+		.unwrap();
+	let expected = reader
+		.iter(section, &cache, heap)
+		.filter(|n| **n.as_ref().unwrap() % 2 == 0)
+		.count();
+	assert_eq!(count, expected as u64);
+	assert_eq!(count, 50);
+}
+
+#[test]
+fn warm_from_log() {
+	let (reader, section, heap) = numbers(100);
+
+	let cache = Cache::with_access_log();
+	for i in [0, 40, 90, 41] {
+		reader.get(section, &cache, &mut (), heap, i).unwrap();
+	}
+	let log = cache.access_log();
+
+	let warm = Cache::default();
+	reader
+		.warm_from_log(section, &warm, &mut (), heap, &log)
+		.unwrap();
+	assert_eq!(warm.len(), 3);
+	for page in [0, 2, 5] {
+		assert!(warm.contains(section.page_offset() + page));
+	}
+}
+
+#[test]
+fn page_chunks() {
+	// The last page only holds 8 entries.
+	let (reader, section, heap) = numbers(40);
+	let cache = Cache::default();
+
+	let chunks: Vec<Vec<u32>> = reader
+		.page_chunks(section, &cache, heap)
+		.map(|chunk| Iterator::map(chunk.unwrap(), |n| *n).collect())
+		.collect();
+	assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), [16, 16, 8]);
+
+	let flat: Vec<u32> = reader
+		.iter(section, &cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	assert_eq!(chunks.concat(), flat);
+}
+
+#[test]
+fn map_iter() {
+	let triples: Vec<(u32, u32, u64)> = (0..50)
+		.map(|i| (i, i * 2, u64::MAX / 2 + i as u64))
+		.collect();
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &triples).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::default();
+	let sums: Vec<u64> = reader
+		.map_iter(section, &cache, &mut (), heap, |(a, b, c)| {
+			*a as u64 + *b as u64 + c
+		})
+		.collect::<Result<_, _>>()
+		.unwrap();
+	let expected: Vec<u64> = triples
+		.iter()
+		.map(|(a, b, c)| *a as u64 + *b as u64 + c)
+		.collect();
+	assert_eq!(sums, expected);
+}
+
+#[test]
+fn tombstones() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder
+		.section_from_iter(&mut heap, &(0..100u32).collect::<Vec<_>>())
+		.unwrap();
+	let tombstones = encoder
+		.tombstones_from_iter(&mut heap, 100, [2, 4])
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::default();
+	let tombstones_cache = Cache::default();
+	for i in 0..100 {
+		let deleted = i == 2 || i == 4;
+		assert_eq!(
+			reader
+				.is_deleted(tombstones, &tombstones_cache, heap, i)
+				.unwrap(),
+			deleted
+		);
+		let entry = reader
+			.get_live(
+				section,
+				&cache,
+				tombstones,
+				&tombstones_cache,
+				&mut (),
+				heap,
+				i,
+			)
+			.unwrap();
+		assert_eq!(entry.map(|n| *n), (!deleted).then_some(i));
+	}
+
+	let live: Vec<u32> = reader
+		.iter_live(section, &cache, tombstones, &tombstones_cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	let expected: Vec<u32> = (0..100).filter(|i| *i != 2 && *i != 4).collect();
+	assert_eq!(live, expected);
+}
+
+#[test]
+fn get_slice() {
+	let (reader, section, heap) = numbers(40);
+	let cache = Cache::default();
+
+	let slice = reader
+		.get_slice(section, &cache, &mut (), heap, 3..6)
+		.unwrap()
+		.unwrap();
+	assert_eq!(&*slice, &[3, 4, 5]);
+
+	let slice = reader
+		.get_slice(section, &cache, &mut (), heap, 32..40)
+		.unwrap()
+		.unwrap();
+	assert_eq!(&*slice, &(32..40).collect::<Vec<_>>()[..]);
+
+	// Entries 15 and 16 are on different pages.
+	assert!(reader
+		.get_slice(section, &cache, &mut (), heap, 14..18)
+		.unwrap()
+		.is_none());
+	assert!(reader
+		.get_slice(section, &cache, &mut (), heap, 38..41)
+		.unwrap()
+		.is_none());
+}
+
+#[test]
+fn export() {
+	let (reader, section, heap) = numbers(40);
+	let cache = Cache::default();
+
+	let mut output = Vec::new();
+	reader
+		.export(section, &cache, &mut (), heap, &mut output, |n, out| {
+			writeln!(out, "{n}")
+		})
+		.unwrap();
+
+	let expected: String = (0..40).map(|n| format!("{n}\n")).collect();
+	assert_eq!(output, expected.into_bytes());
+}
+
+#[test]
+fn fold() {
+	let values: Vec<u64> = (0..50).map(|i| i * 1_000_000_007).collect();
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &values).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::default();
+	let sum = reader
+		.fold(section, &cache, &mut (), heap, 0u64, |sum, n| sum + n)
+		.unwrap();
+	let mut expected = 0;
+	for n in &values {
+		expected += n
+	}
+	assert_eq!(sum, expected);
+
+	let max = reader
+		.fold(section, &cache, &mut (), heap, 0u64, |max, n| max.max(*n))
+		.unwrap();
+	assert_eq!(max, 49 * 1_000_000_007);
+}
+
+#[test]
+fn chain_iter() {
+	let shards: [Vec<u32>; 4] = [
+		(0..20).collect(),
+		vec![],
+		(20..23).collect(),
+		(23..60).collect(),
+	];
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let sections: Vec<Section<u32>> = shards
+		.iter()
+		.map(|shard| encoder.section_from_iter(&mut heap, shard).unwrap())
+		.collect();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::default();
+	let chained: Vec<u32> = reader
+		.chain_iter(&sections, &cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	assert_eq!(chained.len(), 60);
+	assert_eq!(chained, (0..60).collect::<Vec<_>>());
+}
+
+#[test]
+fn iter_permuted() {
+	let values: Vec<u32> = (0..100).map(|i| (i * 37) % 100).collect();
+	let mut sorted: Vec<u32> = (0..100).collect();
+	sorted.sort_by_key(|&i| values[i as usize]);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &values).unwrap();
+	let permutation = encoder.section_from_iter(&mut heap, &sorted).unwrap();
+	let invalid = encoder.section_from_iter(&mut heap, &[3, 100]).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let cache = Cache::default();
+	let permutation_cache = Cache::default();
+	let permuted: Vec<u32> = reader
+		.iter_permuted(section, &cache, permutation, &permutation_cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	assert_eq!(permuted, (0..100).collect::<Vec<_>>());
+
+	let mut permuted = reader.iter_permuted(section, &cache, invalid, &permutation_cache, heap);
+	assert_eq!(*permuted.next().unwrap().unwrap(), values[3]);
+	assert!(matches!(
+		permuted.next(),
+		Some(Err(Error::IO(e))) if e.kind() == io::ErrorKind::InvalidData
+	));
+}
+
+#[test]
+fn tail() {
+	// 7 pages, the last one holding 4 entries.
+	let (reader, section, heap) = numbers(100);
+	let last_page = section.page_offset() + 6;
+
+	let cache = Cache::with_access_log();
+	let tail: Vec<u32> = reader
+		.tail(section, &cache, &mut (), heap, 3)
+		.unwrap()
+		.into_iter()
+		.map(|n| *n)
+		.collect();
+	assert_eq!(tail, [97, 98, 99]);
+	assert_eq!(cache.len(), 1);
+	assert_eq!(cache.access_log(), [last_page]);
+
+	let cache = Cache::with_access_log();
+	let tail: Vec<u32> = reader
+		.tail(section, &cache, &mut (), heap, 6)
+		.unwrap()
+		.into_iter()
+		.map(|n| *n)
+		.collect();
+	assert_eq!(tail, [94, 95, 96, 97, 98, 99]);
+	assert_eq!(cache.len(), 2);
+	assert_eq!(cache.access_log(), [last_page - 1, last_page]);
+
+	let cache = Cache::default();
+	let tail = reader.tail(section, &cache, &mut (), heap, 1000).unwrap();
+	assert_eq!(tail.len(), 100);
+}
+
+#[test]
+fn decode_all() {
+	let entries: Vec<(u32, String)> = vec![
+		(1, "one".to_string()),
+		(2, "two".to_string()),
+		(3, "three".to_string()),
+	];
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let decoded = reader.decode_all(section, &mut (), heap).unwrap();
+	let cache = Cache::default();
+	let cached: Vec<(u32, String)> = reader
+		.iter(section, &cache, heap)
+		.map(|t| (*t.unwrap()).clone())
+		.collect();
+	assert_eq!(decoded, cached);
+	assert_eq!(decoded, entries);
+
+	let (reader, section, heap) = numbers(100);
+	let cache = Cache::default();
+	let cached: Vec<u32> = reader
+		.iter(section, &cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	assert_eq!(reader.decode_all(section, &mut (), heap).unwrap(), cached);
+}
+
+#[test]
+fn section_at_byte_offset() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	encoder
+		.section_from_iter(&mut heap, &(0..40u32).collect::<Vec<_>>())
+		.unwrap();
+	let section = encoder
+		.section_from_iter(&mut heap, &(100..150u32).collect::<Vec<_>>())
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	assert_eq!(section.page_offset(), 3);
+
+	// Pages start after a custom 16 bytes header.
+	let mut bytes = vec![0xff; 16];
+	bytes.extend(encoder.end().into_inner());
+	let reader = Reader::new(Cursor::new(bytes), 64, 16);
+
+	let byte_offset = 16 + 3 * 64;
+	let from_offset = reader
+		.section_at_byte_offset::<u32>(byte_offset, section.entry_count())
+		.unwrap();
+	assert_eq!(from_offset.page_offset(), section.page_offset());
+	assert_eq!(from_offset.entry_count(), section.entry_count());
+	let relative = Section::<u32>::from_byte_offset(3 * 64, section.entry_count(), 64);
+	assert_eq!(relative.page_offset(), section.page_offset());
+
+	let cache = Cache::default();
+	let expected: Vec<u32> = reader
+		.iter(section, &cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	let cache = Cache::default();
+	let decoded: Vec<u32> = reader
+		.iter(from_offset, &cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	assert_eq!(decoded, expected);
+	assert_eq!(decoded, (100..150).collect::<Vec<_>>());
+
+	// Aligned on the page length, but not relative to the header.
+	for byte_offset in [3 * 64, byte_offset + 4, 8] {
+		let err = reader
+			.section_at_byte_offset::<u32>(byte_offset, section.entry_count())
+			.unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+	}
+}
+
+#[test]
+#[should_panic]
+fn unaligned_byte_offset() {
+	Section::<u32>::from_byte_offset(100, 10, 64);
+}
@@ -0,0 +1,45 @@
+use std::io::{self, Cursor};
+
+use paged::{
+	utils::{BigEndian, Ordered},
+	Decode, EncodeSized, Encoder, Heap, Reader,
+};
+use rust_decimal::Decimal;
+
+#[test]
+fn decimals() {
+	assert_eq!(Decimal::ENCODED_SIZE, 16);
+
+	let decimals = vec![
+		Decimal::new(123, 2),
+		Decimal::new(-1, 3),
+		Decimal::from_i128_with_scale(-79_228_162_514_264_337_593_543_950_335, 28),
+		Decimal::from_i128_with_scale(1, 28),
+		Decimal::ZERO,
+		Decimal::MAX,
+	];
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &decimals).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let decoded: Vec<Decimal> = reader.decode_all(section, &mut (), heap).unwrap();
+
+	// Exact equality, including the scale.
+	assert_eq!(decoded, decimals);
+	for (a, b) in decoded.iter().zip(&decimals) {
+		assert_eq!(a.scale(), b.scale());
+		assert_eq!(a.serialize(), b.serialize());
+	}
+	assert_eq!(decoded[0].to_string(), "1.23");
+	assert_eq!(decoded[1].to_string(), "-0.001");
+}
+
+#[test]
+fn invalid_scale() {
+	let mut bytes = Decimal::new(123, 2).serialize();
+	bytes[2] = 29;
+	let err = Decimal::decode(&mut Ordered::<_, BigEndian>::new(&bytes[..]), &mut ()).unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,204 @@
+use std::{
+	cell::RefCell,
+	io::{self, Cursor},
+	rc::Rc,
+};
+
+use paged::{reader::Cache, EncodeSized, Encoder, Heap, HeapSection, Reader, Section};
+
+#[test]
+fn copy_raw_entries() {
+	let values: Vec<u32> = (0..40).map(|i| i * 1000).collect();
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let source = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let source_bytes = encoder.end().into_inner();
+
+	// Entries are copied page by page, without decoding them.
+	let entries_per_page = Section::<u32>::entries_per_page(64);
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section::<u32>(&mut heap).unwrap();
+	for i in 0..source.entry_count() {
+		let start =
+			(source.offset_of_page(64, i / entries_per_page) + i % entries_per_page * 4) as usize;
+		section.push_raw(&source_bytes[start..(start + 4)]).unwrap();
+	}
+	let copy = section.end().unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	for (i, value) in values.iter().enumerate() {
+		let entry = reader.get(copy, &cache, &mut (), heap, i as u32).unwrap();
+		assert_eq!(entry.as_deref(), Some(value));
+	}
+}
+
+#[test]
+#[should_panic]
+fn raw_entry_length_mismatch() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section::<u32>(&mut heap).unwrap();
+	let _ = section.push_raw(&[0, 1, 2]);
+}
+
+#[test]
+fn sections_at_explicit_pages() {
+	let first: Vec<u32> = (0..20).collect();
+	let second: Vec<u32> = (100..110).collect();
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section_at::<u32>(&mut heap, 8).unwrap();
+	for n in &second {
+		section.push(&(), n).unwrap();
+	}
+	let b = section.end().unwrap();
+
+	// Fill the gap before the second section.
+	let mut section = encoder.begin_section_at::<u32>(&mut heap, 2).unwrap();
+	for n in &first {
+		section.push(&(), n).unwrap();
+	}
+	let a = section.end().unwrap();
+	assert_eq!(a.page_offset(), 2);
+	assert_eq!(b.page_offset(), 8);
+
+	// Pages 2 and 3 are taken by the first section.
+	let err = encoder.begin_section_at::<u32>(&mut heap, 3).err().unwrap();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	let decoded: Vec<u32> = reader.iter(a, &cache, heap).map(|n| *n.unwrap()).collect();
+	assert_eq!(decoded, first);
+	let cache = Cache::default();
+	let decoded: Vec<u32> = reader.iter(b, &cache, heap).map(|n| *n.unwrap()).collect();
+	assert_eq!(decoded, second);
+}
+
+/// Output shared with the test, to read the file while it is written.
+#[derive(Clone, Default)]
+struct SharedOutput(Rc<RefCell<Cursor<Vec<u8>>>>);
+
+impl SharedOutput {
+	fn snapshot(&self) -> Vec<u8> {
+		self.0.borrow().get_ref().clone()
+	}
+}
+
+impl io::Write for SharedOutput {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.borrow_mut().write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.0.borrow_mut().flush()
+	}
+}
+
+impl io::Seek for SharedOutput {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.0.borrow_mut().seek(pos)
+	}
+}
+
+#[test]
+fn checkpoint() {
+	let output = SharedOutput::default();
+	let mut encoder = Encoder::new(output.clone(), 64);
+	let mut heap = Heap::new();
+	let mut section = encoder.begin_section::<u32>(&mut heap).unwrap();
+	for i in 0..20 {
+		section.push(&(), &i).unwrap();
+	}
+	let checkpoint = section.checkpoint().unwrap();
+	assert_eq!(checkpoint.entry_count(), 20);
+
+	// The checkpoint is readable while the encoding continues.
+	let reader = Reader::new(Cursor::new(output.snapshot()), 64, 0);
+	let cache = Cache::default();
+	// The heap is not written yet, but `u32` entries do not use it.
+	let no_heap = HeapSection {
+		page_offset: 0,
+		page_count: 0,
+	};
+	let decoded: Vec<u32> = reader
+		.iter(checkpoint, &cache, no_heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	assert_eq!(decoded, (0..20).collect::<Vec<_>>());
+
+	for i in 20..50 {
+		section.push(&(), &i).unwrap();
+	}
+	let full = section.end().unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	encoder.end();
+
+	let reader = Reader::new(Cursor::new(output.snapshot()), 64, 0);
+	let cache = Cache::default();
+	let decoded: Vec<u32> = reader
+		.iter(full, &cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	assert_eq!(decoded, (0..50).collect::<Vec<_>>());
+}
+
+#[test]
+fn dropped_section_encoder() {
+	let values: Vec<u64> = (0..30).map(|i| u64::MAX - i).collect();
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+
+	// Abandoned in the middle of its second page.
+	let mut section = encoder.begin_section::<u32>(&mut heap).unwrap();
+	for i in 0..20 {
+		section.push(&(), &i).unwrap();
+	}
+	drop(section);
+
+	let section = encoder.section_from_iter(&mut heap, &values).unwrap();
+	assert_eq!(section.page_offset(), 2);
+	let heap = encoder.add_heap(heap).unwrap();
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let cache = Cache::default();
+	let decoded: Vec<u64> = reader
+		.iter(section, &cache, heap)
+		.map(|n| *n.unwrap())
+		.collect();
+	assert_eq!(decoded, values);
+}
+
+#[test]
+fn entry_larger_than_page() {
+	type Large = [u64; 25];
+	assert_eq!(Large::ENCODED_SIZE, 200);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 128);
+	let mut heap = Heap::new();
+	let err = encoder.begin_section::<Large>(&mut heap).err().unwrap();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+	let err = encoder
+		.begin_section_at::<Large>(&mut heap, 4)
+		.err()
+		.unwrap();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+	let err = encoder
+		.begin_dynamic_section::<Large>(200, &mut heap)
+		.err()
+		.unwrap();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+	let err = encoder
+		.section_from_iter(&mut heap, &[[0u64; 25]])
+		.unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+	// Nothing was written, the encoder can still be used.
+	let section = encoder.section_from_iter(&mut heap, &[1u64, 2]).unwrap();
+	assert_eq!(section.page_offset(), 0);
+}
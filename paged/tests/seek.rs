@@ -0,0 +1,87 @@
+use std::{
+	io::{self, Cursor},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+use paged::{reader::Cache, Encoder, Heap, Reader};
+
+/// Input counting the seeks performed on it.
+struct CountingSeek<R> {
+	input: R,
+	seeks: Arc<AtomicUsize>,
+}
+
+impl<R: io::Read> io::Read for CountingSeek<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.input.read(buf)
+	}
+}
+
+impl<R: io::Seek> io::Seek for CountingSeek<R> {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.seeks.fetch_add(1, Ordering::Relaxed);
+		self.input.seek(pos)
+	}
+}
+
+#[test]
+fn sequential_pages_seek_once() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let values: Vec<u32> = (0..100).collect();
+	let section = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+
+	// The input is not at the first page offset initially.
+	let mut input = Cursor::new(encoder.end().into_inner());
+	input.set_position(5);
+	let seeks = Arc::new(AtomicUsize::new(0));
+	let input = CountingSeek {
+		input,
+		seeks: seeks.clone(),
+	};
+	let reader = Reader::new(input, 64, 0);
+
+	let cache = Cache::default();
+	let read: Vec<u32> = reader
+		.iter(section, &cache, heap)
+		.map(|t| *t.unwrap())
+		.collect();
+	assert_eq!(read, values);
+
+	// Pages are contiguous: only the first one requires a seek.
+	assert_eq!(section.page_count(64), 7);
+	assert_eq!(seeks.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn heap_input_keeps_pages_sequential() {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let values: Vec<String> = (0..100).map(|i| format!("value {i}")).collect();
+	let section = encoder.section_from_iter(&mut heap, values.iter()).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let bytes = encoder.end().into_inner();
+
+	let seeks = Arc::new(AtomicUsize::new(0));
+	let input = CountingSeek {
+		input: Cursor::new(bytes.clone()),
+		seeks: seeks.clone(),
+	};
+	let heap_input = CountingSeek {
+		input: Cursor::new(bytes),
+		seeks: Arc::new(AtomicUsize::new(0)),
+	};
+	let reader = Reader::new(input, 64, 0).with_heap_input(heap_input);
+
+	let cache = Cache::default();
+	let read: Vec<String> = reader
+		.iter(section, &cache, heap)
+		.map(|t| String::clone(&t.unwrap()))
+		.collect();
+	assert_eq!(read, values);
+	assert_eq!(seeks.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,60 @@
+use std::io::{self, Cursor};
+
+use arrayvec::ArrayVec;
+use paged::{reader::Cache, DecodeFromHeap, EncodeOnHeap, Encoder, Error, Heap, Reader, Section};
+use smallvec::SmallVec;
+
+fn round_trip<T: EncodeOnHeap + DecodeFromHeap>(entries: &[T]) -> Vec<T> {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	reader.decode_all(section, &mut (), heap).unwrap()
+}
+
+#[test]
+fn smallvec() {
+	let entries: Vec<SmallVec<[u32; 4]>> = vec![
+		SmallVec::new(),
+		(0..4).collect(),
+		(0..6).collect(),
+		(0..100).collect(),
+	];
+	let decoded = round_trip(&entries);
+	assert_eq!(decoded, entries);
+	assert!(!decoded[1].spilled());
+	assert!(decoded[2].spilled());
+}
+
+#[test]
+fn arrayvec() {
+	let entries: Vec<ArrayVec<u32, 8>> = vec![ArrayVec::new(), (0..3).collect(), (0..8).collect()];
+	assert_eq!(round_trip(&entries), entries);
+
+	// Vectors are encoded the same way, whatever their capacity.
+	let vecs: Vec<Vec<u32>> = vec![(0..8).collect(), (0..9).collect()];
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &vecs).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let section = Section::<ArrayVec<u32, 8>>::from_byte_offset(
+		section.page_offset() * 64,
+		section.entry_count(),
+		64,
+	);
+	let cache = Cache::default();
+	let err = reader.get(section, &cache, &mut (), heap, 0).err().unwrap();
+	assert!(matches!(err, Error::IO(e) if e.kind() == io::ErrorKind::InvalidData));
+
+	// The first vector alone fits.
+	let section = Section::<ArrayVec<u32, 8>>::from_byte_offset(0, 1, 64);
+	let cache = Cache::default();
+	let decoded = reader
+		.get(section, &cache, &mut (), heap, 0)
+		.unwrap()
+		.unwrap();
+	assert_eq!(decoded.as_slice(), vecs[0].as_slice());
+}
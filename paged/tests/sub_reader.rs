@@ -0,0 +1,75 @@
+use std::io::Cursor;
+
+use paged::{reader::Cache, Encoder, Heap, HeapSection, Reader, Section};
+
+struct InnerFile {
+	bytes: Vec<u8>,
+	numbers: Section<u32>,
+	names: Section<String>,
+	heap: HeapSection,
+}
+
+/// Encodes a paged file with a 4-byte header and 32-byte pages.
+fn inner_file(numbers: &[u32], names: &[String]) -> InnerFile {
+	let mut output = Cursor::new(vec![0xff; 4]);
+	output.set_position(4);
+
+	let mut encoder = Encoder::new(output, 32);
+	let mut heap = Heap::new();
+	let numbers = encoder.section_from_iter(&mut heap, numbers).unwrap();
+	let names = encoder.section_from_iter(&mut heap, names).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	InnerFile {
+		bytes: encoder.end().into_inner(),
+		numbers,
+		names,
+		heap,
+	}
+}
+
+#[test]
+fn embedded_file() {
+	let numbers: Vec<u32> = (0..20).collect();
+	let names: Vec<String> = (0..5).map(|i| format!("name {i}")).collect();
+	let inner = inner_file(&numbers, &names);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let outer: Vec<u64> = (0..30).collect();
+	let outer_section = encoder.section_from_iter(&mut heap, outer.iter()).unwrap();
+	heap.insert(&(), "padding").unwrap();
+	let entry = heap
+		.insert(&(), inner.bytes.as_slice())
+		.unwrap()
+		.sized(inner.bytes.len() as u32);
+	let heap = encoder.add_heap(heap).unwrap();
+
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	let sub_reader = reader.sub_reader(heap, entry, 32, 4);
+
+	let cache = Cache::default();
+	let decoded: Vec<u32> = sub_reader
+		.iter(inner.numbers, &cache, inner.heap)
+		.map(|t| *t.unwrap())
+		.collect();
+	assert_eq!(decoded, numbers);
+	assert_eq!(
+		sub_reader
+			.get(inner.numbers, &cache, &mut (), inner.heap, 13)
+			.unwrap()
+			.map(|t| *t),
+		Some(13)
+	);
+	assert_eq!(
+		sub_reader
+			.decode_all(inner.names, &mut (), inner.heap)
+			.unwrap(),
+		names
+	);
+
+	// The outer file is still readable.
+	assert_eq!(
+		reader.decode_all(outer_section, &mut (), heap).unwrap(),
+		outer
+	);
+}
@@ -0,0 +1,152 @@
+use std::{
+	io::{self, Cursor},
+	path::PathBuf,
+	time::{Duration, Instant},
+};
+
+use paged::{
+	heap::Entry,
+	reader::Cache,
+	utils::{BigEndian, Checked, OptionVec, Ordered, PortablePath, RelInstant, Zigzag},
+	Decode, DecodeFromHeap, Encode, EncodeOnHeap, EncodeSized, Encoder, Error, Heap, Reader,
+};
+
+fn round_trip_with<C, T: EncodeOnHeap<C> + DecodeFromHeap<C>>(
+	context: &mut C,
+	entries: &[T],
+) -> Vec<T> {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder
+		.section_from_iter_with(&mut heap, &*context, entries)
+		.unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+	reader.decode_all(section, context, heap).unwrap()
+}
+
+#[test]
+fn relative_instants() {
+	let mut epoch = Instant::now();
+	let instants: Vec<RelInstant> = [0, 1, 1_500, 3_000_000_007]
+		.into_iter()
+		.map(|nanos| RelInstant(epoch + Duration::from_nanos(nanos)))
+		.collect();
+	assert_eq!(round_trip_with(&mut epoch, &instants), instants);
+
+	// Instants before the epoch cannot be encoded.
+	let later = epoch + Duration::from_secs(1);
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let err = encoder
+		.section_from_iter_with(&mut heap, &later, &instants)
+		.unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn option_vecs() {
+	assert_eq!(OptionVec::<u32>::ENCODED_SIZE, Entry::ENCODED_SIZE);
+
+	let vecs = vec![
+		OptionVec(None),
+		OptionVec(Some(vec![])),
+		OptionVec(Some(vec![1u32, 2, 3])),
+		OptionVec(None),
+	];
+	assert_eq!(round_trip_with(&mut (), &vecs), vecs);
+}
+
+#[test]
+fn checked_entries() {
+	assert_eq!(Checked::<(u32, u64)>::ENCODED_SIZE, 16);
+
+	let entries: Vec<Checked<(u32, u64)>> = (0..10)
+		.map(|i| Checked::new((i, u64::MAX - i as u64)))
+		.collect();
+	assert_eq!(round_trip_with(&mut (), &entries), entries);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let section = encoder.section_from_iter(&mut heap, &entries).unwrap();
+	let heap = encoder.add_heap(heap).unwrap();
+	let mut bytes = encoder.end().into_inner();
+
+	// Corrupt the second entry.
+	bytes[16 + 5] ^= 1;
+	let reader = Reader::new(Cursor::new(bytes), 64, 0);
+	let cache = Cache::default();
+	let err = reader.get(section, &cache, &mut (), heap, 1).err().unwrap();
+	assert!(matches!(err, Error::IO(e) if e.kind() == io::ErrorKind::InvalidData));
+}
+
+/// Encodes and decodes the given value, returning the decoded value and the
+/// encoded length.
+fn encode_decode<T: Encode + Decode<()>>(value: &T) -> (T, u32) {
+	let mut bytes = Ordered::<_, BigEndian>::new(Vec::new());
+	let len = value.encode(&(), &mut bytes).unwrap();
+	assert_eq!(len as usize, bytes.0.len());
+	let mut input = Ordered::<_, BigEndian>::new(bytes.0.as_slice());
+	let decoded = T::decode(&mut input, &mut ()).unwrap();
+	assert!(input.0.is_empty());
+	(decoded, len)
+}
+
+#[test]
+fn zigzag_boundaries() {
+	for n in [i16::MIN, i16::MIN + 1, -1, 0, 1, i16::MAX - 1, i16::MAX] {
+		assert_eq!(encode_decode(&Zigzag(n)).0, Zigzag(n));
+	}
+
+	for n in [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX] {
+		assert_eq!(encode_decode(&Zigzag(n)).0, Zigzag(n));
+	}
+
+	for n in [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX - 1, i64::MAX] {
+		assert_eq!(encode_decode(&Zigzag(n)).0, Zigzag(n));
+	}
+
+	// Small magnitudes are encoded on a single byte.
+	for n in [-64i64, -1, 0, 1, 63] {
+		assert_eq!(encode_decode(&Zigzag(n)).1, 1);
+	}
+	assert_eq!(encode_decode(&Zigzag(64i64)).1, 2);
+	assert_eq!(encode_decode(&Zigzag(i64::MIN)).1, 10);
+	assert_eq!(encode_decode(&Zigzag(i64::MAX)).1, 10);
+
+	// As heap data.
+	let deltas = vec![
+		vec![Zigzag(i64::MIN), Zigzag(0), Zigzag(i64::MAX)],
+		vec![],
+		vec![Zigzag(-3), Zigzag(5)],
+	];
+	assert_eq!(round_trip_with(&mut (), &deltas), deltas);
+}
+
+#[test]
+fn portable_paths() {
+	let paths: Vec<PortablePath> = ["/usr/share/doc", "relative/../path", "file.txt", "/", ""]
+		.into_iter()
+		.map(|p| PortablePath(PathBuf::from(p)))
+		.collect();
+	assert_eq!(round_trip_with(&mut (), &paths), paths);
+
+	// Current directory components are dropped.
+	let path = PortablePath(PathBuf::from("./a/./b/."));
+	assert_eq!(
+		round_trip_with(&mut (), &[path]),
+		[PortablePath(PathBuf::from("a/b"))]
+	);
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_portable_path() {
+	use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+	let path = PortablePath(PathBuf::from(OsStr::from_bytes(b"dir/invalid\xff")));
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let err = encoder.section_from_iter(&mut heap, &[path]).unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,70 @@
+use std::io::Cursor;
+
+use paged::{heap::VarEntry, utils::VarLen, Encoder, Heap, Reader, Section};
+
+#[test]
+fn compact_round_trip() {
+	let values: Vec<VarLen<String>> = (0..100).map(|i| VarLen(format!("value {i}"))).collect();
+
+	// Every other value is dead, and dropped by the compaction.
+	let dead = VarLen(String::from("dead"));
+	let mut heap = Heap::new();
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let section = encoder
+		.section_from_iter(&mut heap, values.iter().flat_map(|v| [v, &dead]))
+		.unwrap();
+	let mut compacted = Heap::new();
+	compacted.insert(&(), heap.as_bytes()).unwrap();
+	let heap_section = encoder.add_heap(heap).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let mut live_entries = Vec::new();
+	for i in 0..values.len() as u32 {
+		let (value, entries) = reader
+			.get_with_heap_entries(section, &mut (), heap_section, i * 2)
+			.unwrap()
+			.unwrap();
+		assert_eq!(value, values[i as usize]);
+		assert_eq!(entries.len(), 1);
+		live_entries.extend(entries);
+	}
+
+	let len = compacted.len();
+	let remap = compacted.compact(&live_entries).unwrap();
+	assert!(compacted.len() < len);
+
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 64);
+	let mut heap = Heap::new();
+	let remapped: Vec<_> = live_entries
+		.iter()
+		.map(|e| VarEntry(remap[&e.offset]))
+		.collect();
+	encoder
+		.section_from_iter(&mut heap, remapped.iter())
+		.unwrap();
+	let heap_section = encoder.add_heap(compacted).unwrap();
+	let reader = Reader::new(Cursor::new(encoder.end().into_inner()), 64, 0);
+
+	let section = Section::<VarLen<String>>::from_byte_offset(0, values.len() as u32, 64);
+	let decoded = reader.decode_all(section, &mut (), heap_section).unwrap();
+	assert_eq!(decoded, values);
+}
+
+fn file_len<T: paged::EncodeSized + paged::EncodeOnHeap>(values: &[T]) -> usize {
+	let mut encoder = Encoder::new(Cursor::new(Vec::new()), 4096);
+	let mut heap = Heap::new();
+	encoder.section_from_iter(&mut heap, values).unwrap();
+	encoder.add_heap(heap).unwrap();
+	encoder.end().into_inner().len()
+}
+
+#[test]
+fn smaller_file() {
+	let strings: Vec<String> = (0..5000).map(|i| format!("{i}")).collect();
+	let var_len_strings: Vec<VarLen<String>> = strings.iter().cloned().map(VarLen).collect();
+
+	// 18890 bytes of string data. Entries take 10 pages with `Entry`, and 5
+	// pages with `VarEntry` plus a one byte length header per string.
+	assert_eq!(file_len(&strings), 10 * 4096 + 18890);
+	assert_eq!(file_len(&var_len_strings), 5 * 4096 + 18890 + 5000);
+}